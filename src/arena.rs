@@ -0,0 +1,416 @@
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use game_constants::*;
+use vector::Vector2f;
+
+pub const ARENA_FILE_EXTENSION : &str = "arena";
+
+// obstacles an arena can hold, each one taking OBSTACLE_VALUE_COUNT values
+// of PhysicsParams::to_array
+pub const MAX_ARENA_OBSTACLES : usize = 8;
+pub const OBSTACLE_VALUE_COUNT : usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ObstacleShape {
+    Circle { centre : Vector2f, radius : f32 },
+    Segment { start : Vector2f, end : Vector2f },
+}
+
+use self::ObstacleShape::*;
+
+// Static collider the ball bounces on, like a floating bumper or a slanted
+// roof. The restitution is the share of the speed towards the obstacle
+// the ball keeps, bouncing off it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obstacle {
+    pub shape : ObstacleShape,
+    pub restitution : f32,
+}
+
+impl Obstacle {
+    // Point of the obstacle closest to the given one
+    pub fn get_closest_point(&self, point : Vector2f) -> Vector2f {
+        match self.shape {
+            Circle { centre, radius } => {
+                let offset = point - centre;
+                let length = offset.length();
+
+                if length > 0.0f32 {
+                    centre + offset * (radius / length)
+                }
+                else {
+                    centre
+                }
+            },
+            Segment { start, end } => {
+                let direction = end - start;
+                let ratio =
+                    (point - start).dot_product(&direction) / direction.dot_product(&direction);
+
+                if ratio <= 0.0f32 {
+                    start
+                }
+                else if ratio >= 1.0f32 {
+                    end
+                }
+                else {
+                    start + direction * ratio
+                }
+            },
+        }
+    }
+
+    // Unit vector from the obstacle to a point on its surface, used when the
+    // ball centre is right on it
+    pub fn get_fallback_normal(&self) -> Vector2f {
+        match self.shape {
+            Circle { .. } => Vector2f::new(0.0f32, -1.0f32),
+            Segment { start, end } => {
+                let direction = (end - start).normalized();
+                Vector2f::new(direction.y, -direction.x)
+            },
+        }
+    }
+
+    // the obstacle on the other side of a vertical axis
+    fn mirrored(&self, mirror : &dyn Fn(f32) -> f32) -> Obstacle {
+        let mirror_point = |point : Vector2f| Vector2f::new(mirror(point.x), point.y);
+
+        Obstacle {
+            shape : match self.shape {
+                Circle { centre, radius } => Circle { centre : mirror_point(centre), radius : radius },
+                Segment { start, end } => Segment { start : mirror_point(start), end : mirror_point(end) },
+            },
+            ..*self
+        }
+    }
+
+    // The values of the obstacle in PhysicsParams::to_array: 1 for a circle
+    // followed by its centre and radius, or 2 for a segment followed by its
+    // ends, then the restitution. Empty slots are all 0
+    pub fn to_values(obstacle : &Option<Obstacle>) -> [f32; OBSTACLE_VALUE_COUNT] {
+        match *obstacle {
+            None => [0.0f32; OBSTACLE_VALUE_COUNT],
+            Some(Obstacle { shape : Circle { centre, radius }, restitution }) =>
+                [1.0f32, centre.x, centre.y, radius, 0.0f32, restitution],
+            Some(Obstacle { shape : Segment { start, end }, restitution }) =>
+                [2.0f32, start.x, start.y, end.x, end.y, restitution],
+        }
+    }
+
+    pub fn from_values(values : &[f32]) -> Option<Obstacle> {
+        let shape =
+            if values[0] == 1.0f32 {
+                Circle { centre : Vector2f::new(values[1], values[2]), radius : values[3] }
+            }
+            else if values[0] == 2.0f32 {
+                Segment { start : Vector2f::new(values[1], values[2]), end : Vector2f::new(values[3], values[4]) }
+            }
+            else {
+                return None;
+            };
+
+        Some(Obstacle {
+            shape : shape,
+            restitution : values[5],
+        })
+    }
+
+    fn validate(&self) -> Result<(), ArenaFileError> {
+        let values = Obstacle::to_values(&Some(*self));
+
+        if !values.iter().all(|value| value.is_finite()) {
+            return Err(ArenaFileError::Invalid("values must be finite"));
+        }
+
+        if self.restitution < 0.0f32 {
+            return Err(ArenaFileError::Invalid("restitutions cannot be negative"));
+        }
+
+        match self.shape {
+            Circle { radius, .. } if radius <= 0.0f32 =>
+                Err(ArenaFileError::Invalid("circles must have a radius")),
+            Segment { start, end } if start == end =>
+                Err(ArenaFileError::Invalid("segments must have a length")),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Geometry of the field, part of PhysicsParams. The default is the classic
+// field, the others come from arena files like the ones of the arenas
+// folder. Coordinates are the ones of the simulation, y going down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arena {
+    // x of the walls
+    pub left_wall : f32,
+    pub right_wall : f32,
+
+    // x of the middle of the net, y of the centre of the sphere on its top
+    // and its half thickness
+    pub net_position_x : f32,
+    pub net_sphere_position : f32,
+    pub net_radius : f32,
+
+    // y of the ground the ball bounces on
+    pub ground_height : f32,
+    // y of the ceiling, None for an open field
+    pub ceiling : Option<f32>,
+
+    // x where the blobs start, and where the ball waits for the serve above
+    // them, at ball_spawn_y
+    pub left_spawn_x : f32,
+    pub right_spawn_x : f32,
+    pub ball_spawn_y : f32,
+
+    // extra colliders of the ball, the first free slot taking the next one
+    pub obstacles : [Option<Obstacle>; MAX_ARENA_OBSTACLES],
+}
+
+impl Default for Arena {
+    fn default() -> Arena {
+        Arena {
+            left_wall : LEFT_PLANE,
+            right_wall : RIGHT_PLANE,
+
+            net_position_x : NET_POSITION_X,
+            net_sphere_position : NET_SPHERE_POSITION,
+            net_radius : NET_RADIUS,
+
+            ground_height : GROUND_PLANE_HEIGHT_MAX,
+            ceiling : None,
+
+            left_spawn_x : LEFT_SPAWN_POS_X as f32,
+            right_spawn_x : RIGHT_SPAWN_POS_X as f32,
+            ball_spawn_y : STANDARD_BALL_HEIGHT,
+
+            obstacles : [None; MAX_ARENA_OBSTACLES],
+        }
+    }
+}
+
+impl Arena {
+    pub fn get_width(&self) -> f32 {
+        self.right_wall - self.left_wall
+    }
+
+    // y of the centre of a blob standing on the ground
+    pub fn get_blob_ground_height(&self) -> f32 {
+        self.ground_height - BLOBBY_HEIGHT / 2.0
+    }
+
+    pub fn get_obstacles<'a>(&'a self) -> impl Iterator<Item = &'a Obstacle> + 'a {
+        self.obstacles.iter().filter_map(|obstacle| obstacle.as_ref())
+    }
+
+    // Puts the obstacle in the first free slot, false if there is none left
+    pub fn add_obstacle(&mut self, obstacle : Obstacle) -> bool {
+        match self.obstacles.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(obstacle);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // where the ball starts when nobody serves
+    pub fn get_middle_spawn(&self) -> Vector2f {
+        Vector2f::new(self.net_position_x, self.ground_height - BALL_MIDDLE_SPAWN_HEIGHT)
+    }
+
+    // The same arena seen from the other side, as the bots see it when they
+    // play on the right
+    pub fn mirrored(&self) -> Arena {
+        let mirror = |x : f32| self.left_wall + self.right_wall - x;
+
+        let mut obstacles = self.obstacles;
+        for obstacle in obstacles.iter_mut() {
+            *obstacle = obstacle.map(|obstacle| obstacle.mirrored(&mirror));
+        }
+
+        Arena {
+            net_position_x : mirror(self.net_position_x),
+            left_spawn_x : mirror(self.right_spawn_x),
+            right_spawn_x : mirror(self.left_spawn_x),
+            obstacles : obstacles,
+            ..*self
+        }
+    }
+
+    // Checks that the blobs and the ball fit on both sides of the net
+    pub fn validate(&self) -> Result<(), ArenaFileError> {
+        let values = [
+            self.left_wall,
+            self.right_wall,
+            self.net_position_x,
+            self.net_sphere_position,
+            self.net_radius,
+            self.ground_height,
+            self.ceiling.unwrap_or(0.0f32),
+            self.left_spawn_x,
+            self.right_spawn_x,
+            self.ball_spawn_y,
+        ];
+
+        if !values.iter().all(|value| value.is_finite()) {
+            return Err(ArenaFileError::Invalid("values must be finite"));
+        }
+
+        if self.net_radius <= 0.0f32 {
+            return Err(ArenaFileError::Invalid("the net must have a thickness"));
+        }
+
+        if
+            self.left_wall >= self.left_spawn_x ||
+            self.left_spawn_x >= self.net_position_x - self.net_radius - BLOBBY_LOWER_RADIUS ||
+            self.net_position_x + self.net_radius + BLOBBY_LOWER_RADIUS >= self.right_spawn_x ||
+            self.right_spawn_x >= self.right_wall
+        {
+            return Err(ArenaFileError::Invalid("walls, spawn points and net must be in this order"));
+        }
+
+        if self.net_sphere_position >= self.ground_height {
+            return Err(ArenaFileError::Invalid("the net must stand above the ground"));
+        }
+
+        if self.ball_spawn_y + BALL_RADIUS >= self.get_blob_ground_height() - BLOBBY_UPPER_SPHERE {
+            return Err(ArenaFileError::Invalid("the ball must spawn above the blobs"));
+        }
+
+        if let Some(ceiling) = self.ceiling {
+            if ceiling + BALL_RADIUS >= self.ball_spawn_y {
+                return Err(ArenaFileError::Invalid("the ceiling must be above the ball"));
+            }
+        }
+
+        for obstacle in self.get_obstacles() {
+            obstacle.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ArenaFileError {
+    Io(io::Error),
+    Syntax { line : usize },
+    UnknownField { line : usize, name : String },
+    Invalid(&'static str),
+}
+
+impl fmt::Display for ArenaFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArenaFileError::Io(error) =>
+                write!(f, "arena file i/o error: {}", error),
+            ArenaFileError::Syntax { line } =>
+                write!(f, "arena file line {}: expected `name = value`", line),
+            ArenaFileError::UnknownField { line, name } =>
+                write!(f, "arena file line {}: unknown field `{}`", line, name),
+            ArenaFileError::Invalid(reason) =>
+                write!(f, "invalid arena: {}", reason),
+        }
+    }
+}
+
+impl error::Error for ArenaFileError {}
+
+impl From<io::Error> for ArenaFileError {
+    fn from(error : io::Error) -> ArenaFileError {
+        ArenaFileError::Io(error)
+    }
+}
+
+// Reads an arena file: one `name = value` line per field of Arena, fields
+// left out keeping their classic value, `ceiling = none` for no ceiling and
+// `#` starting a comment. Each `circle = x, y, radius, restitution` or
+// `segment = x1, y1, x2, y2, restitution` line adds an obstacle
+pub fn parse_arena(text : &str) -> Result<Arena, ArenaFileError> {
+    let mut arena = Arena::default();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let content = line.split('#').next().unwrap_or("").trim();
+
+        if content.is_empty() {
+            continue;
+        }
+
+        let mut parts = content.splitn(2, '=');
+        let name = parts.next().unwrap_or("").trim();
+        let value = match parts.next() {
+            Some(value) => value.trim(),
+            None => return Err(ArenaFileError::Syntax { line : line_number }),
+        };
+
+        if name == "ceiling" && value == "none" {
+            arena.ceiling = None;
+            continue;
+        }
+
+        if name == "circle" || name == "segment" {
+            let values : Vec<f32> =
+                match value.split(',').map(|value| value.trim().parse()).collect() {
+                    Ok(values) => values,
+                    Err(_) => return Err(ArenaFileError::Syntax { line : line_number }),
+                };
+
+            let obstacle =
+                match (name, values.len()) {
+                    ("circle", 4) => Obstacle {
+                        shape : Circle { centre : Vector2f::new(values[0], values[1]), radius : values[2] },
+                        restitution : values[3],
+                    },
+                    ("segment", 5) => Obstacle {
+                        shape : Segment {
+                            start : Vector2f::new(values[0], values[1]),
+                            end : Vector2f::new(values[2], values[3]),
+                        },
+                        restitution : values[4],
+                    },
+                    _ => return Err(ArenaFileError::Syntax { line : line_number }),
+                };
+
+            if !arena.add_obstacle(obstacle) {
+                return Err(ArenaFileError::Invalid("too many obstacles"));
+            }
+            continue;
+        }
+
+        let value : f32 = match value.parse() {
+            Ok(value) => value,
+            Err(_) => return Err(ArenaFileError::Syntax { line : line_number }),
+        };
+
+        match name {
+            "left_wall" => arena.left_wall = value,
+            "right_wall" => arena.right_wall = value,
+            "net_position_x" => arena.net_position_x = value,
+            "net_sphere_position" => arena.net_sphere_position = value,
+            "net_radius" => arena.net_radius = value,
+            "ground_height" => arena.ground_height = value,
+            "ceiling" => arena.ceiling = Some(value),
+            "left_spawn_x" => arena.left_spawn_x = value,
+            "right_spawn_x" => arena.right_spawn_x = value,
+            "ball_spawn_y" => arena.ball_spawn_y = value,
+            _ => return Err(ArenaFileError::UnknownField {
+                line : line_number,
+                name : name.to_string(),
+            }),
+        }
+    }
+
+    arena.validate()?;
+
+    Ok(arena)
+}
+
+pub fn load_arena(path : &Path) -> Result<Arena, ArenaFileError> {
+    parse_arena(&fs::read_to_string(path)?)
+}
@@ -0,0 +1,489 @@
+use global::PlayerSide;
+use global::PlayerSide::*;
+use game_constants::*;
+use physics_params::PhysicsParams;
+use environment::WindState;
+use arena::{Arena, Obstacle, ObstacleShape};
+use character::Character;
+use vector::Vector2f;
+use float_math;
+use state_hash::StateHasher;
+
+// One ball of a PhysicWorld with its own collisions. The classic game has a
+// single ball, the multi-ball mode several, see MatchRules::ball_count.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ball {
+    pub position : Vector2f,
+    pub velocity : Vector2f,
+
+    pub rotation : f32,
+    pub angular_velocity : f32,
+    // spin of the spin model, positive when the ball rolls to the right
+    pub spin : f32,
+
+    // false once the ball made a side lose a point: it then goes through
+    // the blobs and bounces on the ground until the next rally
+    pub is_valid : bool,
+    // relative speed of the ball and the blob at the last blob hit
+    pub last_hit_intensity : f32,
+
+    // collisions of the ball during the last step
+    pub hit_by_blobs : [bool; 2],
+    pub hit_wall : bool,
+    pub hit_net_side : bool,
+    pub hit_net_sphere : bool,
+}
+
+impl Ball {
+    pub fn new() -> Ball {
+        Ball {
+            position : Vector2f::new(0.0f32, 0.0f32),
+            velocity : Vector2f::new(0.0f32, 0.0f32),
+
+            rotation : 0.0f32,
+            angular_velocity : 0.0f32,
+            spin : 0.0f32,
+
+            is_valid : false,
+            last_hit_intensity : 0.0f32,
+
+            hit_by_blobs : [false; 2],
+            hit_wall : false,
+            hit_net_side : false,
+            hit_net_sphere : false,
+        }
+    }
+
+    // Puts the ball back at rest for a new rally
+    pub fn reset(&mut self, position : Vector2f, params : &PhysicsParams) {
+        self.position = position;
+        self.velocity.clear();
+
+        self.rotation = 0.0f32;
+        self.angular_velocity = params.standard_ball_angular_velocity;
+        self.spin = 0.0f32;
+
+        self.is_valid = true;
+        self.last_hit_intensity = 0.0;
+    }
+
+    pub fn add_to_hash(&self, hasher : &mut StateHasher) {
+        hasher.write_vector(&self.position);
+        hasher.write_vector(&self.velocity);
+        hasher.write_f32(self.rotation);
+        hasher.write_f32(self.angular_velocity);
+        hasher.write_f32(self.spin);
+
+        hasher.write_bool(self.is_valid);
+        hasher.write_f32(self.last_hit_intensity);
+
+        hasher.write_bool(self.hit_by_blobs[0]);
+        hasher.write_bool(self.hit_by_blobs[1]);
+        hasher.write_bool(self.hit_wall);
+        hasher.write_bool(self.hit_net_side);
+        hasher.write_bool(self.hit_net_sphere);
+    }
+
+    pub fn get_speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    pub fn hit_player(&self, player : PlayerSide) -> bool {
+        self.hit_by_blobs[player as usize]
+    }
+
+    pub fn hit_right_ground(&self, arena : &Arena) -> bool {
+        if self.is_valid {
+            if
+                self.position.y > arena.get_blob_ground_height() &&
+                self.position.x > arena.net_position_x {
+                    return true;
+            }
+        }
+        false
+    }
+
+    pub fn hit_left_ground(&self, arena : &Arena) -> bool {
+        if self.is_valid {
+            if
+                self.position.y > arena.get_blob_ground_height() &&
+                self.position.x < arena.net_position_x {
+                    return true;
+            }
+        }
+        false
+    }
+
+    // resting on the ground after a rally
+    pub fn is_at_rest(&self, arena : &Arena) -> bool {
+        !self.is_valid &&
+            self.velocity.y < 1.5f32 &&
+            self.velocity.y > -1.5f32 &&
+            self.position.y > arena.ground_height - 70f32
+    }
+
+    pub fn clear_collisions(&mut self) {
+        self.hit_by_blobs = [false; 2];
+        self.hit_wall = false;
+        self.hit_net_side = false;
+        self.hit_net_sphere = false;
+    }
+
+    pub fn damp(&mut self, params : &PhysicsParams) {
+        self.velocity = self.velocity.scale(params.damp_ball_scale_factor);
+    }
+
+    // Gravity, and the spin and the environment of the variants
+    pub fn apply_forces(&mut self, wind : &WindState, params : &PhysicsParams) {
+        self.velocity.y += params.ball_gravitation * params.time_scaling;
+
+        if params.is_spin_enabled() {
+            self.apply_magnus_effect(params);
+        }
+
+        if params.has_wind() {
+            self.velocity.x += wind.get_force(params) * params.time_scaling;
+        }
+
+        if params.has_air_drag() {
+            self.velocity =
+                self.velocity.scale(1.0f32 - params.air_drag * params.time_scaling);
+        }
+    }
+
+    fn check_blobby_collision(
+        &mut self,
+        player : PlayerSide,
+        blob_position : Vector2f,
+        blob_velocity : Vector2f,
+        character : &Character,
+        params : &PhysicsParams
+    ) {
+        let lower_circle_pos =
+            Vector2f::new(blob_position.x, blob_position.y + character.get_lower_sphere());
+        let upper_circle_pos =
+            Vector2f::new(blob_position.x, blob_position.y - character.get_upper_sphere());
+
+        // Check for bottom circles first
+        let circle_pos =
+            if (lower_circle_pos - self.position).length() <= BALL_RADIUS + character.get_lower_radius() {
+                lower_circle_pos
+            }
+            else if (upper_circle_pos - self.position).length() <= BALL_RADIUS + character.get_upper_radius() {
+                upper_circle_pos
+            }
+            else {
+                return;
+            };
+
+        let relative_velocity = self.velocity - blob_velocity;
+        self.last_hit_intensity = relative_velocity.length();
+
+        self.velocity = -(circle_pos - self.position);
+        self.velocity = self.velocity.normalized();
+        let normal = self.velocity;
+        self.apply_contact_friction(normal, relative_velocity, SPIN_BLOB_GRIP, false, params);
+        self.velocity = self.velocity.scale(params.ball_collision_velocity);
+        self.position += self.velocity;
+        self.hit_by_blobs[player as usize] = true;
+    }
+
+    fn move_on_the_ground(&mut self, params : &PhysicsParams) {
+        self.velocity =
+        self.velocity
+        .reflect_y()
+        .scale_y(params.speed_scale_on_ground_bounce_y);
+
+        self.velocity =
+            self.velocity.scale_x(params.speed_scale_on_ground_bounce_x);
+
+        self.position.y = params.arena.ground_height - BALL_RADIUS;
+
+        let velocity = self.velocity;
+        self.apply_contact_friction(Vector2f::new(0.0f32, -1.0f32), velocity, SPIN_GROUND_GRIP, true, params);
+    }
+
+    // Friction of the spin model at a contact of the ball, the normal going
+    // from the surface to the ball and relative_velocity being the one of the
+    // ball against the surface. It removes the grip share of the slip of the
+    // contact point, half by spinning the ball and, if the ball slides on the
+    // surface, half by changing its tangential velocity
+    fn apply_contact_friction(
+        &mut self,
+        normal : Vector2f,
+        relative_velocity : Vector2f,
+        grip : f32,
+        slides : bool,
+        params : &PhysicsParams
+    ) {
+        if !params.is_spin_enabled() {
+            return;
+        }
+
+        let grip = (grip * params.spin_factor).min(1.0f32);
+        let tangent = Vector2f::new(-normal.y, normal.x);
+        let slip = relative_velocity.dot_product(&tangent) - self.spin * BALL_RADIUS;
+
+        self.spin += grip * slip / (2.0f32 * BALL_RADIUS);
+
+        if slides {
+            self.velocity += tangent * (-grip * slip / 2.0f32);
+        }
+    }
+
+    // Magnus effect of the spin model: the spin bends the flight of the ball
+    fn apply_magnus_effect(&mut self, params : &PhysicsParams) {
+        let sideways = Vector2f::new(-self.velocity.y, self.velocity.x);
+
+        self.velocity +=
+            sideways *
+            (self.spin * MAGNUS_COEFFICIENT * params.spin_factor * params.time_scaling);
+
+        self.spin *= 1.0f32 - SPIN_AIR_DAMPING * params.time_scaling;
+    }
+
+    // Number of sub-steps for the ball to move by at most MAX_BALL_STEP_DISTANCE
+    // at a time, 1 at the usual speeds
+    pub fn get_step_count(&self, params : &PhysicsParams) -> u32 {
+        let distance = self.get_speed() * params.time_scaling;
+
+        if distance <= MAX_BALL_STEP_DISTANCE {
+            1
+        }
+        else {
+            // min also turns a NaN speed into the bound
+            (distance / MAX_BALL_STEP_DISTANCE).ceil().min(MAX_BALL_SUB_STEPS as f32) as u32
+        }
+    }
+
+    // Moves the ball by the given fraction of a step and resolves its collisions
+    pub fn step(
+        &mut self,
+        step_fraction : f32,
+        blob_positions : &[Vector2f; 2],
+        blob_velocities : &[Vector2f; 2],
+        characters : &[Character; 2],
+        params : &PhysicsParams
+    ) {
+        let arena = &params.arena;
+
+        self.position += self.velocity * params.time_scaling * step_fraction;
+
+        // Collision detection
+        if self.is_valid {
+            for player in [LeftPlayer, RightPlayer].iter() {
+                let player_index = *player as usize;
+                self.check_blobby_collision(
+                    *player,
+                    blob_positions[player_index],
+                    blob_velocities[player_index],
+                    &characters[player_index],
+                    params
+                );
+            }
+        }
+        // Ball to ground Collision
+        else if self.position.y + BALL_RADIUS > arena.ground_height {
+            self.move_on_the_ground(params);
+        }
+
+        // Ceiling Collision
+        if let Some(ceiling) = arena.ceiling {
+            if self.position.y - BALL_RADIUS <= ceiling && self.velocity.y < 0.0 {
+                self.velocity = self.velocity.reflect_y();
+                self.position.y = ceiling + BALL_RADIUS;
+                self.hit_wall = true;
+            }
+        }
+
+        // Border Collision
+        if
+            self.position.x - BALL_RADIUS <= arena.left_wall &&
+            self.velocity.x < 0.0
+        {
+            self.velocity = self.velocity.reflect_x();
+            // set the ball's position
+            self.position.x = arena.left_wall + BALL_RADIUS;
+            self.hit_wall = true;
+        }
+        else if
+            self.position.x + BALL_RADIUS >= arena.right_wall &&
+            self.velocity.x > 0.0
+        {
+            self.velocity = self.velocity.reflect_x();
+            // set the ball's position
+            self.position.x = arena.right_wall - BALL_RADIUS;
+            self.hit_wall = true;
+        }
+        else if
+
+            self.position.y > arena.net_sphere_position &&
+            (self.position.x - arena.net_position_x).abs() < BALL_RADIUS + arena.net_radius
+        {
+            self.velocity = self.velocity.reflect_x();
+            // set the ball's position so that it touches the net
+            let delta =
+                if self.position.x - arena.net_position_x > 0.0f32 {
+                    BALL_RADIUS + arena.net_radius
+                }
+                else {
+                    -BALL_RADIUS - arena.net_radius
+                };
+
+            self.position.x =  arena.net_position_x + delta;
+            self.hit_net_side = true;
+
+            let velocity = self.velocity;
+            self.apply_contact_friction(
+                Vector2f::new(delta.signum(), 0.0f32),
+                velocity,
+                SPIN_NET_GRIP,
+                true,
+                params
+            );
+        }
+        else
+        {
+            // Net Collisions
+
+            let ball_net_vec =
+                Vector2f::new(arena.net_position_x, arena.net_sphere_position) - self.position;
+
+            let ball_net_distance = ball_net_vec.length();
+
+            if ball_net_distance < arena.net_radius + BALL_RADIUS
+            {
+                let vec =
+                    Vector2f::new(arena.net_position_x, arena.net_sphere_position) -
+                    self.position;
+                // calculate
+                let normal = vec.normalized();
+
+                // normal component of kinetic energy
+                let mut perpendicular_kinematic_energy =
+                    normal.dot_product(&self.velocity);
+
+                perpendicular_kinematic_energy *= perpendicular_kinematic_energy;
+                // parallel component of kinetic energy
+                let mut parallel_kinematic_energy =
+                    self.velocity.length() * self.velocity.length() -
+                    perpendicular_kinematic_energy;
+
+                // the normal component is damped stronger than the parallel component
+                // the values are ~ 0.85² and ca. 0.95², because speed is sqrt(ekin)
+                perpendicular_kinematic_energy *=
+                    params.perpendicular_kinematic_energy_damping_factor;
+
+                parallel_kinematic_energy *=
+                    params.parallel_kinematic_energy_damping_factor;
+
+                let n_speed =
+                    float_math::sqrt(perpendicular_kinematic_energy + parallel_kinematic_energy);
+
+                self.velocity =
+                    self.velocity
+                    .reflect(&normal)
+                    .normalized()
+                    .scale(n_speed);
+
+                // pushes the ball out of the net
+                self.position =
+                    Vector2f::new
+                    (
+                        arena.net_position_x,
+                        arena.net_sphere_position
+                    ) - normal * (arena.net_radius + BALL_RADIUS);
+
+                self.hit_net_sphere = true;
+
+                let velocity = self.velocity;
+                self.apply_contact_friction(-normal, velocity, SPIN_NET_GRIP, true, params);
+            }
+        }
+
+        // Obstacle Collisions
+        for obstacle in arena.get_obstacles() {
+            self.check_obstacle_collision(obstacle, params);
+        }
+    }
+
+    // Bounces the ball off an obstacle of the arena it overlaps and flies
+    // towards, keeping the restitution share of its normal speed
+    fn check_obstacle_collision(&mut self, obstacle : &Obstacle, params : &PhysicsParams) {
+        let closest_point = obstacle.get_closest_point(self.position);
+        let offset = self.position - closest_point;
+        let distance = offset.length();
+
+        let inside =
+            match obstacle.shape {
+                ObstacleShape::Circle { centre, radius } =>
+                    (self.position - centre).length() < radius,
+                ObstacleShape::Segment { .. } => false,
+            };
+
+        if distance >= BALL_RADIUS && !inside {
+            return;
+        }
+
+        let normal =
+            if distance > 0.0f32 {
+                if inside { -offset.normalized() } else { offset.normalized() }
+            }
+            else {
+                obstacle.get_fallback_normal()
+            };
+
+        let normal_speed = self.velocity.dot_product(&normal);
+        if normal_speed < 0.0f32 {
+            self.velocity = self.velocity - normal * ((1.0f32 + obstacle.restitution) * normal_speed);
+        }
+
+        // pushes the ball out of the obstacle
+        self.position = closest_point + normal * BALL_RADIUS;
+        self.hit_wall = true;
+
+        let velocity = self.velocity;
+        self.apply_contact_friction(normal, velocity, SPIN_NET_GRIP, true, params);
+    }
+
+    // Turns the ball, with its spin in the spin model, otherwise as fast as
+    // it flies
+    pub fn update_rotation(&mut self, params : &PhysicsParams) {
+        // Velocity Integration
+        if params.is_spin_enabled() {
+            self.rotation += self.spin * params.time_scaling;
+        }
+        else if self.velocity.x > 0.0 {
+            self.rotation +=
+                self.angular_velocity * params.time_scaling *
+                    (self.get_speed() / params.ball_angular_velocity_scale_factor);
+        }
+        else if self.velocity.x < 0.0 {
+            self.rotation -=
+                self.angular_velocity * params.time_scaling *
+                    (self.get_speed() / params.ball_angular_velocity_scale_factor);
+        }
+        else {
+            self.rotation -=
+                self.angular_velocity * params.time_scaling;
+        }
+
+        // Overflow-Protection
+        if self.rotation <= 0.0f32 {
+            self.rotation = 6.25f32 + self.rotation;
+        }
+        else if self.rotation >= 6.25f32 {
+            self.rotation = self.rotation - 6.25f32;
+        }
+
+        // More Overflow-Protection
+        if self.rotation <= 0.0f32 {
+            self.rotation = 6.25f32;
+        }
+        else if self.rotation >= 6.25f32 {
+            self.rotation = 6.25f32;
+        }
+
+        assert!(self.rotation >= 0.0f32);
+    }
+}
@@ -0,0 +1,130 @@
+use global::PlayerSide;
+use global::PlayerSide::*;
+use physics_params::PhysicsParams;
+use environment::WindState;
+use ball::Ball;
+use character::Character;
+use vector::Vector2f;
+
+// Future states of a ball as if nobody touched it: the ball flies through
+// the blobs and bounces on the ground, like a ball out of play. Built by
+// PhysicWorld::predict_ball_steps, it works on copies and never changes the
+// world. Each item is the ball after one more step, with the collision flags
+// of that step.
+pub struct BallPredictor<'a> {
+    ball : Ball,
+    wind : WindState,
+    is_game_running : bool,
+    params : &'a PhysicsParams,
+}
+
+impl<'a> BallPredictor<'a> {
+    pub fn new(
+        ball : Ball,
+        wind : WindState,
+        is_game_running : bool,
+        params : &'a PhysicsParams
+    ) -> BallPredictor<'a> {
+        BallPredictor {
+            ball : Ball {
+                is_valid : false,
+                ..ball
+            },
+            wind : wind,
+            is_game_running : is_game_running,
+            params : params,
+        }
+    }
+}
+
+impl<'a> Iterator for BallPredictor<'a> {
+    type Item = Ball;
+
+    // The ball part of PhysicWorld::step
+    fn next(&mut self) -> Option<Ball> {
+        let ball = &mut self.ball;
+
+        ball.clear_collisions();
+
+        if self.is_game_running {
+            ball.apply_forces(&self.wind, self.params);
+        }
+
+        if self.params.has_wind_gusts() {
+            self.wind.step(self.params.time_scaling);
+        }
+
+        // the ball is not valid, the blobs are not used
+        let blob_positions = [Vector2f::new(0.0f32, 0.0f32); 2];
+        let blob_velocities = [Vector2f::new(0.0f32, 0.0f32); 2];
+        let characters = [Character::default(); 2];
+
+        let ball_step_count = ball.get_step_count(self.params);
+        for _ in 0..ball_step_count {
+            ball.step(
+                1.0f32 / ball_step_count as f32,
+                &blob_positions,
+                &blob_velocities,
+                &characters,
+                self.params
+            );
+        }
+
+        ball.update_rotation(self.params);
+
+        Some(*ball)
+    }
+}
+
+// Trajectory of a ball over the next steps, see PhysicWorld::predict_ball.
+// The contacts are the index in the trajectory of the first step the ball
+// meets each part of the field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BallPrediction {
+    // position after each step
+    pub trajectory : Vec<Vector2f>,
+
+    // where the ball would score, on the side of the player losing the point
+    pub ground_contact : Option<(usize, PlayerSide)>,
+    // walls, ceiling and obstacles
+    pub wall_contact : Option<usize>,
+    pub net_contact : Option<usize>,
+}
+
+impl BallPrediction {
+    pub fn new(predictor : BallPredictor, max_steps : usize) -> BallPrediction {
+        let arena = predictor.params.arena;
+
+        let mut prediction = BallPrediction {
+            trajectory : Vec::with_capacity(max_steps),
+            ground_contact : None,
+            wall_contact : None,
+            net_contact : None,
+        };
+
+        for (step, ball) in predictor.take(max_steps).enumerate() {
+            prediction.trajectory.push(ball.position);
+
+            // the height at which DuelMatch scores a valid ball
+            if prediction.ground_contact.is_none() && ball.position.y > arena.get_blob_ground_height() {
+                let side = if ball.position.x < arena.net_position_x { LeftPlayer } else { RightPlayer };
+                prediction.ground_contact = Some((step, side));
+            }
+
+            if prediction.wall_contact.is_none() && ball.hit_wall {
+                prediction.wall_contact = Some(step);
+            }
+
+            if prediction.net_contact.is_none() && (ball.hit_net_side || ball.hit_net_sphere) {
+                prediction.net_contact = Some(step);
+            }
+        }
+
+        prediction
+    }
+
+    // where the ball first touches the ground
+    pub fn get_landing_position(&self) -> Option<Vector2f> {
+        self.ground_contact.map(|(step, _)| self.trajectory[step])
+    }
+}
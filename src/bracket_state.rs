@@ -0,0 +1,279 @@
+extern crate rand;
+
+use quicksilver::{
+    Result,
+    geom::{Shape, Transform, Vector},
+    graphics::{Background::Img, Color, Image},
+    lifecycle::{Window, Event,},
+};
+
+use quicksilver::input::*;
+
+use state_manager::{
+    *,
+    RustyGameState::*,
+    StateTransition::*
+};
+
+use field_drawing::draw_background;
+use game_constants::*;
+
+use global::PlayerKind::*;
+use global::PlayerSide;
+use new_game_menu_state::GameConfiguration;
+use tournament::{BracketMatch, BracketPart, Tournament, simulate_match};
+
+// bracket lines in columns, in window coordinates
+const BRACKET_TOP_Y : f32 = 420.0f32;
+const BRACKET_SPACING_Y : f32 = 80.0f32;
+const BRACKET_ROWS : usize = 11;
+const BRACKET_COLUMNS_X : [f32; 3] = [340.0f32, 960.0f32, 1580.0f32];
+
+fn participant_name(participant : usize) -> String {
+    format!("Player {}", participant + 1)
+}
+
+fn short_name(participant : Option<usize>) -> String {
+    participant.map_or("?".to_string(), |participant| format!("P{}", participant + 1))
+}
+
+fn match_label(bracket_match : &BracketMatch) -> String {
+    match bracket_match.part {
+        BracketPart::WinnersBracket => format!("W{}", bracket_match.round),
+        BracketPart::LosersBracket => format!("L{}", bracket_match.round),
+        BracketPart::GrandFinal => "Final".to_string(),
+        BracketPart::GrandFinalReset => "Final 2".to_string(),
+    }
+}
+
+// The bracket of the tournament between its matches. Return plays the next
+// match, or simulates it when both participants are bots and the settings
+// ask for it.
+pub struct BracketState {
+    tournament : Option<Tournament>,
+    configuration : Option<GameConfiguration>,
+    simulate_bot_matches : bool,
+    // match being played in the LocalGameState
+    current_match : Option<usize>,
+    line_texts : Vec<String>,
+    line_images : Vec<Image>,
+}
+
+impl BracketState {
+    pub fn new() -> BracketState {
+        BracketState {
+            tournament : None,
+            configuration : None,
+            simulate_bot_matches : true,
+            current_match : None,
+            line_texts : vec!(),
+            line_images : vec!(),
+        }
+    }
+
+    pub fn set_tournament(&mut self, tournament : Tournament, configuration : GameConfiguration, simulate_bot_matches : bool) {
+        self.tournament = Some(tournament);
+        self.configuration = Some(configuration);
+        self.simulate_bot_matches = simulate_bot_matches;
+        self.current_match = None;
+    }
+
+    // Result of the match played in the LocalGameState, by player
+    pub fn record_winner(&mut self, winner : PlayerSide) {
+        if let (Some(tournament), Some(match_index)) = (self.tournament.as_mut(), self.current_match.take()) {
+            tournament.record_winner(match_index, winner);
+        }
+    }
+
+    fn is_simulated(&self, entrants : [usize; 2]) -> bool {
+        let participants = match self.tournament {
+            Some(ref tournament) => tournament.get_participants(),
+            None => return false,
+        };
+
+        self.simulate_bot_matches &&
+            participants[entrants[0]].kind == Computer &&
+            participants[entrants[1]].kind == Computer
+    }
+
+    // the next match and its participants, None once the tournament is over
+    fn get_next_match(&self) -> Option<(usize, [usize; 2])> {
+        let tournament = self.tournament.as_ref()?;
+        let match_index = tournament.get_next_match()?;
+
+        match tournament.get_entrants(match_index) {
+            [Some(first), Some(second)] => Some((match_index, [first, second])),
+            _ => None,
+        }
+    }
+
+    // the title, the hint and then one line per match of the bracket, the
+    // matches without play left out
+    fn get_line_texts(&self) -> Vec<String> {
+        let tournament = match self.tournament {
+            Some(ref tournament) => tournament,
+            None => return vec!(),
+        };
+
+        let next_match = self.get_next_match();
+
+        let mut line_texts =
+            match (tournament.get_champion(), next_match) {
+                (Some(champion), _) => vec!(
+                    format!("{} wins the tournament!", participant_name(champion)),
+                    "Return for the menu".to_string(),
+                ),
+                (None, Some((_, entrants))) => vec!(
+                    format!(
+                        "Next: {} ({}) vs {} ({})",
+                        participant_name(entrants[0]),
+                        tournament.get_participants()[entrants[0]],
+                        participant_name(entrants[1]),
+                        tournament.get_participants()[entrants[1]]
+                    ),
+                    if self.is_simulated(entrants) {
+                        "Return to simulate, Escape for the menu".to_string()
+                    } else {
+                        "Return to play, Escape for the menu".to_string()
+                    },
+                ),
+                (None, None) => vec!(String::new(), String::new()),
+            };
+
+        for (match_index, bracket_match) in tournament.get_matches().iter().enumerate() {
+            let entrants = tournament.get_entrants(match_index);
+
+            let description =
+                match bracket_match.result {
+                    Some(result) if !result.played => continue,
+                    Some(result) => format!("{} beat {}", short_name(result.winner), short_name(result.loser)),
+                    None => format!("{} vs {}", short_name(entrants[0]), short_name(entrants[1])),
+                };
+
+            let marker = if next_match.map(|(index, _)| index) == Some(match_index) { "> " } else { "" };
+
+            line_texts.push(format!("{}{}: {}", marker, match_label(bracket_match), description));
+        }
+
+        line_texts
+    }
+}
+
+impl RustyVollyState for BracketState {
+    fn step(&mut self, _game_assets: &mut GamesAssets) -> StateTransition {
+        NoTransition
+    }
+
+    fn draw_window_content(&mut self, window: &mut Window, game_assets: &mut GamesAssets) -> Result<()> {
+        window.clear(Color::WHITE)?;
+
+        draw_background(window, game_assets)?;
+
+        // draw text
+        {
+            let line_texts = self.get_line_texts();
+            let should_recreate_texture = line_texts != self.line_texts;
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                if should_recreate_texture {
+                    let mut line_images = vec!();
+
+                    // empty lines cannot be rendered, they are kept blank
+                    for text in line_texts.iter() {
+                        line_images.push(a_font.render(if text.is_empty() { " " } else { text }, &game_assets.font_style).unwrap());
+                    }
+
+                    self.line_images = line_images;
+                    self.line_texts = line_texts;
+                }
+
+                for (line_index, image) in self.line_images.iter().enumerate() {
+                    // the title and the hint, then the bracket in columns
+                    let (x, y, scale) =
+                        if line_index < 2 {
+                            (
+                                WINDOW_WIDTH as f32 / 2.0f32,
+                                150.0f32 + 110.0f32 * line_index as f32,
+                                DISPLAY_SCALE_FACTOR * 1.4f32
+                            )
+                        } else {
+                            let bracket_index = line_index - 2;
+                            (
+                                BRACKET_COLUMNS_X[(bracket_index / BRACKET_ROWS).min(BRACKET_COLUMNS_X.len() - 1)],
+                                BRACKET_TOP_Y + BRACKET_SPACING_Y * (bracket_index % BRACKET_ROWS) as f32,
+                                DISPLAY_SCALE_FACTOR
+                            )
+                        };
+
+                    window.draw_ex(
+                        &image.area().with_center((x * DISPLAY_SCALE_FACTOR, y * DISPLAY_SCALE_FACTOR)),
+                        Img(&image),
+                        Transform::scale(Vector::new(scale, scale)),
+                        4.0f32
+                    );
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event, _window: &mut Window) -> StateTransition {
+        match *event {
+            Event::Key(Key::Return, ButtonState::Pressed) | Event::Key(Key::Space, ButtonState::Pressed) => (),
+            Event::Key(Key::Escape, ButtonState::Pressed) => return StateTransition::StateLessTransition(NewGameMenu),
+            _ => return NoTransition,
+        }
+
+        let (match_index, entrants) = match self.get_next_match() {
+            Some(next_match) => next_match,
+            None => return StateTransition::StateLessTransition(NewGameMenu),
+        };
+
+        let configuration = match self.configuration {
+            Some(ref configuration) => configuration.clone(),
+            None => return NoTransition,
+        };
+
+        let participants = match self.tournament {
+            Some(ref tournament) => [
+                tournament.get_participants()[entrants[0]],
+                tournament.get_participants()[entrants[1]],
+            ],
+            None => return NoTransition,
+        };
+
+        let bot_difficulties = [participants[0].bot_difficulty, participants[1].bot_difficulty];
+
+        if self.is_simulated(entrants) {
+            let winner =
+                simulate_match(
+                    configuration.physics_params,
+                    configuration.rules,
+                    bot_difficulties,
+                    rand::random()
+                );
+
+            self.current_match = Some(match_index);
+            self.record_winner(winner);
+            return NoTransition;
+        }
+
+        self.current_match = Some(match_index);
+
+        StateTransition::StartTournamentMatchTransition(
+            GameConfiguration {
+                player1_configuration : participants[0].kind,
+                player2_configuration : participants[1].kind,
+                bot_difficulties : bot_difficulties,
+                ..configuration
+            },
+            [participant_name(entrants[0]), participant_name(entrants[1])]
+        )
+    }
+}
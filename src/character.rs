@@ -0,0 +1,64 @@
+use game_constants::*;
+use physics_params::PhysicsParams;
+use arena::Arena;
+
+// Body of a blob as factors of the classic one: how fast it walks, how
+// strongly it jumps and how big it is. PhysicsParams holds the characters of
+// the players, the PhysicWorld the ones of the blobs on each side.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Character {
+    pub speed : f32,
+    pub jump : f32,
+    pub size : f32,
+}
+
+impl Default for Character {
+    fn default() -> Character {
+        Character {
+            speed : 1.0f32,
+            jump : 1.0f32,
+            size : 1.0f32,
+        }
+    }
+}
+
+impl Character {
+    pub fn get_speed(&self, params : &PhysicsParams) -> f32 {
+        params.blobby_speed * self.speed
+    }
+
+    pub fn get_jump_acceleration(&self, params : &PhysicsParams) -> f32 {
+        params.blobby_jump_acceleration * self.jump
+    }
+
+    pub fn get_jump_buffer(&self, params : &PhysicsParams) -> f32 {
+        params.blobby_jump_buffer * self.jump
+    }
+
+    pub fn get_height(&self) -> f32 {
+        BLOBBY_HEIGHT * self.size
+    }
+
+    // offsets of the collision spheres from the centre of the blob, and
+    // their radii
+    pub fn get_upper_sphere(&self) -> f32 {
+        BLOBBY_UPPER_SPHERE * self.size
+    }
+
+    pub fn get_upper_radius(&self) -> f32 {
+        BLOBBY_UPPER_RADIUS * self.size
+    }
+
+    pub fn get_lower_sphere(&self) -> f32 {
+        BLOBBY_LOWER_SPHERE * self.size
+    }
+
+    pub fn get_lower_radius(&self) -> f32 {
+        BLOBBY_LOWER_RADIUS * self.size
+    }
+
+    // y of the centre of the blob standing on the ground
+    pub fn get_ground_height(&self, arena : &Arena) -> f32 {
+        arena.ground_height - self.get_height() / 2.0
+    }
+}
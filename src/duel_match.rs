@@ -1,103 +1,378 @@
-use game_logic::GameLogic;
-use physic_world::PhysicWorld;
+use game_logic::{ErrorReason, GameLogic, GameLogicSnapshot};
+use match_logic::{MatchLogic, MatchLogicSnapshot};
+use physic_world::{PhysicWorld, PhysicWorldSnapshot};
+use ball::Ball;
+use physics_params::PhysicsParams;
+use match_rules::{Handicap, MatchRules};
 use global::PlayerSide::*;
 use global::PlayerSide;
+use global::PlayerKind;
+use replay::{Replay, ReplayRecorder};
+use state_hash::StateHasher;
 
 use vector::Vector2f;
 
 pub struct DuelMatch {
     game_logic : GameLogic,
+    match_logic : MatchLogic,
     physic_world : PhysicWorld,
+    recorder : Option<ReplayRecorder>,
 }
 
-#[derive(PartialEq, Eq)]
+// Full state of a match at a given frame, see DuelMatch::snapshot
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchSnapshot {
+    pub physic_world : PhysicWorldSnapshot,
+    pub game_logic : GameLogicSnapshot,
+    pub match_logic : MatchLogicSnapshot,
+}
+
+// Events of a step. The ball position and velocity are the ones right
+// after the collision, the side is the side of the net it happened on
+#[derive(PartialEq, Clone, Debug)]
 pub enum FrameEvent {
-    EventBlobbyHit(PlayerSide),
-    EventBallHitGround(PlayerSide),
-    EventError(PlayerSide),
-    EventWin(PlayerSide),
+    // a valid hit, intensity is the relative speed of the ball and the blob
+    // and touch_count the touches of the side, this one included
+    EventBlobbyHit {
+        side : PlayerSide,
+        ball_position : Vector2f,
+        ball_velocity : Vector2f,
+        intensity : f32,
+        touch_count : i32,
+    },
+    EventBallHitGround {
+        side : PlayerSide,
+        ball_position : Vector2f,
+        ball_velocity : Vector2f,
+    },
+    EventBallHitNetSphere {
+        ball_position : Vector2f,
+        ball_velocity : Vector2f,
+    },
+    EventBallHitNetSide {
+        side : PlayerSide,
+        ball_position : Vector2f,
+        ball_velocity : Vector2f,
+    },
+    EventBallHitWall {
+        side : PlayerSide,
+        ball_position : Vector2f,
+        ball_velocity : Vector2f,
+    },
+    EventError {
+        side : PlayerSide,
+        reason : ErrorReason,
+    },
+    // the first hit of the serving side, which starts the rally
+    EventServe(PlayerSide),
+    // set and match events give the player, i.e. the side the player started
+    // the match on, which differs from the current side once sides are swapped
+    EventSetWon(PlayerSide),
+    EventMatchWon(PlayerSide),
     EventReset,
 }
 
 impl DuelMatch {
     pub fn step(&mut self, events : &mut Vec<FrameEvent>) {
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record_frame(
+                self.physic_world.get_player_input(LeftPlayer),
+                self.physic_world.get_player_input(RightPlayer)
+            );
+        }
+
+        let was_game_running = self.physic_world.is_game_running();
+
         self.physic_world.step();
         self.game_logic.step();
 
-        let mut has_ball_hit_ground = false;
+        if !was_game_running && self.physic_world.is_game_running() {
+            events.push(FrameEvent::EventServe(self.game_logic.get_serving_player()));
+        }
 
-        if self.physic_world.ball_hit_left_player() {
-            let valid_hit = self.game_logic.on_ball_hits_player(LeftPlayer);
-            if valid_hit {
-                events.push(FrameEvent::EventBlobbyHit(LeftPlayer));
+        // the clock only runs while a rally is played, from the serve on
+        if self.physic_world.is_game_running() && self.physic_world.is_any_ball_valid() {
+            self.game_logic.step_clock();
+        }
+
+        for ball_index in 0..self.physic_world.get_ball_count() {
+            let scores = self.game_logic.get_scores();
+            let serving_player = self.game_logic.get_serving_player();
+
+            self.step_ball_events(ball_index, events);
+
+            // one boundary per point played: the errors of practice and the
+            // ones after the game is decided change nothing
+            let point_played =
+                self.game_logic.get_scores() != scores ||
+                self.game_logic.get_serving_player() != serving_player;
+
+            if point_played {
+                if let Some(ref mut recorder) = self.recorder {
+                    recorder.record_point_boundary();
+                }
             }
         }
 
-        if self.physic_world.ball_hit_right_player() {
-            let valid_hit = self.game_logic.on_ball_hits_player(RightPlayer);
-            if valid_hit {
-                events.push(FrameEvent::EventBlobbyHit(RightPlayer));   
+        let set_winning_side = self.game_logic.get_winning_player();
+
+        if set_winning_side != NoPlayer && !self.match_logic.is_set_finished() {
+            let set_winner = self.match_logic.get_player_on_side(set_winning_side);
+            let (left_score, right_score) = self.game_logic.get_scores();
+            let scores =
+                if self.match_logic.get_side_of_player(LeftPlayer) == LeftPlayer {
+                    [left_score, right_score]
+                }
+                else {
+                    [right_score, left_score]
+                };
+
+            self.match_logic.on_set_won(set_winner, scores, &self.game_logic.get_rules());
+            events.push(FrameEvent::EventSetWon(set_winner));
+        }
+
+        if self.physic_world.is_round_finished() {
+            events.push(FrameEvent::EventReset); 
+
+            if
+                self.match_logic.is_set_finished() &&
+                self.match_logic.get_winning_player() == NoPlayer
+            {
+                let rules = self.game_logic.get_rules();
+                let serving_side = self.match_logic.start_next_set(&rules);
+
+                // the inputs follow the players to their new sides
+                if rules.swap_sides_between_sets {
+                    let left_input = self.physic_world.get_player_input(LeftPlayer);
+                    let right_input = self.physic_world.get_player_input(RightPlayer);
+                    self.physic_world.set_player_input(LeftPlayer, right_input);
+                    self.physic_world.set_player_input(RightPlayer, left_input);
+                    self.place_players();
+                }
+
+                // after the handicaps moved, for the head starts
+                self.game_logic.start_new_game(serving_side);
             }
+
+            self.physic_world.reset(self.game_logic.get_serving_player());
         }
 
-        if self.physic_world.ball_hit_left_ground() {
+        let winning_player = self.match_logic.get_winning_player();
+
+        match winning_player {
+            NoPlayer => (),
+            _ => {
+                events.push(FrameEvent::EventMatchWon(winning_player));
+            },
+        }
+
+    }
+
+    // Events and scoring of one ball after the physic step
+    fn step_ball_events(&mut self, ball_index : usize, events : &mut Vec<FrameEvent>) {
+        let ball = self.physic_world.get_balls()[ball_index];
+        let ball_position = ball.position;
+        let ball_velocity = ball.velocity;
+        let arena = self.physic_world.get_physics_params().arena;
+        let ball_side = if ball_position.x < arena.net_position_x { LeftPlayer } else { RightPlayer };
+
+        let mut has_ball_hit_ground = false;
+
+        for side in [LeftPlayer, RightPlayer].iter() {
+            if ball.hit_player(*side) {
+                let touch_count = self.game_logic.get_touch_count(*side, ball_index) + 1;
+                let valid_hit = self.game_logic.on_ball_hits_player(*side, ball_index);
+                if valid_hit {
+                    events.push(FrameEvent::EventBlobbyHit {
+                        side : *side,
+                        ball_position : ball_position,
+                        ball_velocity : ball_velocity,
+                        intensity : ball.last_hit_intensity,
+                        touch_count : touch_count,
+                    });
+                }
+            }
+        }
+
+        if ball.hit_net_sphere {
+            events.push(FrameEvent::EventBallHitNetSphere {
+                ball_position : ball_position,
+                ball_velocity : ball_velocity,
+            });
+        }
+
+        if ball.hit_net_side {
+            events.push(FrameEvent::EventBallHitNetSide {
+                side : ball_side,
+                ball_position : ball_position,
+                ball_velocity : ball_velocity,
+            });
+        }
+
+        if ball.hit_wall {
+            events.push(FrameEvent::EventBallHitWall {
+                side : ball_side,
+                ball_position : ball_position,
+                ball_velocity : ball_velocity,
+            });
+        }
+
+        if ball.hit_left_ground(&arena) {
             has_ball_hit_ground = true;
-            self.game_logic.on_ball_hits_ground(LeftPlayer);
-            events.push(FrameEvent::EventBallHitGround(LeftPlayer));    
+            self.game_logic.on_ball_hits_ground(LeftPlayer, ball_index);
+            events.push(FrameEvent::EventBallHitGround {
+                side : LeftPlayer,
+                ball_position : ball_position,
+                ball_velocity : ball_velocity,
+            });
         }
 
-        if self.physic_world.ball_hit_right_ground() {
+        if ball.hit_right_ground(&arena) {
             has_ball_hit_ground = true;
-            events.push(FrameEvent::EventBallHitGround(RightPlayer));
-            self.game_logic.on_ball_hits_ground(RightPlayer);
+            events.push(FrameEvent::EventBallHitGround {
+                side : RightPlayer,
+                ball_position : ball_position,
+                ball_velocity : ball_velocity,
+            });
+            self.game_logic.on_ball_hits_ground(RightPlayer, ball_index);
         }
 
+        let last_error_reason = self.game_logic.get_last_error_reason();
         let last_error = self.game_logic.get_last_error_side();
 
         match last_error {
             NoPlayer => (),
             _ => {
+                let params = self.physic_world.get_physics_params();
+                let ball = self.physic_world.get_ball_mut(ball_index);
+
                 if !has_ball_hit_ground {
-                    self.physic_world.damp_ball();
+                    ball.damp(&params);
                 }
 
-                events.push(FrameEvent::EventError(last_error));
-                self.physic_world.set_ball_validity(false);
+                events.push(FrameEvent::EventError {
+                    side : last_error,
+                    reason : last_error_reason,
+                });
+                ball.is_valid = false;
             },
         }
+    }
 
-        if self.physic_world.is_round_finished() {
-            events.push(FrameEvent::EventReset); 
-            self.physic_world.reset(self.game_logic.get_serving_player());
+    pub fn new(physics_params : PhysicsParams, rules : MatchRules) -> DuelMatch {
+        let game_logic = GameLogic::new(rules);
+        let mut physic_world = PhysicWorld::new(physics_params);
+        physic_world.set_ball_count(rules.get_ball_count());
+
+        physic_world.reset(game_logic.get_serving_player());
+        physic_world.step();
+
+        let mut duel_match = DuelMatch {
+            physic_world : physic_world,
+            game_logic: game_logic,
+            match_logic : MatchLogic::new(),
+            recorder : None,
+        };
+
+        duel_match.place_players();
+        duel_match
+    }
+
+    pub fn snapshot(&self) -> MatchSnapshot {
+        MatchSnapshot {
+            physic_world : self.physic_world.snapshot(),
+            game_logic : self.game_logic.snapshot(),
+            match_logic : self.match_logic.snapshot(),
         }
+    }
 
-        let winning_player = self.game_logic.get_winning_player();
+    // The snapshots leave the characters and the handicaps out, they are
+    // placed again from the params, the rules and the sides of the players
+    pub fn restore(&mut self, snapshot : &MatchSnapshot) {
+        self.physic_world.restore(&snapshot.physic_world);
+        self.game_logic.restore(&snapshot.game_logic);
+        self.match_logic.restore(&snapshot.match_logic);
+        self.place_players();
+    }
 
-        match winning_player {
-            NoPlayer => (),
-            _ => {
-                events.push(FrameEvent::EventWin(winning_player));
-            },
+    // Gives each side the character and the handicap of the player on it,
+    // the speed penalty slows the character down
+    fn place_players(&mut self) {
+        let characters = self.physic_world.get_physics_params().characters;
+        let handicaps = self.game_logic.get_rules().handicaps;
+        let mut side_handicaps = [Handicap::default(); 2];
+
+        for player in [LeftPlayer, RightPlayer].iter() {
+            let side = self.match_logic.get_side_of_player(*player);
+            let handicap = handicaps[*player as usize];
+
+            self.physic_world.set_character(side, handicap.apply_to(characters[*player as usize]));
+            side_handicaps[side as usize] = handicap;
         }
 
+        self.game_logic.set_handicaps(side_handicaps);
     }
 
-    pub fn new() -> DuelMatch {
-        let mut physic_world = PhysicWorld::new();
+    // Hash of the whole state of the match. Two simulations fed with the same
+    // inputs have the same hash after every step, as long as they agree on
+    // the physics (see the `deterministic` feature for other platforms)
+    pub fn get_state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        let snapshot = self.snapshot();
 
-        physic_world.reset_player();
-        physic_world.step();
+        snapshot.physic_world.add_to_hash(&mut hasher);
+        snapshot.game_logic.add_to_hash(&mut hasher);
+        snapshot.match_logic.add_to_hash(&mut hasher);
+        hasher.finish()
+    }
 
-        DuelMatch {
-            physic_world : physic_world,
-            game_logic: GameLogic::new(),
+    // Starts recording the inputs of every following step, from the current state
+    pub fn start_recording(&mut self) {
+        self.recorder =
+            Some(ReplayRecorder::new(self.snapshot(), self.physic_world.get_physics_params()));
+    }
+
+    // Same, for a match with computer players: the replay keeps who plays
+    // and the difficulty of the bots, by player
+    pub fn start_recording_players(&mut self, player_kinds : [PlayerKind; 2], bot_difficulties : [i32; 2]) {
+        self.start_recording();
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.set_players(player_kinds, bot_difficulties);
         }
     }
 
+    pub fn stop_recording(&mut self) -> Option<Replay> {
+        self.recorder.take().map(|recorder| recorder.finish())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    pub fn get_physics_params(&self) -> PhysicsParams {
+        self.physic_world.get_physics_params()
+    }
+
+    pub fn get_rules(&self) -> MatchRules {
+        self.game_logic.get_rules()
+    }
+
     pub fn get_world(&mut self) -> &mut PhysicWorld {
         &mut self.physic_world
     }
 
+    // Puts the balls back above the blob of the side as for a serve, at any
+    // moment of the rally, for the practice mode. Replays cannot follow it,
+    // so it ends the recording if there is one.
+    pub fn restart_rally(&mut self, side : PlayerSide) {
+        self.recorder = None;
+        self.physic_world.reset(side);
+
+        for ball_index in 0..self.physic_world.get_ball_count() {
+            self.game_logic.clear_touches(ball_index);
+        }
+    }
+
     pub fn get_serving_player(&self) -> PlayerSide {
         self.game_logic.get_serving_player()
     }
@@ -106,6 +381,11 @@ impl DuelMatch {
         self.physic_world.get_ball_position()
     }
 
+    // every ball, the first one being the one of get_ball_position
+    pub fn get_balls(&self) -> &[Ball] {
+        self.physic_world.get_balls()
+    }
+
     pub fn get_blob_position(&self, player: PlayerSide) -> Vector2f {
         if player == LeftPlayer
         {
@@ -121,7 +401,40 @@ impl DuelMatch {
         }
     }
 
+    // points of the current set, by side
     pub fn get_scores(&self) -> (i32, i32) {
         self.game_logic.get_scores()
     }
+
+    // sets won, by player
+    pub fn get_set_scores(&self) -> (i32, i32) {
+        self.match_logic.get_set_scores()
+    }
+
+    // point scores of the finished sets, by player
+    pub fn get_finished_sets(&self) -> &[[i32; 2]] {
+        self.match_logic.get_finished_sets()
+    }
+
+    // handicap of the player on the side
+    pub fn get_handicap(&self, side : PlayerSide) -> Handicap {
+        self.game_logic.get_handicap(side)
+    }
+
+    // steps of play left in the current set, None without time limit
+    pub fn get_remaining_time(&self) -> Option<i32> {
+        self.game_logic.get_remaining_time()
+    }
+
+    pub fn is_time_up(&self) -> bool {
+        self.game_logic.is_time_up()
+    }
+
+    pub fn get_side_of_player(&self, player : PlayerSide) -> PlayerSide {
+        self.match_logic.get_side_of_player(player)
+    }
+
+    pub fn get_player_on_side(&self, side : PlayerSide) -> PlayerSide {
+        self.match_logic.get_player_on_side(side)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+use game_constants::*;
+use physics_params::PhysicsParams;
+use float_math;
+use state_hash::StateHasher;
+
+// Gravity of the party variants, applied on top of the other parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GravityPreset {
+    NormalGravity,
+    MoonGravity,
+    HeavyGravity,
+}
+
+use self::GravityPreset::*;
+
+impl GravityPreset {
+    pub fn get_scale(&self) -> f32 {
+        match *self {
+            NormalGravity => 1.0f32,
+            MoonGravity => MOON_GRAVITY_SCALE,
+            HeavyGravity => HEAVY_GRAVITY_SCALE,
+        }
+    }
+
+    // Scales the gravity of the ball and of the blobs. The jump is scaled
+    // too, so that the blobs still jump as high, only slower or faster
+    pub fn apply(&self, params : PhysicsParams) -> PhysicsParams {
+        let scale = self.get_scale();
+
+        PhysicsParams {
+            ball_gravitation : params.ball_gravitation * scale,
+            blobby_gravitation : params.blobby_gravitation * scale,
+            blobby_jump_buffer : params.blobby_jump_buffer * scale,
+            blobby_jump_acceleration : params.blobby_jump_acceleration * float_math::sqrt(scale),
+            ..params
+        }
+    }
+}
+
+// State of the gusting wind. The gusts are drawn from a xorshift generator
+// whose state is part of the PhysicWorld snapshots, so that a replay or a
+// restored snapshot blows the same wind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindState {
+    pub random_state : u32,
+    // current gust, between -1 and 1, and the one it moves towards
+    pub gust : f32,
+    pub target_gust : f32,
+    pub steps_to_next_gust : i32,
+}
+
+impl WindState {
+    pub fn new(seed : u32) -> WindState {
+        WindState {
+            // xorshift never leaves 0
+            random_state : if seed == 0 { WIND_DEFAULT_SEED } else { seed },
+            gust : 0.0f32,
+            target_gust : 0.0f32,
+            steps_to_next_gust : 0,
+        }
+    }
+
+    fn next_random(&mut self) -> u32 {
+        self.random_state ^= self.random_state << 13;
+        self.random_state ^= self.random_state >> 17;
+        self.random_state ^= self.random_state << 5;
+        self.random_state
+    }
+
+    // uniform in [0, 1), the 24 bits fit exactly in a f32
+    fn next_unit(&mut self) -> f32 {
+        (self.next_random() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    // Moves the gust towards its target, drawing a new target from time to time
+    pub fn step(&mut self, time_scaling : f32) {
+        if self.steps_to_next_gust <= 0 {
+            self.target_gust = 2.0f32 * self.next_unit() - 1.0f32;
+            self.steps_to_next_gust =
+                WIND_GUST_MIN_STEPS + (self.next_random() % WIND_GUST_STEP_RANGE) as i32;
+        }
+
+        self.steps_to_next_gust -= 1;
+        self.gust += (self.target_gust - self.gust) * WIND_GUST_RESPONSE * time_scaling;
+    }
+
+    // horizontal acceleration of the ball, positive to the right
+    pub fn get_force(&self, params : &PhysicsParams) -> f32 {
+        params.wind_force + params.wind_gust_force * self.gust
+    }
+
+    pub fn add_to_hash(&self, hasher : &mut StateHasher) {
+        hasher.write_bytes(&self.random_state.to_le_bytes());
+        hasher.write_f32(self.gust);
+        hasher.write_f32(self.target_gust);
+        hasher.write_i32(self.steps_to_next_gust);
+    }
+}
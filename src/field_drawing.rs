@@ -0,0 +1,123 @@
+use physic_world::PhysicWorld;
+
+use global::PlayerSide::*;
+use game_constants::*;
+
+use quicksilver::{
+    Result,
+    geom::{Shape, Transform, Vector},
+    graphics::{Background::Img},
+    lifecycle::Window,
+};
+
+use state_manager::GamesAssets;
+
+// Drawing of the modes played on the classic field without a match: the
+// positions of the world are those of the background
+
+pub fn draw_background(window : &mut Window, game_assets : &mut GamesAssets) -> Result<()> {
+    let transform =
+        Transform::IDENTITY *
+        Transform::scale(
+            Vector::new(
+                DISPLAY_SCALE_FACTOR,
+                DISPLAY_SCALE_FACTOR
+            )
+        );
+
+    game_assets.background_image.execute(|image| {
+        window.draw_ex(
+            &image.area().with_center(
+                (
+                    WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                    WINDOW_HEIGHT as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR
+                )
+            ),
+            Img(&image),
+            transform,
+            0.0f32
+        );
+        Ok(())
+    })
+}
+
+// the blobs, the first ball and its indicator when it is above the screen
+pub fn draw_world(window : &mut Window, game_assets : &mut GamesAssets, world : &PhysicWorld) -> Result<()> {
+    let scale = DISPLAY_SCALE_FACTOR * 2.4f32;
+
+    for &(side, z) in [(LeftPlayer, 2.0f32), (RightPlayer, 3.0f32)].iter() {
+        let blob_pos = world.get_blob(side);
+        let blob_state = (world.get_blob_state(side) as usize) % BLOBBY_ANIMATION_FRAMES;
+        let character_size = world.get_character(side).size;
+        let transform =
+            Transform::scale(
+                Vector::new(
+                    scale * 0.5f32 * character_size,
+                    scale * 0.5f32 * character_size
+                )
+            );
+
+        let blob_images =
+            if side == LeftPlayer {
+                &mut game_assets.blobs_images_left
+            } else {
+                &mut game_assets.blobs_images_right
+            };
+
+        blob_images[blob_state].execute(|image| {
+            window.draw_ex(
+                &image.area().with_center((blob_pos.x * scale, blob_pos.y * scale)),
+                Img(&image),
+                transform,
+                z
+            );
+
+            Ok(())
+        })?;
+    }
+
+    let ball_pos = world.get_ball_position();
+
+    let transform =
+        Transform::scale(Vector::new(scale * 0.5f32, scale * 0.5f32)) *
+        Transform::rotate(world.get_ball_rotation() / std::f32::consts::PI * 180.0f32);
+
+    game_assets.ball_image.execute(|image| {
+        window.draw_ex(
+            &image.area().with_center((ball_pos.x * scale, ball_pos.y * scale)),
+            Img(&image),
+            transform,
+            1.0f32
+        );
+
+        Ok(())
+    })?;
+
+    if ball_pos.y < -BALL_RADIUS {
+        let transform =
+            Transform::scale(
+                Vector::new(
+                    DISPLAY_SCALE_FACTOR * 2.0f32,
+                    DISPLAY_SCALE_FACTOR * 2.0f32
+                )
+            );
+
+        game_assets.ball_indicator.execute(|image| {
+            window.draw_ex(
+                &image.area().with_center(
+                    (
+                        ball_pos.x * scale,
+                        BALL_INDICATOR_HEIGHT as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR * 2.0f32
+                    )
+                ),
+                Img(&image),
+                transform,
+                5.0f32
+            );
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// Floating point functions of the simulation that are not plain IEEE 754
+// operations. Additions, multiplications and divisions of f32 give the same
+// bits everywhere, but the square root is left to the platform: JavaScript
+// engines for instance do not have to round Math.sqrt correctly.
+//
+// With the `deterministic` feature, sqrt is computed with integer operations
+// only. Its result is the correctly rounded one, i.e. the same bits as a
+// conforming f32::sqrt, so native builds with and without the feature agree.
+
+#[cfg(feature = "deterministic")]
+pub fn sqrt(value : f32) -> f32 {
+    soft_sqrt(value)
+}
+
+#[cfg(not(feature = "deterministic"))]
+pub fn sqrt(value : f32) -> f32 {
+    value.sqrt()
+}
+
+const EXPONENT_BIAS : i32 = 127;
+const MANTISSA_BITS : i32 = 23;
+const IMPLICIT_BIT : u32 = 1 << MANTISSA_BITS;
+const MANTISSA_MASK : u32 = IMPLICIT_BIT - 1;
+const CANONICAL_NAN : u32 = 0x7fc0_0000;
+
+// Correctly rounded square root (round to nearest) using integers only
+pub fn soft_sqrt(value : f32) -> f32 {
+    let bits = value.to_bits();
+    let biased_exponent = ((bits >> MANTISSA_BITS) & 0xff) as i32;
+    let mut mantissa = bits & MANTISSA_MASK;
+
+    // NaN, +inf and both zeros are their own square root
+    if biased_exponent == 0xff {
+        if mantissa != 0 || bits >> 31 == 0 {
+            return value;
+        }
+        return f32::from_bits(CANONICAL_NAN);
+    }
+
+    if bits << 1 == 0 {
+        return value;
+    }
+
+    if bits >> 31 != 0 {
+        return f32::from_bits(CANONICAL_NAN);
+    }
+
+    // value = mantissa * 2^(exponent - 23), with the implicit bit set
+    let mut exponent = biased_exponent - EXPONENT_BIAS;
+    if biased_exponent == 0 {
+        // subnormal, normalize it
+        exponent = 1 - EXPONENT_BIAS;
+        while mantissa & IMPLICIT_BIT == 0 {
+            mantissa <<= 1;
+            exponent -= 1;
+        }
+    }
+    else {
+        mantissa |= IMPLICIT_BIT;
+    }
+
+    // the exponent must be even to be halved
+    if exponent & 1 != 0 {
+        mantissa <<= 1;
+        exponent -= 1;
+    }
+
+    // the square root of a 47 or 48 bits integer has exactly 24 bits
+    let radicand = (mantissa as u64) << MANTISSA_BITS;
+    let (mut root, remainder) = integer_sqrt(radicand);
+
+    // the exact root is above root + 0.5 iff radicand > root^2 + root,
+    // and it is never exactly halfway
+    if remainder > root {
+        root += 1;
+    }
+
+    // a carry out of the mantissa lands in the exponent, as it should
+    let result_bits =
+        (((exponent / 2 + EXPONENT_BIAS) as u32) << MANTISSA_BITS) +
+        (root as u32 - IMPLICIT_BIT);
+
+    f32::from_bits(result_bits)
+}
+
+// Floor of the square root and the remainder, digit by digit
+fn integer_sqrt(value : u64) -> (u64, u64) {
+    let mut remainder = value;
+    let mut root = 0u64;
+    let mut bit = 1u64 << 62;
+
+    while bit > remainder {
+        bit >>= 2;
+    }
+
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        }
+        else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    (root, remainder)
+}
@@ -7,6 +7,9 @@ pub const VERTICAL_PLANE_LENGTH : f32 = 600.0;
 // These numbers should include the blobbys width, but in the original game
 // the blobbys can go a bit into the walls too.
 
+// The tuning values below are the defaults of PhysicsParams, the simulation
+// reads them through PhysicWorld's parameters.
+
 // Blobby Settings
 pub const BLOBBY_HEIGHT : f32 = 89f32;
 //pub const BLOBBY_WIDTH : f32 = 75;		// what is the meaning of this value ???????
@@ -23,7 +26,35 @@ pub const BLOBBY_JUMP_ACCELERATION : f32 = 15.1f32;
 
 // Ball Settings
 pub const BALL_RADIUS : f32 = 31.5f32;
+// longest move of the ball between two collision checks, way under the
+// thickness of the net and the blobs, and above the usual speeds (~7)
+pub const MAX_BALL_STEP_DISTANCE : f32 = BALL_RADIUS / 2.0f32;
+// bound on the sub-steps of a step, i.e. a ball faster than about
+// 1000 per step is no longer guaranteed to collide
+pub const MAX_BALL_SUB_STEPS : u32 = 64;
 pub const BALL_GRAVITATION : f32 = 0.287f32;
+
+// Spin model, scaled by PhysicsParams::spin_factor. The grips are the share
+// of the slip of the contact point the friction removes at each contact
+pub const SPIN_BLOB_GRIP : f32 = 0.5f32;
+pub const SPIN_GROUND_GRIP : f32 = 0.4f32;
+pub const SPIN_NET_GRIP : f32 = 0.3f32;
+// sideways acceleration of the ball per unit of spin and of speed
+pub const MAGNUS_COEFFICIENT : f32 = 0.06f32;
+// share of the spin lost per step in the air
+pub const SPIN_AIR_DAMPING : f32 = 0.002f32;
+
+// Environment of the party variants, see environment.rs. The gravity presets
+// scale the gravities of PhysicsParams
+pub const MOON_GRAVITY_SCALE : f32 = 0.6f32;
+pub const HEAVY_GRAVITY_SCALE : f32 = 1.5f32;
+// seed of the wind gusts of a new PhysicWorld
+pub const WIND_DEFAULT_SEED : u32 = 0x9e37_79b9;
+// a gust blows towards a new strength every 250 to 750 steps
+pub const WIND_GUST_MIN_STEPS : i32 = 250;
+pub const WIND_GUST_STEP_RANGE : u32 = 500;
+// share of the way to its target strength a gust goes per step
+pub const WIND_GUST_RESPONSE : f32 = 0.01f32;
 pub const BALL_COLLISION_VELOCITY : f32 = 13.125f32;
 
 // Volley Ball Net
@@ -53,6 +84,13 @@ pub const WINDOW_HEIGHT : i32 = 1440;
 
 pub const BALL_INDICATOR_HEIGHT : i32 = 20;
 
+// steps ahead the landing markers look, a few seconds
+pub const LANDING_PREDICTION_STEPS : usize = 1000;
+
+// steps between a ball landing in training and the next serve of the launcher
+pub const TRAINING_LAUNCH_DELAY : i32 = 250;
+pub const TRAINING_DEFAULT_SEED : u32 = 0x2545_f491;
+
 pub const SCORE_BASELINE_HEIGHT : i32 = 50;
 pub const SCORE_PADDING_X : i32 = 100;
 
@@ -63,6 +101,13 @@ pub const RIGHT_SPAWN_POS_X : i32 = 600;
 
 pub const BALL_MIDDLE_SPAWN_X : i32 = 400;
 pub const BALL_MIDDLE_SPAWN_Y : i32 = 450;
+// the same, above the ground of an Arena
+pub const BALL_MIDDLE_SPAWN_HEIGHT : f32 = 50.0f32;
+
+// height between the balls waiting for the serve in the multi-ball mode
+pub const MULTI_BALL_SPAWN_SPACING : f32 = 80.0f32;
+// more balls would not fit above the field
+pub const MAX_BALL_COUNT : usize = 4;
 
 pub const DAMP_BALL_SCALE_FACTOR : f32 = 0.6;
 
@@ -81,3 +126,10 @@ pub const DISPLAY_SCALE_FACTOR : f32 = 0.4166666666666667f32;
 pub const TIME_SCALING : f32 = 0.35f32;
 
 pub const SCORE_TO_WIN : i32 = 15;
+
+// Simulation rate: DuelMatch steps per second of play, whatever the frame
+// rate, see TickClock
+pub const STEPS_PER_SECOND : i32 = 250;
+// steps run at most for one frame, the frontend drops the time beyond
+// instead of catching up (100 ms)
+pub const MAX_STEPS_PER_FRAME : u32 = 25;
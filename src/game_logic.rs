@@ -1,22 +1,54 @@
 use global::PlayerSide;
 use global::PlayerSide::*;
-use game_constants::*;
+use match_rules::{Handicap, MatchRules, ScoringMode};
+use state_hash::StateHasher;
+
+// Why a side lost a rally
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorReason {
+    // the ball touched the ground on its side of the net
+    BallHitGround,
+    // the side touched the ball more often than the rules allow
+    TooManyTouches,
+}
 
 pub struct GameLogic {
     // this array contains the scores
     scores: [i32; 2],
-    // in this array the number of touches are counted
-    touches_ball_count : [i32; 2],
-    // this is an helper array to prevent counting hits that happen too fast twice
-    squish: [i32; 2],
+    // in this array the number of touches are counted, per ball and side
+    touches_ball_count : Vec<[i32; 2]>,
+    // this is an helper array to prevent counting hits that happen too fast
+    // twice, per ball and side
+    squish: Vec<[i32; 2]>,
     // last side that made an error
     last_error: PlayerSide,
+    // and what the error was
+    last_error_reason: ErrorReason,
     // player that is currently serving
     serving_player: PlayerSide,
     // player that has won the game
     winning_player: PlayerSide,
-    // config parameter: score to win
-    score_to_win : i32
+    // steps of play counted for the time limit of the rules
+    elapsed_time: i32,
+    // config parameter: rules of the game
+    rules : MatchRules,
+    // handicaps of the players on each side, see set_handicaps
+    handicaps : [Handicap; 2],
+}
+
+// Plain copy of every field of a GameLogic but the handicaps, which
+// DuelMatch::restore gives back from the rules and the sides of the players
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameLogicSnapshot {
+    pub scores: [i32; 2],
+    pub touches_ball_count : Vec<[i32; 2]>,
+    pub squish: Vec<[i32; 2]>,
+    pub last_error: PlayerSide,
+    pub last_error_reason: ErrorReason,
+    pub serving_player: PlayerSide,
+    pub winning_player: PlayerSide,
+    pub elapsed_time: i32,
+    pub rules : MatchRules
 }
 
 pub fn side_to_index(side : PlayerSide) -> usize {
@@ -31,10 +63,35 @@ pub fn other_side(side : PlayerSide) -> PlayerSide {
     }
 }
 
+impl GameLogicSnapshot {
+    // Adds every field but the rules to the hash, see DuelMatch::get_state_hash
+    pub fn add_to_hash(&self, hasher : &mut StateHasher) {
+        for index in 0..2 {
+            hasher.write_i32(self.scores[index]);
+        }
+
+        hasher.write_i32(self.touches_ball_count.len() as i32);
+        for (touches, squish) in self.touches_ball_count.iter().zip(self.squish.iter()) {
+            for index in 0..2 {
+                hasher.write_i32(touches[index]);
+                hasher.write_i32(squish[index]);
+            }
+        }
+
+        hasher.write_i32(self.last_error as i32);
+        hasher.write_i32(self.last_error_reason as i32);
+        hasher.write_i32(self.serving_player as i32);
+        hasher.write_i32(self.winning_player as i32);
+        hasher.write_i32(self.elapsed_time);
+    }
+}
+
 impl GameLogic {
     pub fn step(&mut self) {
-        self.squish[0] = self.squish[0] - 1;
-        self.squish[1] = self.squish[1] - 1;
+        for squish in self.squish.iter_mut() {
+            squish[0] = squish[0] - 1;
+            squish[1] = squish[1] - 1;
+        }
     }
 
     pub fn get_last_error_side(&mut self) -> PlayerSide {
@@ -43,19 +100,29 @@ impl GameLogic {
         returned
     }
 
+    // reason of the error returned by get_last_error_side, to read before it
+    pub fn get_last_error_reason(&self) -> ErrorReason {
+        self.last_error_reason
+    }
+
     pub fn get_winning_player(&self) -> PlayerSide {
         self.winning_player
     }
 
-    pub fn new() -> GameLogic {
+    pub fn new(rules : MatchRules) -> GameLogic {
+        let ball_count = rules.get_ball_count();
+
         let mut game_logic = GameLogic {
             scores: [0i32; 2],
-            touches_ball_count : [0i32; 2],
-            squish: [0i32; 2],
+            touches_ball_count : vec!([0i32; 2]; ball_count),
+            squish: vec!([0i32; 2]; ball_count),
             last_error: NoPlayer,
-            serving_player: LeftPlayer,
+            last_error_reason: ErrorReason::BallHitGround,
+            serving_player: rules.first_serving_player,
             winning_player: NoPlayer,
-            score_to_win : SCORE_TO_WIN,
+            elapsed_time: 0,
+            rules : rules,
+            handicaps : rules.handicaps,
         };
 
 
@@ -63,71 +130,197 @@ impl GameLogic {
         game_logic
     }
 
-    pub fn is_collision_valid(&self, side : PlayerSide) -> bool {
-        self.squish[side as usize] < 0
+    pub fn snapshot(&self) -> GameLogicSnapshot {
+        GameLogicSnapshot {
+            scores: self.scores,
+            touches_ball_count : self.touches_ball_count.clone(),
+            squish: self.squish.clone(),
+            last_error: self.last_error,
+            last_error_reason: self.last_error_reason,
+            serving_player: self.serving_player,
+            winning_player: self.winning_player,
+            elapsed_time: self.elapsed_time,
+            rules : self.rules,
+        }
+    }
+
+    // only complete with the handicaps of DuelMatch::restore
+    pub(crate) fn restore(&mut self, snapshot : &GameLogicSnapshot) {
+        self.scores = snapshot.scores;
+        self.touches_ball_count = snapshot.touches_ball_count.clone();
+        self.squish = snapshot.squish.clone();
+        self.last_error = snapshot.last_error;
+        self.last_error_reason = snapshot.last_error_reason;
+        self.serving_player = snapshot.serving_player;
+        self.winning_player = snapshot.winning_player;
+        self.elapsed_time = snapshot.elapsed_time;
+        self.rules = snapshot.rules;
+    }
+
+    pub fn is_collision_valid(&self, side : PlayerSide, ball : usize) -> bool {
+        self.squish[ball][side as usize] < 0
     }
 
     pub fn reset(&mut self) {
-        self.scores[0] = 0;
-        self.scores[1] = 0;
+        self.scores[0] = self.handicaps[0].head_start;
+        self.scores[1] = self.handicaps[1].head_start;
+
+        for touches in self.touches_ball_count.iter_mut() {
+            *touches = [0; 2];
+        }
 
-        self.touches_ball_count[0] = 0;
-        self.touches_ball_count[1] = 0;
+        for squish in self.squish.iter_mut() {
+            *squish = [0; 2];
+        }
+
+        self.elapsed_time = 0;
+    }
 
-        self.squish[0] = 0;
-        self.squish[1] = 0;
+    // Counts a step of play towards the time limit, the caller stops the
+    // clock while waiting for the serve and between rallies
+    pub fn step_clock(&mut self) {
+        if self.rules.time_limit.is_some() && !self.is_time_up() {
+            self.elapsed_time = self.elapsed_time + 1;
+        }
+    }
+
+    // steps of play left before the time limit, None without time limit
+    pub fn get_remaining_time(&self) -> Option<i32> {
+        self.rules.time_limit.map(|time_limit| time_limit - self.elapsed_time)
+    }
+
+    pub fn is_time_up(&self) -> bool {
+        self.get_remaining_time() == Some(0)
+    }
+
+    // Starts a new game (a new set) with the same rules
+    pub fn start_new_game(&mut self, serving_player : PlayerSide) {
+        self.reset();
+        self.last_error = NoPlayer;
+        self.winning_player = NoPlayer;
+        self.serving_player = serving_player;
+    }
+
+    pub fn get_rules(&self) -> MatchRules {
+        self.rules
+    }
+
+    // The handicaps of the rules are given per player, they start on the
+    // sides of the same index. The owner of the game logic moves them when
+    // the players swap sides, before starting the next game.
+    pub fn set_handicaps(&mut self, handicaps : [Handicap; 2]) {
+        self.handicaps = handicaps;
+    }
+
+    pub fn get_handicap(&self, side : PlayerSide) -> Handicap {
+        self.handicaps[side_to_index(side)]
+    }
+
+    // touches the side is allowed on a ball
+    pub fn get_max_touch_count(&self, side : PlayerSide) -> i32 {
+        (self.rules.max_ball_touch_count - self.handicaps[side_to_index(side)].touch_penalty).max(1)
     }
 
     pub fn get_serving_player(&self) -> PlayerSide {
         self.serving_player
     }
 
-    pub fn on_ball_hits_ground(&mut self, side : PlayerSide) {
-        self.on_error(side);
+    pub fn on_ball_hits_ground(&mut self, side : PlayerSide, ball : usize) {
+        self.on_error(side, ErrorReason::BallHitGround, ball);
     }
 
-    pub fn on_error(&mut self, side : PlayerSide) {
+    // An error of the side with the given ball, each ball scores on its own
+    pub fn on_error(&mut self, side : PlayerSide, reason : ErrorReason, ball : usize) {
         self.last_error = side;
+        self.last_error_reason = reason;
+
+        self.touches_ball_count[ball] = [0; 2];
+        self.squish[ball] = [0; 2];
+
+        // in practice the error only ends the rally, and once the game is
+        // decided the balls still in play do not score any more
+        if self.rules.practice || self.winning_player != NoPlayer {
+            return;
+        }
+
+        let rally_winner = other_side(side);
 
-        self.touches_ball_count[0] = 0;
-        self.touches_ball_count[1] = 0;
-        self.squish[0] = 0;
-        self.squish[1] = 0;
+        // with side-out scoring, the receiver only wins the serve
+        let scores_point =
+            self.rules.scoring_mode == ScoringMode::RallyScoring ||
+            self.serving_player == rally_winner;
+
+        if scores_point {
+            self.scores[side_to_index(rally_winner)] =
+                self.scores[side_to_index(rally_winner)] + 1;
+        }
 
-        self.scores[side_to_index(other_side(side))] =
-            self.scores[side_to_index(other_side(side))] + 1;
+        self.serving_player = rally_winner;
 
-        self.serving_player = other_side(side);
+        let winner_score = self.scores[side_to_index(rally_winner)];
+        let loser_score = self.scores[side_to_index(side)];
+
+        if scores_point && self.rules.is_winning_score(winner_score, loser_score) {
+            self.winning_player = rally_winner;
+        }
+        else if self.is_time_up() {
+            // the rally the time ran out in is the last one, unless the
+            // scores are even: then the next point wins
+            self.winning_player = self.get_leading_side();
+        }
+    }
 
-        if self.scores[side_to_index(other_side(side))] >= self.score_to_win {
-            self.winning_player = other_side(side);
+    // side with the higher score, NoPlayer on a tie
+    fn get_leading_side(&self) -> PlayerSide {
+        if self.scores[0] > self.scores[1] {
+            LeftPlayer
+        }
+        else if self.scores[1] > self.scores[0] {
+            RightPlayer
+        }
+        else {
+            NoPlayer
         }
     }
 
-    pub fn on_ball_hits_player(&mut self, side : PlayerSide) -> bool {
+    pub fn on_ball_hits_player(&mut self, side : PlayerSide, ball : usize) -> bool {
 
-        if !self.is_collision_valid(side) {
+        if !self.is_collision_valid(side, ball) {
             return false;
         }
 
         // otherwise, set the squish value
-        self.squish[side_to_index(side)] = SQUISH_TOLERANCE;
+        self.squish[ball][side_to_index(side)] = self.rules.squish_tolerance;
 
         // count the touches
-        self.touches_ball_count[side_to_index(other_side(side))] = 0;
+        let max_touch_count = self.get_max_touch_count(side);
+        let touches = &mut self.touches_ball_count[ball];
+        touches[side_to_index(other_side(side))] = 0;
 
-        self.touches_ball_count[side_to_index(side)] =
-            self.touches_ball_count[side_to_index(side)] + 1;
+        touches[side_to_index(side)] = touches[side_to_index(side)] + 1;
 
-        if self.touches_ball_count[side_to_index(side)] > MAX_BALL_TOUCH_COUNT
+        if
+            !self.rules.infinite_touches &&
+            touches[side_to_index(side)] > max_touch_count
         {
             // if a player hits a forth time, it is an error
-            self.on_error(side);
+            self.on_error(side, ErrorReason::TooManyTouches, ball);
         }
 
         true
     }
 
+    // Forgets the touches on the ball, when it is put back for a new rally
+    pub fn clear_touches(&mut self, ball : usize) {
+        self.touches_ball_count[ball] = [0; 2];
+        self.squish[ball] = [0; 2];
+    }
+
+    // touches of the side on the ball since it last crossed the net
+    pub fn get_touch_count(&self, side : PlayerSide, ball : usize) -> i32 {
+        self.touches_ball_count[ball][side_to_index(side)]
+    }
+
     pub fn get_scores(&self) -> (i32, i32) {
         (self.scores[0], self.scores[1])
     }
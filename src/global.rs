@@ -1,6 +1,23 @@
+use std::fmt;
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum PlayerSide {
     NoPlayer = -1isize,
     LeftPlayer = 0isize,
     RightPlayer = 1isize
-}
\ No newline at end of file
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PlayerKind {
+    Human,
+    Computer
+}
+
+impl fmt::Display for PlayerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerKind::Human => write!(f, "Human"),
+            PlayerKind::Computer => write!(f, "Computer")
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use duel_match::{DuelMatch, FrameEvent};
+use global::PlayerSide::*;
+use match_rules::MatchRules;
+use physic_world::PhysicWorld;
+use physics_params::PhysicsParams;
+use player_input::PlayerInput;
+
+// file next to the game holding the best juggling score
+pub const JUGGLING_BEST_FILE : &str = "juggling_best.txt";
+
+// Single-player challenge: the left player keeps the ball in the air on their
+// own for as many touches as possible. It counts the valid hits of the left
+// blob, without touch limit, and is over once the ball touches the ground or
+// crosses the net.
+pub struct JugglingChallenge {
+    duel_match : DuelMatch,
+    touch_count : i32,
+    is_over : bool,
+}
+
+impl JugglingChallenge {
+    pub fn new(params : PhysicsParams) -> JugglingChallenge {
+        let rules = MatchRules {
+            practice : true,
+            infinite_touches : true,
+            first_serving_player : LeftPlayer,
+            ..MatchRules::default()
+        };
+
+        JugglingChallenge {
+            duel_match : DuelMatch::new(params, rules),
+            touch_count : 0,
+            is_over : false,
+        }
+    }
+
+    // The ball waits above the blob until its first touch. Once the challenge
+    // is over, the steps go on without counting anything.
+    pub fn step(&mut self, input : PlayerInput, events : &mut Vec<FrameEvent>) {
+        let first_event = events.len();
+
+        self.duel_match.get_world().set_player_input(LeftPlayer, input);
+        self.duel_match.step(events);
+
+        if self.is_over {
+            return;
+        }
+
+        for event in events[first_event..].iter() {
+            match *event {
+                FrameEvent::EventBlobbyHit { side : LeftPlayer, .. } => self.touch_count += 1,
+                FrameEvent::EventBallHitGround { .. } => self.is_over = true,
+                _ => (),
+            }
+        }
+
+        let net_position_x = self.duel_match.get_physics_params().arena.net_position_x;
+        if self.duel_match.get_ball_position().x >= net_position_x {
+            self.is_over = true;
+        }
+    }
+
+    pub fn get_touch_count(&self) -> i32 {
+        self.touch_count
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_over
+    }
+
+    pub fn get_world(&mut self) -> &PhysicWorld {
+        self.duel_match.get_world()
+    }
+}
+
+// Best touch count saved in the file, 0 when it is missing or unreadable
+pub fn load_best_touches(path : &Path) -> i32 {
+    fs::read_to_string(path).ok()
+        .and_then(|text| text.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn save_best_touches(path : &Path, touch_count : i32) -> io::Result<()> {
+    fs::write(path, format!("{}\n", touch_count))
+}
@@ -0,0 +1,122 @@
+use quicksilver::{
+    Result,
+    geom::{Shape, Transform, Vector},
+    graphics::{Background::Img, Color, Image},
+    lifecycle::{Window, Event,},
+};
+
+use quicksilver::input::*;
+
+use state_manager::{
+    *,
+    RustyGameState::*,
+    StateTransition::*
+};
+
+use field_drawing::draw_background;
+use game_constants::*;
+
+// Touches of the last juggling challenge and how they compare to the best
+pub struct JugglingResultState {
+    line_images : Vec<Image>,
+    touch_count : i32,
+    previous_best : i32,
+    // why a new best could not be saved
+    save_error : Option<String>,
+}
+
+impl JugglingResultState {
+    pub fn new() -> JugglingResultState {
+        JugglingResultState {
+            line_images : vec!(),
+            touch_count : 0,
+            previous_best : 0,
+            save_error : None,
+        }
+    }
+
+    pub fn set_result(&mut self, touch_count : i32, previous_best : i32, save_error : Option<String>) {
+        self.touch_count = touch_count;
+        self.previous_best = previous_best;
+        self.save_error = save_error;
+        self.line_images = vec!();
+    }
+
+    fn line_texts(&self) -> Vec<String> {
+        let best_text =
+            match self.save_error {
+                Some(ref error) => format!("New best, not saved: {}", error),
+                None if self.touch_count > self.previous_best => "New best!".to_string(),
+                None => format!("Best: {} touches", self.previous_best),
+            };
+
+        vec!(
+            format!("{} touches", self.touch_count),
+            best_text,
+            "Return to try again, Escape for the menu".to_string(),
+        )
+    }
+}
+
+impl RustyVollyState for JugglingResultState {
+    fn step(&mut self, _game_assets: &mut GamesAssets) -> StateTransition {
+        NoTransition
+    }
+
+    fn draw_window_content(&mut self, window: &mut Window, game_assets: &mut GamesAssets) -> Result<()> {
+        window.clear(Color::WHITE)?;
+
+        draw_background(window, game_assets)?;
+
+        // draw text
+        {
+            let transform =
+                Transform::scale(
+                    Vector::new(
+                        DISPLAY_SCALE_FACTOR * 1.6f32,
+                        DISPLAY_SCALE_FACTOR * 1.6f32
+                    )
+                );
+
+            let line_texts = self.line_texts();
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                if self.line_images.is_empty() {
+                    for text in line_texts.iter() {
+                        self.line_images.push(a_font.render(text, &game_assets.font_style).unwrap());
+                    }
+                }
+
+                for (line_index, image) in self.line_images.iter().enumerate() {
+                    window.draw_ex(
+                        &image.area().with_center(
+                            (
+                                WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                                (WINDOW_HEIGHT as f32 / 2.0f32 + 150.0f32 * line_index as f32) * DISPLAY_SCALE_FACTOR
+                            )
+                        ),
+                        Img(&image),
+                        transform,
+                        4.0f32
+                    );
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event, _window: &mut Window) -> StateTransition {
+        match *event {
+            Event::Key(Key::Return, ButtonState::Pressed) => StateTransition::RetryJugglingTransition,
+            Event::Key(Key::Space, ButtonState::Pressed) => StateTransition::RetryJugglingTransition,
+            Event::Key(Key::Escape, ButtonState::Pressed) => StateTransition::StateLessTransition(NewGameMenu),
+            _ => NoTransition,
+        }
+    }
+}
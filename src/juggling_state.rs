@@ -0,0 +1,213 @@
+use juggling::JugglingChallenge;
+#[cfg(not(target_arch = "wasm32"))]
+use juggling::{JUGGLING_BEST_FILE, load_best_touches, save_best_touches};
+use duel_match::FrameEvent;
+use physics_params::PhysicsParams;
+use player_input::PlayerInput;
+use new_game_menu_state::GameConfiguration;
+use field_drawing::{draw_background, draw_world};
+
+use game_constants::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use quicksilver::{
+    Result,
+    geom::{Shape, Transform, Vector},
+    graphics::{Background::Img, Color, Image},
+    input::{*},
+    lifecycle::{Window, Event},
+};
+
+use state_manager::*;
+use state_manager::StateTransition::*;
+
+use self::RustyGameState::*;
+
+// the best score is kept in a file next to the game, only for the session
+// on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn load_best(_session_best : i32) -> i32 {
+    load_best_touches(Path::new(JUGGLING_BEST_FILE))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_best(touch_count : i32) -> Option<String> {
+    save_best_touches(Path::new(JUGGLING_BEST_FILE), touch_count)
+        .err()
+        .map(|error| error.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn load_best(session_best : i32) -> i32 {
+    session_best
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_best(_touch_count : i32) -> Option<String> {
+    None
+}
+
+// Player 1 keeps the ball in the air as long as they can, on the classic
+// field. The result screen follows once the ball touches the ground or
+// crosses the net.
+pub struct JugglingState {
+    challenge : JugglingChallenge,
+    params : PhysicsParams,
+    input : PlayerInput,
+    events : Vec<FrameEvent>,
+    best_touches : i32,
+    hud_text : String,
+    hud_texture : Option<Image>,
+}
+
+impl JugglingState {
+    pub fn new() -> JugglingState {
+        JugglingState {
+            challenge : JugglingChallenge::new(PhysicsParams::default()),
+            params : PhysicsParams::default(),
+            input : PlayerInput::new(),
+            events : vec!(),
+            best_touches : load_best(0),
+            hud_text : String::new(),
+            hud_texture : None,
+        }
+    }
+
+    // a challenge for everybody: classic physics, only the character of
+    // player 1 is kept
+    pub fn set_config(&mut self, config : GameConfiguration) {
+        self.params = PhysicsParams {
+            characters : [config.physics_params.characters[0], PhysicsParams::default().characters[1]],
+            ..PhysicsParams::default()
+        };
+        self.restart();
+    }
+
+    pub fn restart(&mut self) {
+        self.challenge = JugglingChallenge::new(self.params);
+        self.input = PlayerInput::new();
+        self.best_touches = load_best(self.best_touches);
+    }
+
+    // Saves a new best, returns the previous one and why it could not be
+    // saved if so
+    fn record_touches(&mut self) -> (i32, Option<String>) {
+        let previous_best = self.best_touches;
+        let touch_count = self.challenge.get_touch_count();
+
+        if touch_count > previous_best {
+            self.best_touches = touch_count;
+            return (previous_best, save_best(touch_count));
+        }
+
+        (previous_best, None)
+    }
+}
+
+impl RustyVollyState for JugglingState {
+    fn step(&mut self, game_assets: &mut GamesAssets) -> StateTransition {
+        self.events.clear();
+        self.challenge.step(self.input, &mut self.events);
+
+        if self.events.iter().any( |x|
+            match *x {
+                FrameEvent::EventBlobbyHit { .. } => true,
+                _ => false,
+            }
+        ) {
+            let _ = game_assets.sounds[0].execute(|sound| {
+                sound.set_volume(10.0f32);
+                let _ = sound.play()?;
+                Ok(())
+            });
+        }
+
+        if self.challenge.is_over() {
+            let _ = game_assets.sounds[1].execute(|sound| {
+                sound.set_volume(1.0f32);
+                let _ = sound.play()?;
+                Ok(())
+            });
+
+            let (previous_best, save_error) = self.record_touches();
+            return StateTransition::JugglingOverTransition(self.challenge.get_touch_count(), previous_best, save_error);
+        }
+
+        NoTransition
+    }
+
+    fn draw_window_content(&mut self, window: &mut Window, game_assets: &mut GamesAssets) -> Result<()> {
+        window.clear(Color::WHITE)?;
+
+        draw_background(window, game_assets)?;
+        draw_world(window, game_assets, self.challenge.get_world())?;
+
+        // draw the touches and the best
+        {
+            let transform =
+                Transform::scale(
+                    Vector::new(
+                        DISPLAY_SCALE_FACTOR * 1.6f32,
+                        DISPLAY_SCALE_FACTOR * 1.6f32
+                    )
+                );
+
+            let hud_text = format!("{} touches, best {}", self.challenge.get_touch_count(), self.best_touches);
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                if self.hud_texture.is_none() || self.hud_text != hud_text {
+                    self.hud_texture = Some(a_font.render(&hud_text, &game_assets.font_style).unwrap());
+                    self.hud_text = hud_text;
+                }
+
+                match self.hud_texture {
+                    None => (),
+                    Some(ref image) => {
+                        window.draw_ex(
+                            &image.area().with_center(
+                                (
+                                    WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                                    SCORE_BASELINE_HEIGHT as f32 * DISPLAY_SCALE_FACTOR
+                                )
+                            ),
+                            Img(&image),
+                            transform,
+                            4.0f32
+                        );
+                    }
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event, _window: &mut Window) -> StateTransition {
+        if let &Event::Key(key, state) = event {
+            let pressed =
+                match state {
+                    ButtonState::Pressed => true,
+                    ButtonState::Released => false,
+                    _ => return NoTransition,
+                };
+
+            // W, A and D like in a match, the arrows work too
+            match key {
+                Key::W | Key::Up => self.input.up = pressed,
+                Key::A | Key::Left => self.input.left = pressed,
+                Key::D | Key::Right => self.input.right = pressed,
+                Key::Escape if pressed => return StateTransition::StateLessTransition(NewGameMenu),
+                _ => (),
+            }
+        }
+
+        NoTransition
+    }
+}
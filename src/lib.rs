@@ -0,0 +1,43 @@
+// Headless simulation core of RustyVolley.
+//
+// Everything in here runs without a window, audio or GPU, so tools, tests
+// and servers can drive a `DuelMatch` directly. The quicksilver frontend
+// lives in the binary and is only built with the `frontend` feature.
+
+#![allow(
+    clippy::needless_return,
+    clippy::redundant_field_names,
+    clippy::assign_op_pattern,
+    clippy::collapsible_if,
+    clippy::new_without_default,
+    clippy::manual_unwrap_or,
+    clippy::if_same_then_else,
+    clippy::excessive_precision,
+    clippy::legacy_numeric_constants,
+    clippy::clone_on_copy
+)]
+
+pub mod duel_match;
+pub mod game_logic;
+pub mod match_logic;
+pub mod match_rules;
+pub mod physic_world;
+pub mod ball;
+pub mod ball_prediction;
+pub mod physics_params;
+pub mod environment;
+pub mod arena;
+pub mod character;
+pub mod game_constants;
+pub mod vector;
+pub mod global;
+pub mod player_input;
+pub mod simple_bot;
+pub mod juggling;
+pub mod training;
+pub mod tournament;
+pub mod replay;
+pub mod replay_file;
+pub mod tick_clock;
+pub mod float_math;
+pub mod state_hash;
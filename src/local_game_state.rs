@@ -1,15 +1,29 @@
+extern crate rand;
+
 use duel_match::DuelMatch;
 use duel_match::FrameEvent;
+use physics_params::PhysicsParams;
+use arena::{Arena, ObstacleShape};
+use match_rules::MatchRules;
+use replay::{Replay, ReplayPlayer};
+#[cfg(not(target_arch = "wasm32"))]
+use replay_file::{save_replay, REPLAY_FILE_EXTENSION};
+use vector::Vector2f;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use global::PlayerSide;
 use global::PlayerSide::*;
 use game_constants::*;
 use simple_bot::*;
 use new_game_menu_state::GameConfiguration;
-use new_game_menu_state::PlayerKind::Computer;
+use global::PlayerKind;
+use global::PlayerKind::*;
 
 use quicksilver::{
     Result,
-    geom::{Shape, Transform, Vector},
-    graphics::{Background::Img, Color, Image},
+    geom::{Circle, Line, Rectangle, Shape, Transform, Vector},
+    graphics::{Background::{Col, Img}, Color, Image},
     input::{*},
     lifecycle::{Window, Event},
 };
@@ -20,8 +34,15 @@ use state_manager::StateTransition::*;
 pub struct Scoring {
     score1: i32,
     score2: i32,
+    sets1: i32,
+    sets2: i32,
     score1_texture : Option<Image>,
     score2_texture : Option<Image>,
+    clock_text : String,
+    clock_texture : Option<Image>,
+    // handicaps of the players on each side, below their score
+    handicap_texts : [String; 2],
+    handicap_textures : [Option<Image>; 2],
 }
 
 impl Scoring {
@@ -29,8 +50,14 @@ impl Scoring {
         Scoring {
             score1: -1,
             score2: -1,
+            sets1: -1,
+            sets2: -1,
             score1_texture: None,
             score2_texture: None,
+            clock_text: String::new(),
+            clock_texture: None,
+            handicap_texts: [String::new(), String::new()],
+            handicap_textures: [None, None],
         }
     }
 }
@@ -44,93 +71,352 @@ pub struct LocalGameState {
     bot_left : SimpleBot,
     use_bot_right : bool,
     use_bot_left : bool,
+    // difficulty of the bots, by player
+    bot_difficulties : [i32; 2],
+    physics_params : PhysicsParams,
+    rules : MatchRules,
+    last_replay : Option<Replay>,
+    replay_player : Option<ReplayPlayer>,
+    // positions before the last step, drawn positions are interpolated
+    // from them to the current ones
+    previous_ball_positions : Vec<Vector2f>,
+    previous_blob_positions : [Vector2f; 2],
+    interpolation : f32,
+    // marks where the balls will land, toggled with L
+    show_landing_markers : bool,
 }
 
 impl LocalGameState {
 
     pub fn new() -> LocalGameState {
+        let physics_params = PhysicsParams::default();
+        let rules = MatchRules::default();
+        let mut duel_match = DuelMatch::new(physics_params, rules);
+        duel_match.start_recording();
+
+        let ball_positions = duel_match.get_balls().iter().map(|ball| ball.position).collect();
+        let blob_positions = duel_match.get_world().get_blob_positions();
+
         LocalGameState {
-            duel_match: DuelMatch::new(),
+            duel_match: duel_match,
             frame_events: vec!(),
             frame_number: 0,
             scoring: Scoring::new(),
-            bot_left: SimpleBot::new(LeftPlayer, 0),
-            bot_right: SimpleBot::new(RightPlayer, 0),
+            bot_left: SimpleBot::new(LeftPlayer, 0, physics_params),
+            bot_right: SimpleBot::new(RightPlayer, 0, physics_params),
             use_bot_right : false,
             use_bot_left : false,
+            bot_difficulties : [0; 2],
+            physics_params : physics_params,
+            rules : rules,
+            last_replay : None,
+            replay_player : None,
+            previous_ball_positions : ball_positions,
+            previous_blob_positions : blob_positions,
+            interpolation : 0.0f32,
+            show_landing_markers : false,
         }
     }
 
     pub fn reset(&mut self) {
-        self.duel_match = DuelMatch::new();
+        // a watched replay must not overwrite the recording of the last match
+        if self.replay_player.take().is_none() {
+            // practices are not recorded and keep the last match
+            if let Some(replay) = self.duel_match.stop_recording() {
+                self.last_replay = Some(replay);
+            }
+        }
+        self.start_new_match();
+    }
+
+    fn start_new_match(&mut self) {
+        self.duel_match = DuelMatch::new(self.physics_params, self.rules);
+        self.duel_match.get_world().seed_wind(rand::random());
+
+        // the rallies restarted at will in practice cannot be replayed
+        if !self.rules.practice {
+            let player_kinds = self.get_player_kinds();
+            self.duel_match.start_recording_players(player_kinds, self.bot_difficulties);
+        }
         self.frame_events = vec!();
         self.frame_number = 0;
         self.scoring = Scoring::new();
+        self.save_previous_positions();
     }
 
-    pub fn set_config(&mut self, config: GameConfiguration) {
-        self.use_bot_left = config.player1_configuration == Computer;
-        self.use_bot_right = config.player2_configuration == Computer;
+    fn save_previous_positions(&mut self) {
+        self.previous_ball_positions =
+            self.duel_match.get_balls().iter().map(|ball| ball.position).collect();
+        self.previous_blob_positions = self.duel_match.get_world().get_blob_positions();
     }
 
-    pub fn step(&mut self, game_assets: &mut GamesAssets) -> StateTransition {
-        self.frame_events.clear();
+    // Fraction of a step elapsed since the last one, see TickClock
+    pub fn set_interpolation(&mut self, interpolation : f32) {
+        self.interpolation = interpolation;
+    }
 
-        if self.use_bot_right {
+    fn interpolate(&self, previous : Vector2f, current : Vector2f) -> Vector2f {
+        previous + (current - previous) * self.interpolation
+    }
 
-            let bot_data = CurrentGameState {
-                blob_positions : self.duel_match.get_world().get_blob_positions(),
-                blob_velocities : self.duel_match.get_world().get_blob_velocities(),
-                is_game_running : self.duel_match.get_world().is_game_running(),
-                is_ball_valid : self.duel_match.get_world().is_ball_valid(),
-                serving_player : self.duel_match.get_serving_player()
-            };
+    // Scale of the drawing of the arena, 1 unless it is wider than the
+    // background and has to be shrunk to fit
+    fn get_view_scale(&self) -> f32 {
+        let arena = self.duel_match.get_physics_params().arena;
+        (FIELD_WIDTH / arena.get_width()).min(1.0f32)
+    }
 
-            self.bot_right.step
-            (
-                bot_data,
-                self.duel_match.get_world().get_ball_position(),
-                self.duel_match.get_world().get_ball_velocity()
-            );
+    // Position on the background of a point of the arena: its middle above
+    // the middle of the background and its ground on the drawn ground
+    fn to_screen(&self, position : Vector2f) -> Vector2f {
+        let arena = self.duel_match.get_physics_params().arena;
+        let view_scale = self.get_view_scale();
+        let arena_middle = (arena.left_wall + arena.right_wall) / 2.0f32;
+
+        Vector2f::new(
+            FIELD_MIDDLE + (position.x - arena_middle) * view_scale,
+            GROUND_PLANE_HEIGHT_MAX + (position.y - arena.ground_height) * view_scale
+        )
+    }
 
-            self.duel_match.get_world().set_player_input(
-                RightPlayer, 
-                self.bot_right.compute_input()
-            );
+    // The background shows the classic field, other arenas draw their net,
+    // walls and ceiling over it
+    fn draw_arena(&self, window : &mut Window) -> Result<()> {
+        let arena = self.duel_match.get_physics_params().arena;
 
-            self.bot_right.reset_input();
+        if arena == Arena::default() {
+            return Ok(());
         }
 
-        if self.use_bot_left {
+        let scale = DISPLAY_SCALE_FACTOR * 2.4f32;
+        let to_window = |position : Vector2f| {
+            let screen_position = self.to_screen(position);
+            Vector::new(screen_position.x * scale, screen_position.y * scale)
+        };
+        let arena_color = Color { r : 0.3f32, g : 0.3f32, b : 0.35f32, a : 0.9f32 };
+
+        // the net, from its top sphere down to the ground
+        let net_top_left =
+            to_window(Vector2f::new(arena.net_position_x - arena.net_radius, arena.net_sphere_position));
+        let net_bottom_right =
+            to_window(Vector2f::new(arena.net_position_x + arena.net_radius, arena.ground_height));
+        window.draw_ex(
+            &Rectangle::new(net_top_left, net_bottom_right - net_top_left),
+            Col(arena_color),
+            Transform::IDENTITY,
+            1.5f32
+        );
+        window.draw_ex(
+            &Circle::new(
+                to_window(Vector2f::new(arena.net_position_x, arena.net_sphere_position)),
+                arena.net_radius * self.get_view_scale() * scale
+            ),
+            Col(arena_color),
+            Transform::IDENTITY,
+            1.5f32
+        );
+
+        // everything beyond the walls and above the ceiling
+        let window_size = Vector::new(WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32) * DISPLAY_SCALE_FACTOR;
+        let left_wall = to_window(Vector2f::new(arena.left_wall, 0.0f32)).x;
+        let right_wall = to_window(Vector2f::new(arena.right_wall, 0.0f32)).x;
+        let mut outside_areas = vec!(
+            Rectangle::new((0.0f32, 0.0f32), (left_wall, window_size.y)),
+            Rectangle::new((right_wall, 0.0f32), (window_size.x - right_wall, window_size.y)),
+        );
+        if let Some(ceiling) = arena.ceiling {
+            let ceiling_y = to_window(Vector2f::new(0.0f32, ceiling)).y;
+            outside_areas.push(Rectangle::new((0.0f32, 0.0f32), (window_size.x, ceiling_y)));
+        }
 
-            let bot_data = CurrentGameState {
-                blob_positions : self.duel_match.get_world().get_blob_positions(),
-                blob_velocities : self.duel_match.get_world().get_blob_velocities(),
-                is_game_running : self.duel_match.get_world().is_game_running(),
-                is_ball_valid : self.duel_match.get_world().is_ball_valid(),
-                serving_player : self.duel_match.get_serving_player()
-            };
+        for area in outside_areas.iter().filter(|area| area.width() > 0.0f32 && area.height() > 0.0f32) {
+            window.draw_ex(area, Col(arena_color), Transform::IDENTITY, 1.5f32);
+        }
 
-            self.bot_left.step
-            (
-                bot_data,
-                self.duel_match.get_world().get_ball_position(),
-                self.duel_match.get_world().get_ball_velocity()
-            );
+        // the obstacles, segments being drawn as thin lines
+        for obstacle in arena.get_obstacles() {
+            match obstacle.shape {
+                ObstacleShape::Circle { centre, radius } => window.draw_ex(
+                    &Circle::new(to_window(centre), radius * self.get_view_scale() * scale),
+                    Col(arena_color),
+                    Transform::IDENTITY,
+                    1.5f32
+                ),
+                ObstacleShape::Segment { start, end } => window.draw_ex(
+                    &Line::new(to_window(start), to_window(end))
+                        .with_thickness(4.0f32 * self.get_view_scale() * scale),
+                    Col(arena_color),
+                    Transform::IDENTITY,
+                    1.5f32
+                ),
+            }
+        }
+
+        Ok(())
+    }
 
-            self.duel_match.get_world().set_player_input(
-                LeftPlayer, 
-                self.bot_left.compute_input()
+    // A shadow on the ground where each ball in play will land if nobody
+    // touches it
+    fn draw_landing_markers(&mut self, window : &mut Window) -> Result<()> {
+        if !self.show_landing_markers {
+            return Ok(());
+        }
+
+        let scale = DISPLAY_SCALE_FACTOR * 2.4f32;
+        let radius = BALL_RADIUS * self.get_view_scale() * scale;
+        let marker_color = Color { r : 0.0f32, g : 0.0f32, b : 0.0f32, a : 0.3f32 };
+
+        let world = self.duel_match.get_world();
+        let landing_positions : Vec<Vector2f> =
+            world.get_balls().iter().enumerate()
+                .filter(|(_, ball)| ball.is_valid)
+                .filter_map(|(index, _)| world.predict_ball(index, LANDING_PREDICTION_STEPS).get_landing_position())
+                .collect();
+
+        let ground_height = self.duel_match.get_physics_params().arena.ground_height;
+        for position in landing_positions {
+            let marker_position = self.to_screen(Vector2f::new(position.x, ground_height));
+            window.draw_ex(
+                &Circle::new((marker_position.x * scale, marker_position.y * scale), radius),
+                Col(marker_color),
+                Transform::scale(Vector::new(1.0f32, 0.25f32)),
+                1.5f32
             );
+        }
+
+        Ok(())
+    }
+
+    // Plays back the last finished match instead of the players' inputs.
+    // Returns false if no match has been recorded yet.
+    pub fn watch_last_replay(&mut self) -> bool {
+        let replay = match self.last_replay {
+            Some(ref replay) => replay.clone(),
+            None => return false,
+        };
+
+        let replay_player = ReplayPlayer::new(replay);
+        self.duel_match = replay_player.create_match();
+        self.replay_player = Some(replay_player);
+        self.frame_events = vec!();
+        self.frame_number = 0;
+        self.scoring = Scoring::new();
+        self.save_previous_positions();
+        true
+    }
+
+    // Writes the last finished match next to the game, in the first free
+    // replay_XXX.rvr file. Returns what happened, to show to the players,
+    // None without a finished match
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_last_replay(&self) -> Option<String> {
+        let replay = match self.last_replay {
+            Some(ref replay) => replay,
+            None => return None,
+        };
+
+        let path =
+            (0..1000)
+            .map(|index| PathBuf::from(format!("replay_{:03}.{}", index, REPLAY_FILE_EXTENSION)))
+            .find(|path| !path.exists());
+
+        let message =
+            match path {
+                Some(path) => match save_replay(replay, &path) {
+                    Ok(()) => format!("Replay saved to {}", path.display()),
+                    Err(error) => format!("Cannot save the replay: {}", error),
+                },
+                None => "Cannot save the replay: no free file name".to_string(),
+            };
+
+        Some(message)
+    }
+
+    // there are no files to save to on the web
+    #[cfg(target_arch = "wasm32")]
+    pub fn save_last_replay(&self) -> Option<String> {
+        self.last_replay.as_ref().map(|_| "Replays cannot be saved on the web".to_string())
+    }
+
+    fn get_player_kinds(&self) -> [PlayerKind; 2] {
+        [
+            if self.use_bot_left { Computer } else { Human },
+            if self.use_bot_right { Computer } else { Human },
+        ]
+    }
 
-            self.bot_left.reset_input();
+    pub fn set_config(&mut self, config: GameConfiguration) {
+        self.use_bot_left = config.player1_configuration == Computer;
+        self.use_bot_right = config.player2_configuration == Computer;
+        self.bot_difficulties = config.bot_difficulties;
+        self.rules = config.rules;
+        self.physics_params = config.physics_params;
+        self.bot_left = SimpleBot::new(LeftPlayer, 0, self.physics_params);
+        self.bot_right = SimpleBot::new(RightPlayer, 0, self.physics_params);
+        self.start_new_match();
+    }
+
+    // the bots are bound to a side, while the configuration is per player
+    fn use_bot_on_side(&self, side : PlayerSide) -> bool {
+        match self.duel_match.get_player_on_side(side) {
+            LeftPlayer => self.use_bot_left,
+            RightPlayer => self.use_bot_right,
+            NoPlayer => false,
         }
+    }
 
-        self.duel_match.step(&mut self.frame_events);
+    fn bot_difficulty_on_side(&self, side : PlayerSide) -> i32 {
+        match self.duel_match.get_player_on_side(side) {
+            NoPlayer => 0,
+            player => self.bot_difficulties[player as usize],
+        }
+    }
+
+    // point scores of the finished sets, by player
+    pub fn get_finished_sets(&self) -> Vec<[i32; 2]> {
+        self.duel_match.get_finished_sets().to_vec()
+    }
+
+    fn step_bots(&mut self) {
+        if self.use_bot_on_side(RightPlayer) {
+            let difficulty = self.bot_difficulty_on_side(RightPlayer);
+            self.bot_right.set_difficulty(difficulty);
+            self.bot_right.play(&mut self.duel_match);
+        }
+
+        if self.use_bot_on_side(LeftPlayer) {
+            let difficulty = self.bot_difficulty_on_side(LeftPlayer);
+            self.bot_left.set_difficulty(difficulty);
+            self.bot_left.play(&mut self.duel_match);
+        }
+    }
+
+    pub fn step(&mut self, game_assets: &mut GamesAssets) -> StateTransition {
+        self.frame_events.clear();
+        self.save_previous_positions();
+
+        let is_replay_finished = match self.replay_player {
+            Some(ref mut replay_player) => {
+                replay_player.step(&mut self.duel_match, &mut self.frame_events);
+                replay_player.is_finished()
+            },
+            None => {
+                self.step_bots();
+                self.duel_match.step(&mut self.frame_events);
+                false
+            },
+        };
+
+        // the ball and blobs are teleported, nothing to interpolate
+        if self.frame_events.contains(&FrameEvent::EventReset) {
+            self.save_previous_positions();
+        }
 
         if self.frame_events.iter().any( |x|
-            *x == FrameEvent::EventBlobbyHit(LeftPlayer) ||
-            *x == FrameEvent::EventBlobbyHit(RightPlayer)
+            match *x {
+                FrameEvent::EventBlobbyHit { .. } => true,
+                _ => false,
+            }
         ) {
             let _ = game_assets.sounds[0].execute(|sound| {
                 sound.set_volume(10.0f32);
@@ -140,8 +426,10 @@ impl LocalGameState {
         }
 
         if self.frame_events.iter().any( |x|
-            *x == FrameEvent::EventError(LeftPlayer) ||
-            *x == FrameEvent::EventError(RightPlayer)
+            match *x {
+                FrameEvent::EventError { .. } => true,
+                _ => false,
+            }
         ) {
             let _ = game_assets.sounds[1].execute(|sound| {
                 sound.set_volume(1.0f32);
@@ -161,14 +449,18 @@ impl LocalGameState {
         self.frame_number += 1;
 
         if self.frame_events.iter().any( |x|
-            *x == FrameEvent::EventWin(LeftPlayer)
+            *x == FrameEvent::EventMatchWon(LeftPlayer)
         ) {
             StateTransition::WinStateTransition(LeftPlayer)
         }
         else if self.frame_events.iter().any( |x|
-            *x == FrameEvent::EventWin(RightPlayer)
+            *x == FrameEvent::EventMatchWon(RightPlayer)
         ) {
             StateTransition::WinStateTransition(RightPlayer)
+        }
+        else if is_replay_finished {
+            self.reset();
+            StateTransition::StateLessTransition(RustyGameState::WinMenu)
         } else {
             NoTransition
         }
@@ -205,19 +497,38 @@ impl LocalGameState {
             })?;
         }
 
+        let view_scale = self.get_view_scale();
+
+        self.draw_arena(window)?;
+        self.draw_landing_markers(window)?;
+
         // draw left player
         {
-            let blob_pos = self.duel_match.get_blob_position(LeftPlayer);
+            let blob_pos =
+                self.to_screen(
+                    self.interpolate(
+                        self.previous_blob_positions[LeftPlayer as usize],
+                        self.duel_match.get_blob_position(LeftPlayer)
+                    )
+                );
             let blob_state = (self.duel_match.get_world().get_blob_state(LeftPlayer) as usize) % (BLOBBY_ANIMATION_FRAMES) ;
+            let character_size = self.duel_match.get_world().get_character(LeftPlayer).size;
              let transform =
                 Transform::scale(
                     Vector::new(
-                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32,
-                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32
+                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32 * view_scale * character_size,
+                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32 * view_scale * character_size
                     )
                 );
 
-            game_assets.blobs_images_left[blob_state].execute(|image| {
+            let blob_images =
+                if self.duel_match.get_player_on_side(LeftPlayer) == LeftPlayer {
+                    &mut game_assets.blobs_images_left
+                } else {
+                    &mut game_assets.blobs_images_right
+                };
+
+            blob_images[blob_state].execute(|image| {
                 window.draw_ex(
                     &image.area().with_center(
                         (
@@ -236,17 +547,31 @@ impl LocalGameState {
 
         // draw right player
         {
-            let blob_pos = self.duel_match.get_blob_position(RightPlayer);
+            let blob_pos =
+                self.to_screen(
+                    self.interpolate(
+                        self.previous_blob_positions[RightPlayer as usize],
+                        self.duel_match.get_blob_position(RightPlayer)
+                    )
+                );
             let blob_state = (self.duel_match.get_world().get_blob_state(RightPlayer) as usize) % (BLOBBY_ANIMATION_FRAMES);
+            let character_size = self.duel_match.get_world().get_character(RightPlayer).size;
             let transform =
                 Transform::scale(
                     Vector::new(
-                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32,
-                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32
+                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32 * view_scale * character_size,
+                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32 * view_scale * character_size
                     )
                 );
 
-            game_assets.blobs_images_right[blob_state].execute(|image| {
+            let blob_images =
+                if self.duel_match.get_player_on_side(RightPlayer) == LeftPlayer {
+                    &mut game_assets.blobs_images_left
+                } else {
+                    &mut game_assets.blobs_images_right
+                };
+
+            blob_images[blob_state].execute(|image| {
                 window.draw_ex(
                     &image.area().with_center(
                         (
@@ -263,16 +588,18 @@ impl LocalGameState {
             })?;
         }
 
-        // draw the ball
-        {
-            let ball_pos = self.duel_match.get_ball_position();
-            let ball_rot = self.duel_match.get_world().get_ball_rotation();
+        // draw the balls and the indicators of the ones above the screen
+        for (index, ball) in self.duel_match.get_balls().iter().enumerate() {
+            let previous_position =
+                self.previous_ball_positions.get(index).cloned().unwrap_or(ball.position);
+            let ball_pos = self.to_screen(self.interpolate(previous_position, ball.position));
+            let ball_rot = ball.rotation;
 
             let transform =
                 Transform::scale(
                     Vector::new(
-                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32,
-                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32
+                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32 * view_scale,
+                        DISPLAY_SCALE_FACTOR * 2.4f32 * 0.5f32 * view_scale
                     )
                 ) *
                 Transform::rotate(
@@ -294,13 +621,8 @@ impl LocalGameState {
 
                 Ok(())
             })?;
-        }
-
-        //draw ball indicator
-        {
-            let ball_pos = self.duel_match.get_ball_position();
 
-            if ball_pos.y < (0.0f32 - BALL_RADIUS) {
+            if ball_pos.y < (0.0f32 - BALL_RADIUS * view_scale) {
 
                 let transform =
                     Transform::scale(
@@ -328,8 +650,8 @@ impl LocalGameState {
             }
         }
 
-        // draw the score
-        {
+        // draw the score, a practice has none
+        if !self.rules.practice {
             let transform =
                     Transform::scale(
                         Vector::new(
@@ -339,10 +661,20 @@ impl LocalGameState {
                     );
 
             let (score1, score2) = self.duel_match.get_scores();
+            let (player1_sets, player2_sets) = self.duel_match.get_set_scores();
+            let (sets1, sets2) =
+                if self.duel_match.get_player_on_side(LeftPlayer) == LeftPlayer {
+                    (player1_sets, player2_sets)
+                } else {
+                    (player2_sets, player1_sets)
+                };
+            let show_sets = self.rules.set_count > 1;
 
             let should_recreate_texture =
                 self.scoring.score1 != score1 ||
                 self.scoring.score2 != score2 ||
+                self.scoring.sets1 != sets1 ||
+                self.scoring.sets2 != sets2 ||
                 self.scoring.score1_texture.is_none() ||
                 self.scoring.score2_texture.is_none();
 
@@ -352,18 +684,26 @@ impl LocalGameState {
 
                 if should_recreate_texture {
 
+                    let score1_text =
+                        if show_sets { format!("({}) {:02}", sets1, score1) } else { format!("{:02}", score1) };
+
                     let score1_texture =
-                        a_font.render(&format!("{:02}", score1), &game_assets.font_style)
+                        a_font.render(&score1_text, &game_assets.font_style)
                         .unwrap();
 
                     self.scoring.score1 = score1;
+                    self.scoring.sets1 = sets1;
                     self.scoring.score1_texture = Some(score1_texture);
 
+                    let score2_text =
+                        if show_sets { format!("{:02} ({})", score2, sets2) } else { format!("{:02}", score2) };
+
                    let score2_texture =
-                       a_font.render(&format!("{:02}", score2), &game_assets.font_style)
+                       a_font.render(&score2_text, &game_assets.font_style)
                        .unwrap();
 
                     self.scoring.score2 = score2;
+                    self.scoring.sets2 = sets2;
                     self.scoring.score2_texture = Some(score2_texture);
                 }
 
@@ -405,12 +745,155 @@ impl LocalGameState {
             })?;
         }
 
+        // draw the handicaps below the scores, along the sides of the window
+        if !self.rules.practice {
+            let scale = DISPLAY_SCALE_FACTOR;
+            let transform = Transform::scale(Vector::new(scale, scale));
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                for &side in [LeftPlayer, RightPlayer].iter() {
+                    let handicap = self.duel_match.get_handicap(side);
+                    let handicap_text = if handicap.is_none() { String::new() } else { handicap.to_string() };
+                    let index = side as usize;
+
+                    if self.scoring.handicap_texts[index] != handicap_text {
+                        self.scoring.handicap_textures[index] =
+                            if handicap_text.is_empty() {
+                                None
+                            } else {
+                                Some(a_font.render(&handicap_text, &game_assets.font_style).unwrap())
+                            };
+                        self.scoring.handicap_texts[index] = handicap_text;
+                    }
+
+                    if let Some(ref image) = self.scoring.handicap_textures[index] {
+                        let half_width = image.area().size.x * scale / 2.0f32;
+                        let center_x =
+                            if side == LeftPlayer {
+                                SCORE_PADDING_X as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR + half_width
+                            } else {
+                                (WINDOW_WIDTH - SCORE_PADDING_X / 2) as f32 * DISPLAY_SCALE_FACTOR - half_width
+                            };
+
+                        window.draw_ex(
+                            &image.area().with_center(
+                                (
+                                    center_x,
+                                    (SCORE_BASELINE_HEIGHT * 2) as f32 * DISPLAY_SCALE_FACTOR
+                                )
+                            ),
+                            Img(&image),
+                            transform,
+                            4.0f32
+                        );
+                    }
+                }
+
+                Ok(())
+            })?;
+        }
+
+        // draw the remaining time of timed matches
+        if let Some(remaining_time) = self.duel_match.get_remaining_time() {
+            let (score1, score2) = self.duel_match.get_scores();
+
+            let clock_text =
+                if self.duel_match.is_time_up() && score1 == score2 {
+                    "Golden point".to_string()
+                } else {
+                    let remaining_seconds = (remaining_time + STEPS_PER_SECOND - 1) / STEPS_PER_SECOND;
+                    format!("{}:{:02}", remaining_seconds / 60, remaining_seconds % 60)
+                };
+
+            let transform =
+                    Transform::scale(
+                        Vector::new(
+                            DISPLAY_SCALE_FACTOR * 1.6f32,
+                            DISPLAY_SCALE_FACTOR * 1.6f32
+                        )
+                    );
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                if self.scoring.clock_texture.is_none() || self.scoring.clock_text != clock_text {
+                    self.scoring.clock_texture =
+                        Some(a_font.render(&clock_text, &game_assets.font_style).unwrap());
+                    self.scoring.clock_text = clock_text;
+                }
+
+                match self.scoring.clock_texture {
+                    None => (),
+                    Some(ref image) => {
+                        window.draw_ex(
+                            &image.area().with_center(
+                                (
+                                    WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                                    SCORE_BASELINE_HEIGHT as f32 * DISPLAY_SCALE_FACTOR
+                                )
+                            ),
+                            Img(&image),
+                            transform,
+                            4.0f32
+                        );
+                    }
+                }
+
+                Ok(())
+            })?;
+        }
+
         Ok(())
     }
 
     pub fn handle_event(&mut self, event: &Event, _window: &mut Window) -> StateTransition {
-        let mut player_right_input = self.duel_match.get_world().get_player_input(RightPlayer);
-        let mut player_left_input = self.duel_match.get_world().get_player_input(LeftPlayer);
+        if let &Event::Key(Key::L, ButtonState::Pressed) = event {
+            self.show_landing_markers = !self.show_landing_markers;
+            return NoTransition;
+        }
+
+        if self.replay_player.is_some() {
+            return NoTransition;
+        }
+
+        // in practice S and the down arrow put the ball back above the blob
+        // of player 1 and 2, and Escape leaves
+        if self.rules.practice {
+            if let &Event::Key(key, ButtonState::Pressed) = event {
+                let restarting_player =
+                    match key {
+                        Key::S => LeftPlayer,
+                        Key::Down => RightPlayer,
+                        Key::Escape => {
+                            self.reset();
+                            return StateTransition::StateLessTransition(RustyGameState::NewGameMenu);
+                        },
+                        _ => NoPlayer,
+                    };
+
+                // practices are never recorded, see start_new_match. The
+                // balls and blobs are teleported between two steps: the
+                // previous positions are the new ones too, so the next
+                // frames draw them in place instead of sliding there
+                if restarting_player != NoPlayer {
+                    let side = self.duel_match.get_side_of_player(restarting_player);
+                    self.duel_match.restart_rally(side);
+                    self.save_previous_positions();
+                    return NoTransition;
+                }
+            }
+        }
+
+        // W, A and D control player 1 and the arrows player 2, whatever their sides
+        let player_left_side = self.duel_match.get_side_of_player(LeftPlayer);
+        let player_right_side = self.duel_match.get_side_of_player(RightPlayer);
+
+        let mut player_right_input = self.duel_match.get_world().get_player_input(player_right_side);
+        let mut player_left_input = self.duel_match.get_world().get_player_input(player_left_side);
 
         if let &Event::Key(key, state) = event {
 
@@ -461,8 +944,8 @@ impl LocalGameState {
                     _ => ()
                 }
             }
-            self.duel_match.get_world().set_player_input(LeftPlayer, player_left_input);
-            self.duel_match.get_world().set_player_input(RightPlayer, player_right_input);
+            self.duel_match.get_world().set_player_input(player_left_side, player_left_input);
+            self.duel_match.get_world().set_player_input(player_right_side, player_right_input);
         }
         NoTransition
     }
@@ -1,18 +1,40 @@
 extern crate quicksilver;
+extern crate rustyvolley;
+#[cfg(target_arch = "wasm32")]
+extern crate stdweb;
 
 mod new_game_menu_state;
 mod local_game_state;
 mod home_menu_state;
 mod win_menu_state;
-mod duel_match;
-mod game_logic;
-mod physic_world;
-mod game_constants;
-mod vector;
-mod global;
-mod player_input;
+mod training_state;
+mod juggling_state;
+mod juggling_result_state;
+mod tournament_menu_state;
+mod bracket_state;
+mod field_drawing;
 mod state_manager;
-mod simple_bot;
+
+use rustyvolley::{
+    arena,
+    character,
+    duel_match,
+    environment,
+    game_constants,
+    global,
+    juggling,
+    match_rules,
+    physic_world,
+    physics_params,
+    player_input,
+    replay,
+    replay_file,
+    simple_bot,
+    tick_clock,
+    tournament,
+    training,
+    vector,
+};
 
 use game_constants::*;
 
@@ -33,7 +55,10 @@ fn main() {
         ),
         Settings {
             draw_rate: 4.0,
+            // the StateManager runs the simulation steps due at each update
+            // from its own clock, so a single update per frame is enough
             update_rate: 4.0,
+            max_updates: 1,
             vsync: true,
             multisampling: Some(16),
             //fullscreen: true,
@@ -0,0 +1,143 @@
+use game_logic::{other_side, side_to_index};
+use global::PlayerSide;
+use global::PlayerSide::*;
+use match_rules::MatchRules;
+use state_hash::StateHasher;
+
+// Set layer above GameLogic: each GameLogic game is a set, MatchLogic counts
+// the sets and decides who wins the match.
+//
+// Players are identified by the side they started the match on, so that
+// they keep their set scores when the sides are swapped between sets.
+pub struct MatchLogic {
+    // number of sets won by each player
+    set_scores : [i32; 2],
+    // point scores of every finished set, indexed by player
+    finished_sets : Vec<[i32; 2]>,
+    // true between the end of a set and the start of the next one
+    is_set_finished : bool,
+    // true when each player plays on the side the other one started on
+    sides_swapped : bool,
+    // player that has won the match
+    winning_player : PlayerSide,
+}
+
+// Plain copy of every field of a MatchLogic
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchLogicSnapshot {
+    pub set_scores : [i32; 2],
+    pub finished_sets : Vec<[i32; 2]>,
+    pub is_set_finished : bool,
+    pub sides_swapped : bool,
+    pub winning_player : PlayerSide,
+}
+
+impl MatchLogicSnapshot {
+    // Adds every field to the hash, see DuelMatch::get_state_hash
+    pub fn add_to_hash(&self, hasher : &mut StateHasher) {
+        hasher.write_i32(self.set_scores[0]);
+        hasher.write_i32(self.set_scores[1]);
+
+        hasher.write_i32(self.finished_sets.len() as i32);
+        for scores in self.finished_sets.iter() {
+            hasher.write_i32(scores[0]);
+            hasher.write_i32(scores[1]);
+        }
+
+        hasher.write_bool(self.is_set_finished);
+        hasher.write_bool(self.sides_swapped);
+        hasher.write_i32(self.winning_player as i32);
+    }
+}
+
+impl MatchLogic {
+    pub fn new() -> MatchLogic {
+        MatchLogic {
+            set_scores : [0i32; 2],
+            finished_sets : vec!(),
+            is_set_finished : false,
+            sides_swapped : false,
+            winning_player : NoPlayer,
+        }
+    }
+
+    pub fn snapshot(&self) -> MatchLogicSnapshot {
+        MatchLogicSnapshot {
+            set_scores : self.set_scores,
+            finished_sets : self.finished_sets.clone(),
+            is_set_finished : self.is_set_finished,
+            sides_swapped : self.sides_swapped,
+            winning_player : self.winning_player,
+        }
+    }
+
+    pub fn restore(&mut self, snapshot : &MatchLogicSnapshot) {
+        self.set_scores = snapshot.set_scores;
+        self.finished_sets = snapshot.finished_sets.clone();
+        self.is_set_finished = snapshot.is_set_finished;
+        self.sides_swapped = snapshot.sides_swapped;
+        self.winning_player = snapshot.winning_player;
+    }
+
+    pub fn get_side_of_player(&self, player : PlayerSide) -> PlayerSide {
+        if self.sides_swapped && player != NoPlayer {
+            other_side(player)
+        }
+        else {
+            player
+        }
+    }
+
+    pub fn get_player_on_side(&self, side : PlayerSide) -> PlayerSide {
+        // the mapping is its own inverse
+        self.get_side_of_player(side)
+    }
+
+    pub fn is_set_finished(&self) -> bool {
+        self.is_set_finished
+    }
+
+    // scores is indexed by player
+    pub fn on_set_won(&mut self, player : PlayerSide, scores : [i32; 2], rules : &MatchRules) {
+        self.is_set_finished = true;
+        self.finished_sets.push(scores);
+
+        self.set_scores[side_to_index(player)] = self.set_scores[side_to_index(player)] + 1;
+
+        if self.set_scores[side_to_index(player)] >= rules.get_sets_to_win() {
+            self.winning_player = player;
+        }
+    }
+
+    // Returns the side that serves first in the new set. The first server
+    // alternates between the players from one set to the next.
+    pub fn start_next_set(&mut self, rules : &MatchRules) -> PlayerSide {
+        self.is_set_finished = false;
+
+        if rules.swap_sides_between_sets {
+            self.sides_swapped = !self.sides_swapped;
+        }
+
+        let first_server =
+            if self.finished_sets.len() % 2 == 1 {
+                other_side(rules.first_serving_player)
+            }
+            else {
+                rules.first_serving_player
+            };
+
+        self.get_side_of_player(first_server)
+    }
+
+    pub fn get_set_scores(&self) -> (i32, i32) {
+        (self.set_scores[0], self.set_scores[1])
+    }
+
+    pub fn get_finished_sets(&self) -> &[[i32; 2]] {
+        &self.finished_sets
+    }
+
+    pub fn get_winning_player(&self) -> PlayerSide {
+        self.winning_player
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt;
+
+use character::Character;
+use game_constants::*;
+use global::PlayerSide;
+use global::PlayerSide::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoringMode {
+    // every rally gives a point to its winner
+    RallyScoring,
+    // only the serving player scores, winning a rally as receiver only
+    // takes the serve, like in the original Blobby Volley
+    SideOutScoring,
+}
+
+// Help for uneven matchups, per player: a head start for the weaker one,
+// penalties for the stronger one. The default is no handicap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Handicap {
+    // points the player has at the start of every set
+    pub head_start : i32,
+    // touches the player is allowed less than the rules say, at least one
+    // touch is always left
+    pub touch_penalty : i32,
+    // percentage taken from the walking speed of the player
+    pub speed_penalty : i32,
+}
+
+impl Handicap {
+    pub fn is_none(&self) -> bool {
+        *self == Handicap::default()
+    }
+
+    // the character walking slower by the speed penalty, a blob never walks
+    // backwards
+    pub fn apply_to(&self, character : Character) -> Character {
+        let speed_penalty = self.speed_penalty.min(100);
+
+        Character {
+            speed : character.speed * ((100 - speed_penalty) as f32 / 100.0f32),
+            ..character
+        }
+    }
+}
+
+impl fmt::Display for Handicap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = vec!();
+
+        if self.head_start != 0 {
+            parts.push(format!("{:+} points", self.head_start));
+        }
+
+        if self.speed_penalty != 0 {
+            parts.push(format!("{}% slower", self.speed_penalty));
+        }
+
+        if self.touch_penalty == 1 {
+            parts.push("1 touch less".to_string());
+        } else if self.touch_penalty != 0 {
+            parts.push(format!("{} touches less", self.touch_penalty));
+        }
+
+        if parts.is_empty() {
+            write!(f, "no handicap")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+// Rules of a match, given to GameLogic when it is created. The default is
+// the classic game: a single set of 15 points, no margin, three touches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchRules {
+    pub score_to_win : i32,
+    // points of advance needed to win once score_to_win is reached,
+    // 2 for the usual win-by-two, 1 to win as soon as score_to_win is reached
+    pub win_margin : i32,
+    // reaching this score wins even without the margin, None for no cap
+    pub score_cap : Option<i32>,
+    // touches allowed per side, the next one is an error
+    pub max_ball_touch_count : i32,
+    // number of steps during which a blob cannot hit the ball again
+    pub squish_tolerance : i32,
+    // LeftPlayer or RightPlayer
+    pub first_serving_player : PlayerSide,
+    pub scoring_mode : ScoringMode,
+    // the match is played in the best of this many sets, 1 for a single game
+    pub set_count : i32,
+    pub swap_sides_between_sets : bool,
+    // steps of play after which the leader wins each set, None for no limit.
+    // The clock stops between rallies and a tie goes to the next point
+    pub time_limit : Option<i32>,
+    // balls in play at once, 1 for the classic game. Each ball scores a
+    // point when it lands and the rally goes on until the last one does
+    pub ball_count : i32,
+    // free play: errors end the rally without scoring nor giving the serve
+    // away, and nobody ever wins
+    pub practice : bool,
+    // no touch limit, a side never touches the ball too often
+    pub infinite_touches : bool,
+    // handicaps of player 1 and player 2, they follow the players when the
+    // sides are swapped
+    pub handicaps : [Handicap; 2],
+}
+
+impl Default for MatchRules {
+    fn default() -> MatchRules {
+        MatchRules {
+            score_to_win : SCORE_TO_WIN,
+            win_margin : 1,
+            score_cap : None,
+            max_ball_touch_count : MAX_BALL_TOUCH_COUNT,
+            squish_tolerance : SQUISH_TOLERANCE,
+            first_serving_player : LeftPlayer,
+            scoring_mode : ScoringMode::RallyScoring,
+            set_count : 1,
+            swap_sides_between_sets : false,
+            time_limit : None,
+            ball_count : 1,
+            practice : false,
+            infinite_touches : false,
+            handicaps : [Handicap::default(); 2],
+        }
+    }
+}
+
+impl MatchRules {
+    pub fn is_winning_score(&self, score : i32, opponent_score : i32) -> bool {
+        if let Some(score_cap) = self.score_cap {
+            if score >= score_cap {
+                return true;
+            }
+        }
+
+        score >= self.score_to_win && score - opponent_score >= self.win_margin
+    }
+
+    pub fn get_sets_to_win(&self) -> i32 {
+        self.set_count / 2 + 1
+    }
+
+    // ball_count within the balls a PhysicWorld can hold
+    pub fn get_ball_count(&self) -> usize {
+        (self.ball_count.max(1) as usize).min(MAX_BALL_COUNT)
+    }
+}
+
+// Describes the rules of a single set
+impl fmt::Display for MatchRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.practice {
+            return write!(f, "practice, no score");
+        }
+
+        write!(f, "{} points", self.score_to_win)?;
+
+        if self.win_margin == 2 {
+            write!(f, ", win by two")?;
+        }
+        else if self.win_margin > 2 {
+            write!(f, ", win by {}", self.win_margin)?;
+        }
+
+        if let Some(score_cap) = self.score_cap {
+            write!(f, ", cap {}", score_cap)?;
+        }
+
+        if self.scoring_mode == ScoringMode::SideOutScoring {
+            write!(f, ", side-out")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,3 @@
-use std::fmt;
-
 use quicksilver::{
     Result,
     geom::{Shape, Transform, Vector},
@@ -16,11 +14,15 @@ use state_manager::{
 
 use game_constants::*;
 
-#[derive(PartialEq, Eq, Clone)]
-pub enum PlayerKind {
-    Human,
-    Computer
-}
+use std::fs;
+use std::path::PathBuf;
+
+use global::PlayerKind;
+use match_rules::{Handicap, MatchRules, ScoringMode};
+use physics_params::PhysicsParams;
+use environment::GravityPreset::*;
+use arena::{Arena, ARENA_FILE_EXTENSION, load_arena};
+use character::Character;
 
 fn switch_conf(player_kind : &PlayerKind) -> PlayerKind {
     match player_kind {
@@ -29,50 +31,445 @@ fn switch_conf(player_kind : &PlayerKind) -> PlayerKind {
     }
 }
 
-impl fmt::Display for PlayerKind {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Human => write!(f, "Human"),
-            Computer => write!(f, "Computer")
-        }
-        
-    }
-}
-
 #[derive(Clone)]
 pub struct GameConfiguration {
     pub player1_configuration : PlayerKind,
     pub player2_configuration : PlayerKind,
+    // difficulty of the bot of each player, when it is a computer
+    pub bot_difficulties : [i32; 2],
+    pub rules : MatchRules,
+    pub physics_params : PhysicsParams,
+}
+
+use global::PlayerKind::*;
+
+// The lines of the menu, from top to bottom
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MenuLine {
+    Player1Line,
+    Player2Line,
+    Character1Line,
+    Character2Line,
+    RulesLine,
+    TouchesLine,
+    HandicapLine,
+    SetsLine,
+    TimeLine,
+    BallsLine,
+    VariantLine,
+    ArenaLine,
+    StartLine,
+    TrainingLine,
+    JugglingLine,
+    TournamentLine,
+}
+
+use self::MenuLine::*;
+
+const MENU_LINES : [MenuLine; 16] = [
+    Player1Line,
+    Player2Line,
+    Character1Line,
+    Character2Line,
+    RulesLine,
+    TouchesLine,
+    HandicapLine,
+    SetsLine,
+    TimeLine,
+    BallsLine,
+    VariantLine,
+    ArenaLine,
+    StartLine,
+    TrainingLine,
+    JugglingLine,
+    TournamentLine,
+];
+
+// vertical position of the first and last lines, in window coordinates
+const MENU_TOP_Y : f32 = 200.0f32;
+const MENU_BOTTOM_Y : f32 = 1330.0f32;
+const MENU_LINE_CENTER_X : f32 = 1000.0f32;
+
+// clickable area around each line, in screen coordinates
+const MENU_CLICK_MIN_X : f32 = 165.0f32;
+const MENU_CLICK_MAX_X : f32 = 675.0f32;
+const MENU_CLICK_HALF_HEIGHT : f32 = 15.0f32;
+
+fn rules_presets() -> [MatchRules; 5] {
+    [
+        MatchRules::default(),
+        MatchRules {
+            win_margin : 2,
+            ..MatchRules::default()
+        },
+        MatchRules {
+            score_to_win : 25,
+            win_margin : 2,
+            ..MatchRules::default()
+        },
+        MatchRules {
+            scoring_mode : ScoringMode::SideOutScoring,
+            ..MatchRules::default()
+        },
+        MatchRules {
+            practice : true,
+            ..MatchRules::default()
+        },
+    ]
+}
+
+// handicaps of the weaker and the stronger player, each level helping the
+// weaker one more
+fn handicap_levels() -> [(Handicap, Handicap); 3] {
+    [
+        (Handicap { head_start : 3, ..Handicap::default() }, Handicap::default()),
+        (
+            Handicap { head_start : 3, ..Handicap::default() },
+            Handicap { speed_penalty : 15, ..Handicap::default() }
+        ),
+        (
+            Handicap { head_start : 5, ..Handicap::default() },
+            Handicap { speed_penalty : 15, touch_penalty : 1, ..Handicap::default() }
+        ),
+    ]
+}
+
+// The handicaps of both players: none for the first preset, then the levels
+// helping player 1 and the levels helping player 2
+fn handicap_preset_count() -> usize {
+    1 + 2 * handicap_levels().len()
+}
+
+fn handicaps_of_preset(preset : usize) -> [Handicap; 2] {
+    let level_count = handicap_levels().len();
+
+    if preset == 0 {
+        [Handicap::default(); 2]
+    } else if preset <= level_count {
+        let (weaker, stronger) = handicap_levels()[preset - 1];
+        [weaker, stronger]
+    } else {
+        let (weaker, stronger) = handicap_levels()[preset - 1 - level_count];
+        [stronger, weaker]
+    }
 }
 
-use new_game_menu_state::PlayerKind::*;
+fn handicap_text(preset : usize) -> String {
+    let level_count = handicap_levels().len();
+
+    if preset == 0 {
+        "none".to_string()
+    } else if preset <= level_count {
+        format!("helps player 1, level {}", preset)
+    } else {
+        format!("helps player 2, level {}", preset - level_count)
+    }
+}
+
+// set count and side swapping, combined with the rules preset
+const SETS_PRESETS : [(i32, bool); 5] = [
+    (1, false),
+    (3, false),
+    (3, true),
+    (5, false),
+    (5, true),
+];
+
+// time limit of each set in minutes, 0 for none
+const TIME_LIMIT_PRESETS : [i32; 4] = [0, 3, 5, 10];
+
+// balls in play at once
+const BALL_COUNT_PRESETS : [i32; 3] = [1, 2, 3];
+
+// physics of the party variants, the first one being the classic game
+fn variant_presets() -> [(&'static str, PhysicsParams); 6] {
+    [
+        ("classic", PhysicsParams::default()),
+        ("spin", PhysicsParams {
+            spin_factor : 1.0f32,
+            ..PhysicsParams::default()
+        }),
+        ("breeze", PhysicsParams {
+            wind_force : 0.02f32,
+            air_drag : 0.001f32,
+            ..PhysicsParams::default()
+        }),
+        ("gusts", PhysicsParams {
+            wind_gust_force : 0.05f32,
+            air_drag : 0.001f32,
+            ..PhysicsParams::default()
+        }),
+        ("moon", MoonGravity.apply(PhysicsParams::default())),
+        ("heavy", HeavyGravity.apply(PhysicsParams::default())),
+    ]
+}
+
+// bodies of the blobs, the first one being the classic blob
+fn character_presets() -> [(&'static str, Character); 4] {
+    [
+        ("classic", Character::default()),
+        ("quick", Character {
+            speed : 1.25f32,
+            jump : 0.95f32,
+            size : 0.85f32,
+        }),
+        ("giant", Character {
+            speed : 0.85f32,
+            jump : 0.95f32,
+            size : 1.25f32,
+        }),
+        ("jumper", Character {
+            speed : 0.9f32,
+            jump : 1.08f32,
+            size : 1.0f32,
+        }),
+    ]
+}
+
+// folder of the arena files offered by the menu, next to the game
+const ARENAS_FOLDER : &str = "arenas";
+
+// the classic field, then the arena files of ARENAS_FOLDER by file name,
+// with the error of the first file that could not be loaded
+fn arena_presets() -> (Vec<(String, Arena)>, Option<String>) {
+    let mut presets = vec!(("classic".to_string(), Arena::default()));
+    let mut load_error = None;
+
+    let mut paths : Vec<PathBuf> =
+        match fs::read_dir(ARENAS_FOLDER) {
+            Ok(entries) =>
+                entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().map_or(false, |extension| extension == ARENA_FILE_EXTENSION))
+                .collect(),
+            Err(_) => vec!(),
+        };
+    paths.sort();
+
+    for path in paths.iter() {
+        let name = path.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().replace('_', " "));
+
+        match load_arena(path) {
+            Ok(arena) => presets.push((name, arena)),
+            Err(error) => {
+                if load_error.is_none() {
+                    load_error = Some(format!("cannot load {}: {}", path.display(), error));
+                }
+            },
+        }
+    }
+
+    (presets, load_error)
+}
+
+fn sets_text(set_count : i32, swap_sides : bool) -> String {
+    if set_count <= 1 {
+        "single game".to_string()
+    } else if swap_sides {
+        format!("best of {}, swap sides", set_count)
+    } else {
+        format!("best of {}", set_count)
+    }
+}
+
+fn line_center_y(line_index : usize) -> f32 {
+    MENU_TOP_Y +
+        (MENU_BOTTOM_Y - MENU_TOP_Y) * line_index as f32 / (MENU_LINES.len() - 1) as f32
+}
 
 pub struct NewGameMenuState {
-    conf_line_1 : Option<Image>,
-    player1_configuration : PlayerKind,
-    conf_line_2 : Option<Image>,
-    player2_configuration : PlayerKind,
-    line_start : Option<Image>,
+    // rendered lines and the texts they were rendered from
+    line_images : Vec<Image>,
+    line_texts : Vec<String>,
+    rules_preset : usize,
+    sets_preset : usize,
+    time_limit_preset : usize,
+    ball_count_preset : usize,
+    variant_preset : usize,
+    arena_preset : usize,
+    arenas : Vec<(String, Arena)>,
+    // shown on the arena line until another arena is picked
+    arena_error : Option<String>,
+    infinite_touches : bool,
+    handicap_preset : usize,
+    // character of each player
+    character_presets : [usize; 2],
     configuration : GameConfiguration,
 }
 
 impl NewGameMenuState {
     pub fn new() -> NewGameMenuState {
-        NewGameMenuState {
-
-            conf_line_1: None,
-            conf_line_2: None,
-            line_start : None,
+        let (arenas, arena_error) = arena_presets();
 
-            player1_configuration : Human,
-            player2_configuration : Computer,
+        NewGameMenuState {
+            line_images : vec!(),
+            line_texts : vec!(),
+            rules_preset : 0,
+            sets_preset : 0,
+            time_limit_preset : 0,
+            ball_count_preset : 0,
+            variant_preset : 0,
+            arena_preset : 0,
+            arenas : arenas,
+            arena_error : arena_error,
+            infinite_touches : false,
+            handicap_preset : 0,
+            character_presets : [0; 2],
 
             configuration: GameConfiguration {
                 player1_configuration : Human,
                 player2_configuration : Computer,
+                bot_difficulties : [0; 2],
+                rules : rules_presets()[0],
+                physics_params : variant_presets()[0].1,
             }
         }
     }
+
+    fn update_rules(&mut self) {
+        let rules_preset = rules_presets()[self.rules_preset];
+
+        // a practice has no end, neither sets nor time limit
+        let (set_count, swap_sides) =
+            if rules_preset.practice { SETS_PRESETS[0] } else { SETS_PRESETS[self.sets_preset] };
+        let time_limit_minutes =
+            if rules_preset.practice { 0 } else { TIME_LIMIT_PRESETS[self.time_limit_preset] };
+
+        self.configuration.rules = MatchRules {
+            set_count : set_count,
+            swap_sides_between_sets : swap_sides,
+            time_limit :
+                if time_limit_minutes > 0 {
+                    Some(time_limit_minutes * 60 * STEPS_PER_SECOND)
+                } else {
+                    None
+                },
+            ball_count : BALL_COUNT_PRESETS[self.ball_count_preset],
+            infinite_touches : self.infinite_touches,
+            handicaps : handicaps_of_preset(self.handicap_preset),
+            ..rules_preset
+        };
+    }
+
+    fn update_physics_params(&mut self) {
+        self.configuration.physics_params = PhysicsParams {
+            arena : self.arenas[self.arena_preset].1,
+            characters : [
+                character_presets()[self.character_presets[0]].1,
+                character_presets()[self.character_presets[1]].1,
+            ],
+            ..variant_presets()[self.variant_preset].1
+        };
+    }
+
+    fn line_text(&self, line : MenuLine) -> String {
+        match line {
+            Player1Line => format!("Player 1: {}", self.configuration.player1_configuration),
+            Player2Line => format!("Player 2: {}", self.configuration.player2_configuration),
+            Character1Line => format!("Character 1: {}", character_presets()[self.character_presets[0]].0),
+            Character2Line => format!("Character 2: {}", character_presets()[self.character_presets[1]].0),
+            RulesLine => format!("Rules: {}", self.configuration.rules),
+            TouchesLine =>
+                if self.configuration.rules.infinite_touches {
+                    "Touches: unlimited".to_string()
+                } else {
+                    format!("Touches: {}", self.configuration.rules.max_ball_touch_count)
+                },
+            HandicapLine => format!("Handicap: {}", handicap_text(self.handicap_preset)),
+            SetsLine => format!(
+                "Sets: {}",
+                sets_text(self.configuration.rules.set_count, self.configuration.rules.swap_sides_between_sets)
+            ),
+            TimeLine => match self.configuration.rules.time_limit {
+                None => "Time: no limit".to_string(),
+                Some(time_limit) => format!("Time: {} min", time_limit / (60 * STEPS_PER_SECOND)),
+            },
+            BallsLine => match self.configuration.rules.ball_count {
+                1 => "Balls: 1".to_string(),
+                ball_count => format!("Balls: {} at once", ball_count),
+            },
+            VariantLine => format!("Variant: {}", variant_presets()[self.variant_preset].0),
+            ArenaLine => match self.arena_error {
+                Some(ref error) => format!("Arena: {} ({})", self.arenas[self.arena_preset].0, error),
+                None => format!("Arena: {}", self.arenas[self.arena_preset].0),
+            },
+            StartLine => "Click here to start!".to_string(),
+            TrainingLine => "Or train against the launcher".to_string(),
+            JugglingLine => "Or juggle for a record".to_string(),
+            TournamentLine => "Or organise a tournament".to_string(),
+        }
+    }
+
+    fn on_line_clicked(&mut self, line : MenuLine) -> StateTransition {
+        match line {
+            Player1Line => {
+                self.configuration.player1_configuration =
+                    switch_conf(&self.configuration.player1_configuration);
+                NoTransition
+            },
+            Player2Line => {
+                self.configuration.player2_configuration =
+                    switch_conf(&self.configuration.player2_configuration);
+                NoTransition
+            },
+            Character1Line => {
+                self.character_presets[0] = (self.character_presets[0] + 1) % character_presets().len();
+                self.update_physics_params();
+                NoTransition
+            },
+            Character2Line => {
+                self.character_presets[1] = (self.character_presets[1] + 1) % character_presets().len();
+                self.update_physics_params();
+                NoTransition
+            },
+            RulesLine => {
+                self.rules_preset = (self.rules_preset + 1) % rules_presets().len();
+                self.update_rules();
+                NoTransition
+            },
+            TouchesLine => {
+                self.infinite_touches = !self.infinite_touches;
+                self.update_rules();
+                NoTransition
+            },
+            HandicapLine => {
+                self.handicap_preset = (self.handicap_preset + 1) % handicap_preset_count();
+                self.update_rules();
+                NoTransition
+            },
+            SetsLine => {
+                self.sets_preset = (self.sets_preset + 1) % SETS_PRESETS.len();
+                self.update_rules();
+                NoTransition
+            },
+            TimeLine => {
+                self.time_limit_preset = (self.time_limit_preset + 1) % TIME_LIMIT_PRESETS.len();
+                self.update_rules();
+                NoTransition
+            },
+            BallsLine => {
+                self.ball_count_preset = (self.ball_count_preset + 1) % BALL_COUNT_PRESETS.len();
+                self.update_rules();
+                NoTransition
+            },
+            VariantLine => {
+                self.variant_preset = (self.variant_preset + 1) % variant_presets().len();
+                self.update_physics_params();
+                NoTransition
+            },
+            ArenaLine => {
+                self.arena_error = None;
+                self.arena_preset = (self.arena_preset + 1) % self.arenas.len();
+                self.update_physics_params();
+                NoTransition
+            },
+            StartLine => StateTransition::StartGameTransition(self.configuration.clone()),
+            TrainingLine => StateTransition::StartTrainingTransition(self.configuration.clone()),
+            JugglingLine => StateTransition::StartJugglingTransition(self.configuration.clone()),
+            TournamentLine => StateTransition::StartTournamentMenuTransition(self.configuration.clone()),
+        }
+    }
 }
 
 impl RustyVollyState for NewGameMenuState {
@@ -121,103 +518,38 @@ impl RustyVollyState for NewGameMenuState {
                     )
                 );
 
-            let should_recreate_texture =
-                self.player1_configuration != self.configuration.player1_configuration ||
-                self.player2_configuration != self.configuration.player2_configuration ||
-                self.conf_line_1.is_none() ||
-                self.conf_line_2.is_none() ||
-                self.line_start.is_none();
+            let line_texts : Vec<String> =
+                MENU_LINES.iter().map(|line| self.line_text(*line)).collect();
 
+            let should_recreate_texture = line_texts != self.line_texts;
 
             let cloned_font_ref = game_assets.font.clone();
 
             cloned_font_ref.borrow_mut().execute(|a_font| {
 
                 if should_recreate_texture {
+                    let mut line_images = vec!();
 
-                    self.configuration.player1_configuration = self.player1_configuration.clone();
-                    self.configuration.player2_configuration = self.player2_configuration.clone();
-
-                    let line1 =
-                        a_font.render
-                        (
-                            &format!("Player 1: {}", self.configuration.player1_configuration), 
-                            &game_assets.font_style
-                        )
-                        .unwrap();
-
-                    self.conf_line_1 = Some(line1);
-
-                   let line2 =
-                       a_font.render
-                        (
-                            &format!("Player 2: {}", self.configuration.player2_configuration), 
-                            &game_assets.font_style
-                        )
-                        .unwrap();
-
-                    self.conf_line_2 = Some(line2);
-
-                    let line_start = 
-                        a_font.render
-                        (
-                            "Click here to start!", 
-                            &game_assets.font_style
-                        )
-                        .unwrap();
-                    
-                    self.line_start = Some(line_start);
-                }
-
-                match self.conf_line_1 {
-                    None => (),
-                    Some(ref image) => {
-                        window.draw_ex(
-                            &image.area().with_center(
-                                (
-                                    1000.0f32 * DISPLAY_SCALE_FACTOR,
-                                    300.0f32 as f32 * DISPLAY_SCALE_FACTOR
-                                )
-                            ),
-                            Img(&image),
-                            transform,
-                            4.0f32
-                        );
+                    for text in line_texts.iter() {
+                        line_images.push(a_font.render(text, &game_assets.font_style).unwrap());
                     }
-                }
 
-                match self.conf_line_2 {
-                    None => (),
-                    Some(ref image) => {
-                        window.draw_ex(
-                            &image.area().with_center(
-                                (
-                                    1000.0f32 * DISPLAY_SCALE_FACTOR,
-                                    700.0f32 as f32 * DISPLAY_SCALE_FACTOR
-                                )
-                            ),
-                            Img(&image),
-                            transform,
-                            4.0f32
-                        );
-                    }
+                    self.line_images = line_images;
+                    self.line_texts = line_texts;
                 }
 
-                match self.line_start {
-                    None => (),
-                    Some(ref image) => {
-                        window.draw_ex(
-                            &image.area().with_center(
-                                (
-                                    1000.0f32 * DISPLAY_SCALE_FACTOR,
-                                    1100.0f32 as f32 * DISPLAY_SCALE_FACTOR
-                                )
-                            ),
-                            Img(&image),
-                            transform,
-                            4.0f32
-                        );
-                    }
+                for (line_index, image) in self.line_images.iter().enumerate() {
+                    window.draw_ex(
+                        &image.area().with_center(
+                            (
+                                MENU_LINE_CENTER_X * DISPLAY_SCALE_FACTOR,
+                                line_center_y(line_index) * DISPLAY_SCALE_FACTOR
+                            )
+                        ),
+                        Img(&image),
+                        transform,
+                        4.0f32
+                    );
                 }
 
                 Ok(())
@@ -234,36 +566,18 @@ impl RustyVollyState for NewGameMenuState {
                 let mouse = window.mouse();
                 let mouse_pos = mouse.pos();
 
-                let transition = 
-                    if  
-                        mouse_pos.x >= 220f32 && mouse_pos.x <= 620f32 &&
-                        mouse_pos.y >= 100f32 && mouse_pos.y <= 145f32 
-                    {
-                        self.player1_configuration = switch_conf(&self.player1_configuration);
-                        NoTransition
-                    }
-
-                    else if 
-                        mouse_pos.x >= 220f32 && mouse_pos.x <= 620f32 &&
-                        mouse_pos.y >= 265f32 && mouse_pos.y <= 315f32 
-                    {
-                        self.player2_configuration = switch_conf(&self.player2_configuration);
-                        NoTransition
+                let clicked_line =
+                    MENU_LINES.iter().enumerate().find(|(line_index, _)| {
+                        let center_y = line_center_y(*line_index) * DISPLAY_SCALE_FACTOR;
 
-                    }
+                        mouse_pos.x >= MENU_CLICK_MIN_X && mouse_pos.x <= MENU_CLICK_MAX_X &&
+                        (mouse_pos.y - center_y).abs() <= MENU_CLICK_HALF_HEIGHT
+                    });
 
-                    else if 
-                        mouse_pos.x >= 165f32 && mouse_pos.x <= 675f32 &&
-                        mouse_pos.y >= 435f32 && mouse_pos.y <= 480f32
-                    {
-                        StateTransition::StartGameTransition(self.configuration.clone())
-                    } 
-                    else 
-                    {
-                        NoTransition
-                    };
-
-                transition
+                match clicked_line {
+                    Some((_, line)) => self.on_line_clicked(*line),
+                    None => NoTransition,
+                }
             }
 
             _ => NoTransition,
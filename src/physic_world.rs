@@ -2,49 +2,104 @@ use global::PlayerSide;
 use global::PlayerSide::*;
 use game_constants::*;
 use player_input::PlayerInput;
+use physics_params::PhysicsParams;
+use environment::WindState;
+use ball::Ball;
+use character::Character;
+use ball_prediction::{BallPrediction, BallPredictor};
 
 use vector::Vector2f;
+use state_hash::StateHasher;
 
 pub struct PhysicWorld {
-    ball_hit_by_blobs : [bool; 2],
     blob_positions : [Vector2f; 2],
-    ball_position : Vector2f,
     blob_velocities : [Vector2f; 2],
-    ball_velocity : Vector2f,
-
-    ball_rotation : f32,
-    ball_angular_velocity : f32,
     blobs_animation_states : [f32; 2],
     blobs_animation_speed : [f32; 2],
 
+    // at least one, the first ball being the one of the classic game
+    balls : Vec<Ball>,
+
     player_inputs : [PlayerInput; 2],
 
     is_game_running : bool,
-    is_ball_valid : bool,
-    last_hit_intensity: f32,
     time_since_ball_out: f32,
+
+    wind : WindState,
+
+    params : PhysicsParams,
+
+    // bodies of the blobs by side, the ones of the params until the
+    // players swap sides
+    characters : [Character; 2],
+}
+
+// Plain copy of every field of a PhysicWorld but the characters, which
+// DuelMatch::restore gives back from the params and the sides of the
+// players. Used to save and restore the exact simulation state (rollback,
+// undo, search-based bots...)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicWorldSnapshot {
+    pub blob_positions : [Vector2f; 2],
+    pub blob_velocities : [Vector2f; 2],
+    pub blobs_animation_states : [f32; 2],
+    pub blobs_animation_speed : [f32; 2],
+
+    pub balls : Vec<Ball>,
+
+    pub player_inputs : [PlayerInput; 2],
+
+    pub is_game_running : bool,
+    pub time_since_ball_out: f32,
+
+    pub wind : WindState,
+}
+
+impl PhysicWorldSnapshot {
+    // Adds every field to the hash, see DuelMatch::get_state_hash
+    pub fn add_to_hash(&self, hasher : &mut StateHasher) {
+        for index in 0..2 {
+            hasher.write_vector(&self.blob_positions[index]);
+            hasher.write_vector(&self.blob_velocities[index]);
+            hasher.write_f32(self.blobs_animation_states[index]);
+            hasher.write_f32(self.blobs_animation_speed[index]);
+            hasher.write_bool(self.player_inputs[index].left);
+            hasher.write_bool(self.player_inputs[index].right);
+            hasher.write_bool(self.player_inputs[index].up);
+        }
+
+        hasher.write_i32(self.balls.len() as i32);
+        for ball in self.balls.iter() {
+            ball.add_to_hash(hasher);
+        }
+
+        hasher.write_bool(self.is_game_running);
+        hasher.write_f32(self.time_since_ball_out);
+
+        self.wind.add_to_hash(hasher);
+    }
 }
 
 impl PhysicWorld {
-    pub fn new() -> PhysicWorld {
+    pub fn new(params : PhysicsParams) -> PhysicWorld {
         let mut physic_world = PhysicWorld {
-            ball_hit_by_blobs : [false; 2],
             blob_positions : [Vector2f::new(0.0f32, 0.0f32); 2],
-            ball_position : Vector2f::new(0.0f32, 0.0f32),
             blob_velocities : [Vector2f::new(0.0f32, 0.0f32); 2],
-            ball_velocity : Vector2f::new(0.0f32, 0.0f32),
-
-            ball_rotation : 0.0f32,
-            ball_angular_velocity : 0.0f32,
             blobs_animation_states : [0.0f32; 2],
             blobs_animation_speed : [0.0f32; 2],
 
+            balls : vec!(Ball::new()),
+
             player_inputs : [PlayerInput::new() ; 2],
 
             is_game_running : false,
-            is_ball_valid : false,
-            last_hit_intensity: 0.0f32,
             time_since_ball_out: 0.0f32,
+
+            wind : WindState::new(WIND_DEFAULT_SEED),
+
+            params : params,
+
+            characters : params.characters,
         };
 
         physic_world.reset(LeftPlayer);
@@ -55,6 +110,45 @@ impl PhysicWorld {
         physic_world
     }
 
+    pub fn get_physics_params(&self) -> PhysicsParams {
+        self.params
+    }
+
+    pub fn snapshot(&self) -> PhysicWorldSnapshot {
+        PhysicWorldSnapshot {
+            blob_positions : self.blob_positions,
+            blob_velocities : self.blob_velocities,
+            blobs_animation_states : self.blobs_animation_states,
+            blobs_animation_speed : self.blobs_animation_speed,
+
+            balls : self.balls.clone(),
+
+            player_inputs : self.player_inputs,
+
+            is_game_running : self.is_game_running,
+            time_since_ball_out: self.time_since_ball_out,
+
+            wind : self.wind,
+        }
+    }
+
+    // only complete with the characters of DuelMatch::restore
+    pub(crate) fn restore(&mut self, snapshot : &PhysicWorldSnapshot) {
+        self.blob_positions = snapshot.blob_positions;
+        self.blob_velocities = snapshot.blob_velocities;
+        self.blobs_animation_states = snapshot.blobs_animation_states;
+        self.blobs_animation_speed = snapshot.blobs_animation_speed;
+
+        self.balls = snapshot.balls.clone();
+
+        self.player_inputs = snapshot.player_inputs;
+
+        self.is_game_running = snapshot.is_game_running;
+        self.time_since_ball_out = snapshot.time_since_ball_out;
+
+        self.wind = snapshot.wind;
+    }
+
     pub fn get_blob_positions(&self) -> [Vector2f; 2] {
         self.blob_positions
     }
@@ -63,166 +157,238 @@ impl PhysicWorld {
         self.blob_velocities
     }
 
+    pub fn get_ball_count(&self) -> usize {
+        self.balls.len()
+    }
+
+    // Adds or removes balls, the new ones being placed at the next reset
+    pub fn set_ball_count(&mut self, ball_count : usize) {
+        let ball_count = if ball_count == 0 { 1 } else { ball_count.min(MAX_BALL_COUNT) };
+        self.balls.resize(ball_count, Ball::new());
+    }
+
+    pub fn get_balls(&self) -> &[Ball] {
+        &self.balls
+    }
+
+    pub fn get_ball_mut(&mut self, index : usize) -> &mut Ball {
+        &mut self.balls[index]
+    }
+
+    // Future states of the given ball from the current state of the world,
+    // see BallPredictor
+    pub fn predict_ball_steps<'a>(&'a self, ball : Ball) -> BallPredictor<'a> {
+        BallPredictor::new(ball, self.wind, self.is_game_running, &self.params)
+    }
+
+    pub fn predict_ball_from(&self, ball : Ball, max_steps : usize) -> BallPrediction {
+        BallPrediction::new(self.predict_ball_steps(ball), max_steps)
+    }
+
+    // Trajectory of the ball of the given index over the next max_steps
+    // steps and where it first meets the ground, the walls and the net
+    pub fn predict_ball(&self, ball : usize, max_steps : usize) -> BallPrediction {
+        self.predict_ball_from(self.balls[ball], max_steps)
+    }
+
+    // Same for every ball, in the order of get_balls
+    pub fn predict_balls(&self, max_steps : usize) -> Vec<BallPrediction> {
+        self.balls.iter().map(|ball| self.predict_ball_from(*ball, max_steps)).collect()
+    }
+
+    // The ball accessors below are about the first ball, the only one of
+    // the classic game
+
     pub fn get_ball_position(&self) -> Vector2f {
-        self.ball_position
+        self.balls[0].position
     }
 
     pub fn set_ball_position(&mut self, ball_position : Vector2f) {
-        self.ball_position = ball_position;
+        self.balls[0].position = ball_position;
     }
 
     pub fn get_ball_velocity(&self) -> Vector2f {
-        self.ball_velocity
+        self.balls[0].velocity
     }
 
     pub fn set_ball_velocity(&mut self, ball_velocity : Vector2f) {
-        self.ball_velocity = ball_velocity;
+        self.balls[0].velocity = ball_velocity;
+    }
+
+    // relative speed of the ball and the blob at the last blob hit
+    pub fn get_last_hit_intensity(&self) -> f32 {
+        self.balls[0].last_hit_intensity
+    }
+
+    pub fn get_ball_spin(&self) -> f32 {
+        self.balls[0].spin
+    }
+
+    pub fn set_ball_spin(&mut self, ball_spin : f32) {
+        self.balls[0].spin = ball_spin;
     }
 
     pub fn get_ball_rotation(&self) -> f32 {
-        self.ball_rotation
+        self.balls[0].rotation
     }
 
     pub fn is_ball_valid(&self) -> bool {
-        self.is_ball_valid
+        self.balls[0].is_valid
     }
 
     pub fn set_ball_validity(&mut self, validity: bool) {
-        self.is_ball_valid = validity;
+        self.balls[0].is_valid = validity;
     }
 
-    pub fn is_game_running(&self) -> bool {
-        self.is_game_running
+    pub fn get_ball_speed(&self) -> f32 {
+        self.balls[0].get_speed()
     }
 
-    pub fn set_game_running(&mut self, game_running : bool) {
-        self.is_game_running = game_running;
+    pub fn damp_ball(&mut self) {
+        self.balls[0].damp(&self.params);
     }
 
-    pub fn get_blob(&self, player: PlayerSide) -> Vector2f {
-        self.blob_positions[player as usize]
+    pub fn ball_hit_left_player(&self) -> bool {
+        self.balls[0].hit_player(LeftPlayer)
     }
 
-    pub fn get_blob_state(&self, player: PlayerSide) -> f32 {
-        self.blobs_animation_states[player as usize]
+    pub fn ball_hit_right_player(&self) -> bool {
+        self.balls[0].hit_player(RightPlayer)
     }
 
-    pub fn reset_player(&mut self) {
-        self.blob_positions[LeftPlayer as usize] =
-            Vector2f::new(LEFT_SPAWN_POS_X as f32, GROUND_PLANE_HEIGHT);
+    pub fn ball_hit_right_ground(&self) -> bool {
+        self.balls[0].hit_right_ground(&self.params.arena)
+    }
 
-        self.blob_positions[RightPlayer as usize] =
-            Vector2f::new(RIGHT_SPAWN_POS_X as f32, GROUND_PLANE_HEIGHT);
+    pub fn ball_hit_left_ground(&self) -> bool {
+        self.balls[0].hit_left_ground(&self.params.arena)
     }
 
-    pub fn reset(&mut self, player: PlayerSide) {
-        match player {
-            LeftPlayer =>
-                self.ball_position =
-                    Vector2f::new(BALL_SPAWN_POS_X as f32, STANDARD_BALL_HEIGHT),
-            RightPlayer =>
-                self.ball_position =
-                    Vector2f::new(RIGHT_SPAWN_POS_X as f32, STANDARD_BALL_HEIGHT),
-            NoPlayer =>
-                self.ball_position =
-                    Vector2f::new(BALL_MIDDLE_SPAWN_X as f32, BALL_MIDDLE_SPAWN_Y as f32),
-        }
+    pub fn ball_hit_wall(&self) -> bool {
+        self.balls[0].hit_wall
+    }
 
-        self.reset_player();
+    pub fn ball_hit_net_side(&self) -> bool {
+        self.balls[0].hit_net_side
+    }
 
-        self.ball_velocity.clear();
+    pub fn ball_hit_net_sphere(&self) -> bool {
+        self.balls[0].hit_net_sphere
+    }
 
-        self.ball_rotation = 0.0f32;
-        self.ball_angular_velocity = STANDARD_BALL_ANGULAR_VELOCITY;
+    // whether a ball is still in play in the current rally
+    pub fn is_any_ball_valid(&self) -> bool {
+        self.balls.iter().any(|ball| ball.is_valid)
+    }
 
-        self.blobs_animation_states[LeftPlayer as usize] = 0.0f32;
-        self.blobs_animation_states[RightPlayer as usize] = 0.0f32;
+    pub fn get_wind_state(&self) -> WindState {
+        self.wind
+    }
 
-        self.is_game_running = false;
-        self.is_ball_valid = true;
+    pub fn set_wind_state(&mut self, wind : WindState) {
+        self.wind = wind;
+    }
 
-        self.last_hit_intensity = 0.0;
+    // Restarts the gusts from the given seed, before a match is recorded so
+    // that each match blows its own wind
+    pub fn seed_wind(&mut self, seed : u32) {
+        self.wind = WindState::new(seed);
     }
 
-    pub fn get_ball_speed(&self) -> f32 {
-        self.ball_velocity.length()
+    // current horizontal acceleration of the ball by the wind
+    pub fn get_wind_force(&self) -> f32 {
+        self.wind.get_force(&self.params)
     }
 
-    fn player_bottom_ball_collision(&mut self, player : PlayerSide) -> bool {
-        let player_index = player as usize;
+    pub fn is_game_running(&self) -> bool {
+        self.is_game_running
+    }
 
-        let pos =
-            Vector2f::new
-            (
-                self.blob_positions[player_index].x,
-                self.blob_positions[player_index].y + BLOBBY_LOWER_SPHERE
-            );
+    pub fn set_game_running(&mut self, game_running : bool) {
+        self.is_game_running = game_running;
+    }
 
-        if (pos - self.ball_position).length() <= BALL_RADIUS + BLOBBY_LOWER_RADIUS
-        {
-            return true;
-        }
-        false
+    pub fn get_blob(&self, player: PlayerSide) -> Vector2f {
+        self.blob_positions[player as usize]
     }
 
-    fn player_top_ball_collision(&mut self, player : PlayerSide) -> bool {
-        let player_index = player as usize;
+    pub fn get_character(&self, side : PlayerSide) -> Character {
+        self.characters[side as usize]
+    }
 
-        let pos =
-            Vector2f::new
-            (
-                self.blob_positions[player_index].x,
-                self.blob_positions[player_index].y - BLOBBY_UPPER_SPHERE
-            );
+    pub fn get_characters(&self) -> [Character; 2] {
+        self.characters
+    }
 
-        if (pos - self.ball_position).length() <= BALL_RADIUS + BLOBBY_UPPER_RADIUS
-        {
-		    return true;
-        }
+    // Changes the body of the blob of a side, taking effect right away
+    pub fn set_character(&mut self, side : PlayerSide, character : Character) {
+        self.characters[side as usize] = character;
+    }
 
-        false
+    pub fn get_blob_state(&self, player: PlayerSide) -> f32 {
+        self.blobs_animation_states[player as usize]
     }
 
-    pub fn check_blobby_ball_collision(&mut self, player : PlayerSide) {
-        let player_index = player as usize;
+    pub fn reset_player(&mut self) {
+        let arena = self.params.arena;
 
-        // Check for bottom circles
-        if self.player_bottom_ball_collision(player) {
-            self.last_hit_intensity =
-                (self.ball_velocity - self.blob_velocities[player_index]).length();
+        self.blob_positions[LeftPlayer as usize] =
+            Vector2f::new(
+                arena.left_spawn_x,
+                self.characters[LeftPlayer as usize].get_ground_height(&arena)
+            );
 
-            let blob_pos = self.blob_positions[player_index];
-            let circle_pos : Vector2f =
-                Vector2f::new(blob_pos.x, blob_pos.y + BLOBBY_LOWER_SPHERE);
+        self.blob_positions[RightPlayer as usize] =
+            Vector2f::new(
+                arena.right_spawn_x,
+                self.characters[RightPlayer as usize].get_ground_height(&arena)
+            );
+    }
 
-            self.ball_velocity = -(circle_pos - self.ball_position);
-            self.ball_velocity = self.ball_velocity.normalized();
-            self.ball_velocity = self.ball_velocity.scale(BALL_COLLISION_VELOCITY);
-            self.ball_position += self.ball_velocity;
-            self.ball_hit_by_blobs[player_index] = true;
+    pub fn reset(&mut self, player: PlayerSide) {
+        let arena = self.params.arena;
+        let ball_position =
+            match player {
+                LeftPlayer =>
+                    Vector2f::new(arena.left_spawn_x, arena.ball_spawn_y),
+                RightPlayer =>
+                    Vector2f::new(arena.right_spawn_x, arena.ball_spawn_y),
+                NoPlayer => arena.get_middle_spawn(),
+            };
+
+        self.balls[0].reset(ball_position, &self.params);
+
+        // the other balls wait above the blobs, alternately above the
+        // receiver and the server, higher and higher
+        let serving_side = if player == RightPlayer { RightPlayer } else { LeftPlayer };
+        for index in 1..self.balls.len() {
+            let x =
+                if (index % 2 == 1) == (serving_side == LeftPlayer) {
+                    arena.right_spawn_x
+                }
+                else {
+                    arena.left_spawn_x
+                };
+            let row = index - index / 2;
+            let y = arena.ball_spawn_y - MULTI_BALL_SPAWN_SPACING * row as f32;
 
+            self.balls[index].reset(Vector2f::new(x, y), &self.params);
         }
-        else if self.player_top_ball_collision(player) {
-            self.last_hit_intensity =
-                (self.ball_velocity - self.blob_velocities[player_index]).length();
-
-            let blob_pos = self.blob_positions[player_index];
-            let circle_pos : Vector2f =
-                 Vector2f::new(blob_pos.x, blob_pos.y - BLOBBY_UPPER_SPHERE);
-
-            self.ball_velocity = -(circle_pos - self.ball_position);
-            self.ball_velocity = self.ball_velocity.normalized();
-            self.ball_velocity = self.ball_velocity.scale(BALL_COLLISION_VELOCITY);
-            self.ball_position += self.ball_velocity;
-            self.ball_hit_by_blobs[player_index] = true;
-        }
 
+        self.reset_player();
+
+        self.blobs_animation_states[LeftPlayer as usize] = 0.0f32;
+        self.blobs_animation_states[RightPlayer as usize] = 0.0f32;
+
+        self.is_game_running = false;
     }
 
     fn blobby_hit_ground(&self, player : PlayerSide) -> bool {
         match player {
             NoPlayer => return false,
-            _ => self.get_blob(player).y >= GROUND_PLANE_HEIGHT,
+            _ =>
+                self.get_blob(player).y >=
+                self.characters[player as usize].get_ground_height(&self.params.arena),
         }
     }
 
@@ -230,14 +396,10 @@ impl PhysicWorld {
         let player_index = player as usize;
 
         if self.blobs_animation_speed[player_index] == 0.0f32 {
-            self.blobs_animation_speed[player_index] = BLOBBY_ANIMATION_SPEED;
+            self.blobs_animation_speed[player_index] = self.params.blobby_animation_speed;
         }
     }
 
-    pub fn damp_ball(&mut self) {
-        self.ball_velocity = self.ball_velocity.scale(DAMP_BALL_SCALE_FACTOR);
-    }
-
     fn blobby_animation_step(&mut self, player : PlayerSide) {
         let player_index = player as usize;
 
@@ -247,11 +409,11 @@ impl PhysicWorld {
         }
 
         if self.blobs_animation_states[player_index] >= BLOBBY_ANIMATION_FRAMES as f32 - 1.0f32 {
-            self.blobs_animation_speed[player_index] = -BLOBBY_ANIMATION_SPEED;
+            self.blobs_animation_speed[player_index] = -self.params.blobby_animation_speed;
         }
 
         self.blobs_animation_states[player_index] +=
-            self.blobs_animation_speed[player_index] * TIME_SCALING;
+            self.blobs_animation_speed[player_index] * self.params.time_scaling;
 
         if self.blobs_animation_states[player_index] > BLOBBY_ANIMATION_FRAMES as f32 - 1.0f32 {
             self.blobs_animation_states[player_index] = BLOBBY_ANIMATION_FRAMES as f32 - 1.0f32;
@@ -268,15 +430,14 @@ impl PhysicWorld {
 
     fn handle_blob(&mut self, player : PlayerSide) {
         let player_index = player as usize;
-
-        self.ball_hit_by_blobs[player_index] = false;
+        let character = self.characters[player_index];
 
         if self.player_inputs[player_index].up {
             if self.blobby_hit_ground(player) {
-                self.blob_velocities[player_index].y = - BLOBBY_JUMP_ACCELERATION;
+                self.blob_velocities[player_index].y = - character.get_jump_acceleration(&self.params);
                 self.blobby_start_animation(player);
             }
-            self.blob_velocities[player_index].y -= BLOBBY_JUMP_BUFFER * TIME_SCALING;
+            self.blob_velocities[player_index].y -= character.get_jump_buffer(&self.params) * self.params.time_scaling;
         }
 
         if
@@ -286,57 +447,37 @@ impl PhysicWorld {
             }
 
         self.blob_velocities[player_index].x =
-            if self.player_inputs[player_index].right { BLOBBY_SPEED } else { 0.0f32 } -
-            if self.player_inputs[player_index].left { BLOBBY_SPEED } else { 0.0f32 };
+            if self.player_inputs[player_index].right { character.get_speed(&self.params) } else { 0.0f32 } -
+            if self.player_inputs[player_index].left { character.get_speed(&self.params) } else { 0.0f32 };
 
         // Acceleration Integration
-        self.blob_velocities[player_index].y += BLOBBY_GRAVITATION * TIME_SCALING;
+        self.blob_velocities[player_index].y += self.params.blobby_gravitation * self.params.time_scaling;
 
         // Compute new position
         self.blob_positions[player_index] +=
-            self.blob_velocities[player_index] * TIME_SCALING;
+            self.blob_velocities[player_index] * self.params.time_scaling;
 
         if self.blobby_hit_ground(player) {
             if self.blob_velocities[player_index].y > 3.5f32 {
                 self.blobby_start_animation(player);
             }
 
-            self.blob_positions[player_index].y = GROUND_PLANE_HEIGHT;
+            self.blob_positions[player_index].y = character.get_ground_height(&self.params.arena);
             self.blob_velocities[player_index].y = 0.0f32;
 
         }
 
-        self.blobby_animation_step(player);
-    }
-
-    pub fn ball_hit_left_player(&self) -> bool {
-        self.ball_hit_by_blobs[LeftPlayer as usize]
-    }
+        // Collision between blobby and the ceiling
+        if let Some(ceiling) = self.params.arena.ceiling {
+            let highest_y = ceiling + character.get_upper_sphere() + character.get_upper_radius();
 
-    pub fn ball_hit_right_player(&self) -> bool {
-        self.ball_hit_by_blobs[RightPlayer as usize]
-    }
-
-    pub fn ball_hit_right_ground(&self) -> bool {
-        if self.is_ball_valid {
-            if
-                self.ball_position.y > GROUND_PLANE_HEIGHT &&
-                self.ball_position.x > NET_POSITION_X {
-                    return true;
+            if self.blob_positions[player_index].y < highest_y {
+                self.blob_positions[player_index].y = highest_y;
+                self.blob_velocities[player_index].y = 0.0f32;
             }
         }
-        false
-    }
 
-    pub fn ball_hit_left_ground(&self) -> bool {
-        if self.is_ball_valid {
-            if
-                self.ball_position.y > GROUND_PLANE_HEIGHT &&
-                self.ball_position.x < NET_POSITION_X {
-                    return true;
-            }
-        }
-        false
+        self.blobby_animation_step(player);
     }
 
     fn reset_area_clear(&self) -> bool {
@@ -347,210 +488,92 @@ impl PhysicWorld {
         false
     }
 
+    // every ball is out of play and resting on the ground
     pub fn is_round_finished(&self) -> bool {
         if self.reset_area_clear() {
-            if !self.is_ball_valid {
-                if
-                    self.ball_velocity.y < 1.5f32 &&
-                    self.ball_velocity.y > -1.5f32 &&
-                    self.ball_position.y > 430f32 {
-                        return true;
-                }
-            }
+            let arena = &self.params.arena;
+            return self.balls.iter().all(|ball| ball.is_at_rest(arena));
         }
 
         false
     }
 
-    fn ball_move_on_the_ground(&mut self) {
-        self.ball_velocity =
-        self.ball_velocity
-        .reflect_y()
-        .scale_y(SPEED_SCALE_ON_GROUND_BOUNCE_Y);
-
-        self.ball_velocity =
-            self.ball_velocity.scale_x(SPEED_SCALE_ON_GROUND_BOUNCE_X);
-
-        self.ball_position.y = GROUND_PLANE_HEIGHT_MAX - BALL_RADIUS;
-    }
-
     pub fn step(&mut self) {
-
-        if self.is_game_running {
-            self.ball_velocity.y += BALL_GRAVITATION * TIME_SCALING;
+        for ball in self.balls.iter_mut() {
+            ball.clear_collisions();
         }
 
-        self.handle_blob(LeftPlayer);
-        self.handle_blob(RightPlayer);
-
-        self.ball_position += self.ball_velocity * TIME_SCALING;
-
-        // Collision detection
-        if self.is_ball_valid {
-            self.check_blobby_ball_collision(LeftPlayer);
-            self.check_blobby_ball_collision(RightPlayer);
-        }
-        // Ball to ground Collision
-        else if self.ball_position.y + BALL_RADIUS > GROUND_PLANE_HEIGHT_MAX {
-            self.ball_move_on_the_ground();
+        if self.is_game_running {
+            for ball in self.balls.iter_mut() {
+                ball.apply_forces(&self.wind, &self.params);
+            }
         }
 
-        if self.ball_hit_left_player() || self.ball_hit_right_player() {
-            self.is_game_running = true;
+        if self.params.has_wind_gusts() {
+            self.wind.step(self.params.time_scaling);
         }
 
-        // Border Collision
-        if
-            self.ball_position.x - BALL_RADIUS <= LEFT_PLANE &&
-            self.ball_velocity.x < 0.0
-        {
-            self.ball_velocity = self.ball_velocity.reflect_x();
-            // set the ball's position
-            self.ball_position.x = LEFT_PLANE + BALL_RADIUS;
-        }
-        else if
-            self.ball_position.x + BALL_RADIUS >= RIGHT_PLANE &&
-            self.ball_velocity.x > 0.0
-        {
-            self.ball_velocity = self.ball_velocity.reflect_x();
-            // set the ball's position
-            self.ball_position.x = RIGHT_PLANE - BALL_RADIUS;
-        }
-        else if
+        self.handle_blob(LeftPlayer);
+        self.handle_blob(RightPlayer);
 
-            self.ball_position.y > NET_SPHERE_POSITION &&
-            (self.ball_position.x - NET_POSITION_X).abs() < BALL_RADIUS + NET_RADIUS
-        {
-            self.ball_velocity = self.ball_velocity.reflect_x();
-            // set the ball's position so that it touches the net
-            let delta =
-                if self.ball_position.x - NET_POSITION_X > 0.0f32 {
-                    BALL_RADIUS + NET_RADIUS
-                }
-                else {
-                    -BALL_RADIUS - NET_RADIUS
-                };
+        for ball in self.balls.iter_mut() {
+            // a fast ball moves in several sub-steps, each one short enough not
+            // to go through the net or a blob between two collision checks
+            let ball_step_count = ball.get_step_count(&self.params);
+
+            for _ in 0..ball_step_count {
+                ball.step(
+                    1.0f32 / ball_step_count as f32,
+                    &self.blob_positions,
+                    &self.blob_velocities,
+                    &self.characters,
+                    &self.params
+                );
+            }
 
-            self.ball_position.x =  NET_POSITION_X + delta;
-        }
-        else
-        {
-            // Net Collisions
-
-            let ball_net_vec =
-                Vector2f::new(NET_POSITION_X, NET_SPHERE_POSITION) - self.ball_position;
-
-            let ball_net_distance = ball_net_vec.length();
-
-            if ball_net_distance < NET_RADIUS + BALL_RADIUS
-            {
-                let vec =
-                    Vector2f::new(NET_POSITION_X, NET_SPHERE_POSITION) -
-                    self.ball_position;
-                // calculate
-                let normal = vec.normalized();
-
-                // normal component of kinetic energy
-                let mut perpendicular_kinematic_energy =
-                    normal.dot_product(&self.ball_velocity);
-
-                perpendicular_kinematic_energy *= perpendicular_kinematic_energy;
-                // parallel component of kinetic energy
-                let mut parallel_kinematic_energy =
-                    self.ball_velocity.length() * self.ball_velocity.length() -
-                    perpendicular_kinematic_energy;
-
-                // the normal component is damped stronger than the parallel component
-                // the values are ~ 0.85² and ca. 0.95², because speed is sqrt(ekin)
-                perpendicular_kinematic_energy *=
-                    PERPENDICULAR_KINEMATIC_ENERGY_DAMPING_FACTOR;
-
-                parallel_kinematic_energy *=
-                    PARALLEL_KINEMATIC_ENERGY_DAMPING_FACTOR;
-
-                let n_speed =
-                    (perpendicular_kinematic_energy + parallel_kinematic_energy).sqrt();
-
-                self.ball_velocity =
-                    self.ball_velocity
-                    .reflect(&normal)
-                    .normalized()
-                    .scale(n_speed);
-
-                // pushes the ball out of the net
-                self.ball_position =
-                    Vector2f::new
-                    (
-                        NET_POSITION_X,
-                        NET_SPHERE_POSITION
-                    ) - normal * (NET_RADIUS + BALL_RADIUS);
+            if ball.hit_player(LeftPlayer) || ball.hit_player(RightPlayer) {
+                self.is_game_running = true;
             }
         }
 
+        let arena = self.params.arena;
+        let left_radius = self.characters[LeftPlayer as usize].get_lower_radius();
+        let right_radius = self.characters[RightPlayer as usize].get_lower_radius();
+
         // Collision between blobby and the net
         if
-            self.blob_positions[LeftPlayer as usize].x + BLOBBY_LOWER_RADIUS >
-            NET_POSITION_X - NET_RADIUS // Collision with the net
+            self.blob_positions[LeftPlayer as usize].x + left_radius >
+            arena.net_position_x - arena.net_radius // Collision with the net
         {
 		    self.blob_positions[LeftPlayer as usize].x =
-                NET_POSITION_X - NET_RADIUS - BLOBBY_LOWER_RADIUS;
+                arena.net_position_x - arena.net_radius - left_radius;
         }
 
         if
-            self.blob_positions[RightPlayer as usize].x - BLOBBY_LOWER_RADIUS <
-            NET_POSITION_X + NET_RADIUS
+            self.blob_positions[RightPlayer as usize].x - right_radius <
+            arena.net_position_x + arena.net_radius
         {
             self.blob_positions[RightPlayer as usize].x =
-                NET_POSITION_X + NET_RADIUS + BLOBBY_LOWER_RADIUS;
+                arena.net_position_x + arena.net_radius + right_radius;
         }
 
         // Collision between blobby and the border
-        if self.blob_positions[LeftPlayer as usize].x < LEFT_PLANE {
-            self.blob_positions[LeftPlayer as usize].x = LEFT_PLANE;
-        }
-
-        if self.blob_positions[RightPlayer as usize].x > RIGHT_PLANE {
-            self.blob_positions[RightPlayer as usize].x = RIGHT_PLANE;
-        }
-
-        // Velocity Integration
-        if self.ball_velocity.x > 0.0 {
-            self.ball_rotation +=
-                self.ball_angular_velocity * TIME_SCALING *
-                    (self.get_ball_speed() / BALL_ANGULAR_VELOCITY_SCALE_FACTOR);
-        }
-        else if self.ball_velocity.x < 0.0 {
-            self.ball_rotation -=
-                self.ball_angular_velocity * TIME_SCALING *
-                    (self.get_ball_speed() / BALL_ANGULAR_VELOCITY_SCALE_FACTOR);
-        }
-        else {
-            self.ball_rotation -=
-                self.ball_angular_velocity * TIME_SCALING;
+        if self.blob_positions[LeftPlayer as usize].x < arena.left_wall {
+            self.blob_positions[LeftPlayer as usize].x = arena.left_wall;
         }
 
-        // Overflow-Protection
-        if self.ball_rotation <= 0.0f32 {
-            self.ball_rotation = 6.25f32 + self.ball_rotation;
-        }
-        else if self.ball_rotation >= 6.25f32 {
-            self.ball_rotation = self.ball_rotation - 6.25f32;
+        if self.blob_positions[RightPlayer as usize].x > arena.right_wall {
+            self.blob_positions[RightPlayer as usize].x = arena.right_wall;
         }
 
-        // More Overflow-Protection
-        if self.ball_rotation <= 0.0f32 {
-            self.ball_rotation = 6.25f32;
-        }
-        else if self.ball_rotation >= 6.25f32 {
-            self.ball_rotation = 6.25f32;
+        for ball in self.balls.iter_mut() {
+            ball.update_rotation(&self.params);
         }
 
-        assert!(self.ball_rotation >= 0.0f32);
-
         self.time_since_ball_out =
-            if self.is_ball_valid
+            if self.is_any_ball_valid()
             { 0.0 }
             else
-            { self.time_since_ball_out + 1.0f32 / 60f32 }
+            { self.time_since_ball_out + 1.0f32 / STEPS_PER_SECOND as f32 }
     }
 }
@@ -0,0 +1,282 @@
+use game_constants::*;
+use arena::{Arena, Obstacle, MAX_ARENA_OBSTACLES, OBSTACLE_VALUE_COUNT};
+use character::Character;
+
+// values of PhysicsParams::to_array before the obstacles of the arena
+const SCALAR_PHYSICS_PARAM_COUNT : usize = 30;
+
+// speed, jump and size of each character
+const CHARACTER_VALUE_COUNT : usize = 3;
+
+// number of values in PhysicsParams::to_array
+pub const PHYSICS_PARAM_COUNT : usize =
+    SCALAR_PHYSICS_PARAM_COUNT +
+    MAX_ARENA_OBSTACLES * OBSTACLE_VALUE_COUNT +
+    2 * CHARACTER_VALUE_COUNT;
+
+// Tuning values of the simulation. The default is the classic Blobby Volley
+// physics, other values give game variants without recompiling.
+//
+// The ball radius and the collision spheres of the blobs stay constants:
+// the blobs are resized through their characters, while the ball sprite,
+// the aim of the bot and the checks of the arena files all assume
+// BALL_RADIUS. Both are part of physics_constants_hash, so replays notice
+// a build that changed them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsParams {
+    // Blobby Settings
+    pub blobby_jump_buffer : f32,
+    pub blobby_gravitation : f32,
+    pub blobby_jump_acceleration : f32,
+    pub blobby_speed : f32,
+    pub blobby_animation_speed : f32,
+
+    // Ball Settings
+    pub ball_gravitation : f32,
+    pub ball_collision_velocity : f32,
+    pub standard_ball_angular_velocity : f32,
+    pub ball_angular_velocity_scale_factor : f32,
+
+    // Damping applied on errors, ground bounces and net collisions
+    pub damp_ball_scale_factor : f32,
+    pub speed_scale_on_ground_bounce_x : f32,
+    pub speed_scale_on_ground_bounce_y : f32,
+    pub perpendicular_kinematic_energy_damping_factor : f32,
+    pub parallel_kinematic_energy_damping_factor : f32,
+
+    // speed of the whole simulation
+    pub time_scaling : f32,
+
+    // strength of the spin model, 0 for the classic game where the rotation
+    // of the ball is only drawn, 1 for the model as tuned in game_constants
+    pub spin_factor : f32,
+
+    // Environment, all 0 in the classic game: constant horizontal wind
+    // acceleration of the ball, positive to the right, amplitude of the
+    // random gusts added to it and linear air drag of the ball
+    pub wind_force : f32,
+    pub wind_gust_force : f32,
+    pub air_drag : f32,
+
+    // geometry of the field
+    pub arena : Arena,
+
+    // bodies of the blobs, by the side each player starts the match on
+    pub characters : [Character; 2],
+}
+
+impl Default for PhysicsParams {
+    fn default() -> PhysicsParams {
+        PhysicsParams {
+            blobby_jump_buffer : BLOBBY_JUMP_BUFFER,
+            blobby_gravitation : BLOBBY_GRAVITATION,
+            blobby_jump_acceleration : BLOBBY_JUMP_ACCELERATION,
+            blobby_speed : BLOBBY_SPEED,
+            blobby_animation_speed : BLOBBY_ANIMATION_SPEED,
+
+            ball_gravitation : BALL_GRAVITATION,
+            ball_collision_velocity : BALL_COLLISION_VELOCITY,
+            standard_ball_angular_velocity : STANDARD_BALL_ANGULAR_VELOCITY,
+            ball_angular_velocity_scale_factor : BALL_ANGULAR_VELOCITY_SCALE_FACTOR,
+
+            damp_ball_scale_factor : DAMP_BALL_SCALE_FACTOR,
+            speed_scale_on_ground_bounce_x : SPEED_SCALE_ON_GROUND_BOUNCE_X,
+            speed_scale_on_ground_bounce_y : SPEED_SCALE_ON_GROUND_BOUNCE_Y,
+            perpendicular_kinematic_energy_damping_factor : PERPENDICULAR_KINEMATIC_ENERGY_DAMPING_FACTOR,
+            parallel_kinematic_energy_damping_factor : PARALLEL_KINEMATIC_ENERGY_DAMPING_FACTOR,
+
+            time_scaling : TIME_SCALING,
+
+            spin_factor : 0.0f32,
+
+            wind_force : 0.0f32,
+            wind_gust_force : 0.0f32,
+            air_drag : 0.0f32,
+
+            arena : Arena::default(),
+
+            characters : [Character::default(); 2],
+        }
+    }
+}
+
+impl PhysicsParams {
+    // Every value in a fixed order, used to hash and serialize the parameters
+    pub fn to_array(&self) -> [f32; PHYSICS_PARAM_COUNT] {
+        // destructured so that a new field does not build until it is added
+        let PhysicsParams {
+            blobby_jump_buffer,
+            blobby_gravitation,
+            blobby_jump_acceleration,
+            blobby_speed,
+            blobby_animation_speed,
+            ball_gravitation,
+            ball_collision_velocity,
+            standard_ball_angular_velocity,
+            ball_angular_velocity_scale_factor,
+            damp_ball_scale_factor,
+            speed_scale_on_ground_bounce_x,
+            speed_scale_on_ground_bounce_y,
+            perpendicular_kinematic_energy_damping_factor,
+            parallel_kinematic_energy_damping_factor,
+            time_scaling,
+            spin_factor,
+            wind_force,
+            wind_gust_force,
+            air_drag,
+            arena,
+            characters,
+        } = *self;
+
+        let Arena {
+            left_wall,
+            right_wall,
+            net_position_x,
+            net_sphere_position,
+            net_radius,
+            ground_height,
+            ceiling,
+            left_spawn_x,
+            right_spawn_x,
+            ball_spawn_y,
+            obstacles,
+        } = arena;
+
+        let mut values = vec!(
+            blobby_jump_buffer,
+            blobby_gravitation,
+            blobby_jump_acceleration,
+            blobby_speed,
+            blobby_animation_speed,
+            ball_gravitation,
+            ball_collision_velocity,
+            standard_ball_angular_velocity,
+            ball_angular_velocity_scale_factor,
+            damp_ball_scale_factor,
+            speed_scale_on_ground_bounce_x,
+            speed_scale_on_ground_bounce_y,
+            perpendicular_kinematic_energy_damping_factor,
+            parallel_kinematic_energy_damping_factor,
+            time_scaling,
+            spin_factor,
+            wind_force,
+            wind_gust_force,
+            air_drag,
+            left_wall,
+            right_wall,
+            net_position_x,
+            net_sphere_position,
+            net_radius,
+            ground_height,
+            // 1 then the ceiling, or 0 then 0 for no ceiling
+            if ceiling.is_some() { 1.0f32 } else { 0.0f32 },
+            ceiling.unwrap_or(0.0f32),
+            left_spawn_x,
+            right_spawn_x,
+            ball_spawn_y,
+        );
+
+        // then the obstacles, slot after slot
+        for obstacle in obstacles.iter() {
+            values.extend_from_slice(&Obstacle::to_values(obstacle));
+        }
+
+        // then the characters of both players
+        for character in characters.iter() {
+            let Character { speed, jump, size } = *character;
+            values.extend_from_slice(&[speed, jump, size]);
+        }
+
+        let mut array = [0.0f32; PHYSICS_PARAM_COUNT];
+        array.copy_from_slice(&values);
+        array
+    }
+
+    pub fn from_array(values : [f32; PHYSICS_PARAM_COUNT]) -> PhysicsParams {
+        // the fields are read in the order they are written, the one of
+        // to_array
+        let mut values = values.iter().cloned();
+        let mut next = || values.next().unwrap();
+
+        PhysicsParams {
+            blobby_jump_buffer : next(),
+            blobby_gravitation : next(),
+            blobby_jump_acceleration : next(),
+            blobby_speed : next(),
+            blobby_animation_speed : next(),
+            ball_gravitation : next(),
+            ball_collision_velocity : next(),
+            standard_ball_angular_velocity : next(),
+            ball_angular_velocity_scale_factor : next(),
+            damp_ball_scale_factor : next(),
+            speed_scale_on_ground_bounce_x : next(),
+            speed_scale_on_ground_bounce_y : next(),
+            perpendicular_kinematic_energy_damping_factor : next(),
+            parallel_kinematic_energy_damping_factor : next(),
+            time_scaling : next(),
+            spin_factor : next(),
+            wind_force : next(),
+            wind_gust_force : next(),
+            air_drag : next(),
+            arena : Arena {
+                left_wall : next(),
+                right_wall : next(),
+                net_position_x : next(),
+                net_sphere_position : next(),
+                net_radius : next(),
+                ground_height : next(),
+                ceiling : read_ceiling(&mut next),
+                left_spawn_x : next(),
+                right_spawn_x : next(),
+                ball_spawn_y : next(),
+                obstacles : read_obstacles(&mut next),
+            },
+            characters : [read_character(&mut next), read_character(&mut next)],
+        }
+    }
+
+    pub fn is_spin_enabled(&self) -> bool {
+        self.spin_factor != 0.0f32
+    }
+
+    pub fn has_wind(&self) -> bool {
+        self.wind_force != 0.0f32 || self.has_wind_gusts()
+    }
+
+    pub fn has_wind_gusts(&self) -> bool {
+        self.wind_gust_force != 0.0f32
+    }
+
+    pub fn has_air_drag(&self) -> bool {
+        self.air_drag != 0.0f32
+    }
+}
+
+fn read_obstacles<F : FnMut() -> f32>(next : &mut F) -> [Option<Obstacle>; MAX_ARENA_OBSTACLES] {
+    let mut obstacles = [None; MAX_ARENA_OBSTACLES];
+
+    for obstacle in obstacles.iter_mut() {
+        let mut values = [0.0f32; OBSTACLE_VALUE_COUNT];
+        for value in values.iter_mut() {
+            *value = next();
+        }
+        *obstacle = Obstacle::from_values(&values);
+    }
+
+    obstacles
+}
+
+fn read_ceiling<F : FnMut() -> f32>(next : &mut F) -> Option<f32> {
+    let has_ceiling = next() == 1.0f32;
+    let ceiling = next();
+
+    if has_ceiling { Some(ceiling) } else { None }
+}
+
+fn read_character<F : FnMut() -> f32>(next : &mut F) -> Character {
+    Character {
+        speed : next(),
+        jump : next(),
+        size : next(),
+    }
+}
@@ -0,0 +1,128 @@
+use duel_match::{DuelMatch, FrameEvent, MatchSnapshot};
+use global::PlayerSide::*;
+use global::PlayerKind;
+use physics_params::PhysicsParams;
+use player_input::PlayerInput;
+
+// A recorded match: the state it started from and the inputs of both
+// players for every step. The simulation itself uses no randomness (the
+// bots only influence it through their inputs), so this is enough to
+// replay the match frame by frame, events included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    // who played, by player, and the difficulty of the computer players
+    pub player_kinds : [PlayerKind; 2],
+    pub bot_difficulties : [i32; 2],
+    pub physics_params : PhysicsParams,
+    pub initial_state : MatchSnapshot,
+    // inputs of the left and right player, one entry per DuelMatch::step
+    pub frames : Vec<[PlayerInput; 2]>,
+    // number of frames already recorded when each rally ended (a point was
+    // scored, or the serve changed side with side-out scoring)
+    pub point_boundaries : Vec<usize>,
+}
+
+impl Replay {
+    pub fn new(initial_state : MatchSnapshot, physics_params : PhysicsParams) -> Replay {
+        Replay {
+            player_kinds : [PlayerKind::Human, PlayerKind::Human],
+            bot_difficulties : [0; 2],
+            physics_params : physics_params,
+            initial_state : initial_state,
+            frames : vec!(),
+            point_boundaries : vec!(),
+        }
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+pub struct ReplayRecorder {
+    replay : Replay,
+}
+
+impl ReplayRecorder {
+    pub fn new(initial_state : MatchSnapshot, physics_params : PhysicsParams) -> ReplayRecorder {
+        ReplayRecorder {
+            replay : Replay::new(initial_state, physics_params),
+        }
+    }
+
+    // Who plays the recorded match, given with the starting state
+    pub fn set_players(&mut self, player_kinds : [PlayerKind; 2], bot_difficulties : [i32; 2]) {
+        self.replay.player_kinds = player_kinds;
+        self.replay.bot_difficulties = bot_difficulties;
+    }
+
+    pub fn record_frame(&mut self, left_input : PlayerInput, right_input : PlayerInput) {
+        self.replay.frames.push([left_input, right_input]);
+    }
+
+    pub fn record_point_boundary(&mut self) {
+        self.replay.point_boundaries.push(self.replay.frames.len());
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn finish(self) -> Replay {
+        self.replay
+    }
+}
+
+pub struct ReplayPlayer {
+    replay : Replay,
+    frame_index : usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay : Replay) -> ReplayPlayer {
+        ReplayPlayer {
+            replay : replay,
+            frame_index : 0,
+        }
+    }
+
+    // Creates a match in the state the replay was recorded from
+    pub fn create_match(&self) -> DuelMatch {
+        let mut duel_match =
+            DuelMatch::new(self.replay.physics_params, self.replay.initial_state.game_logic.rules);
+        duel_match.restore(&self.replay.initial_state);
+        duel_match
+    }
+
+    pub fn rewind(&mut self) {
+        self.frame_index = 0;
+    }
+
+    pub fn get_frame_index(&self) -> usize {
+        self.frame_index
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame_index >= self.replay.frames.len()
+    }
+
+    // Feeds the recorded inputs of the next frame and steps the match.
+    // Returns false once every recorded frame has been played.
+    pub fn step(&mut self, duel_match : &mut DuelMatch, events : &mut Vec<FrameEvent>) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        let inputs = self.replay.frames[self.frame_index];
+        duel_match.get_world().set_player_input(LeftPlayer, inputs[0]);
+        duel_match.get_world().set_player_input(RightPlayer, inputs[1]);
+        duel_match.step(events);
+
+        self.frame_index += 1;
+        true
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+}
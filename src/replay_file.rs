@@ -0,0 +1,741 @@
+// On-disk format of the replays (.rvr files)
+//
+// All numbers are little endian.
+//
+// header:
+//   magic               4 bytes, "RVR\0"
+//   format version      u16
+//   physics hash        u64, see physics_constants_hash
+//   rules               score to win, win margin, score cap (0 for none),
+//                       touches allowed and squish tolerance as i32, the
+//                       first serving player as i8, the scoring mode as u8
+//                       (0: rally, 1: side-out), the set count as i32,
+//                       whether sides are swapped between sets as u8, the
+//                       time limit in steps as i32 (0 for none), the ball
+//                       count as i32, whether it is a practice and whether
+//                       touches are unlimited as u8, then the handicaps of
+//                       player 1 and player 2: head start, touch penalty and
+//                       speed penalty as i32
+//   player kinds        2 x u8 (0: human, 1: computer)
+//   bot difficulties    2 x i32, by player like the kinds
+//   physics parameters  their count as u32 then as many f32, in
+//                       PhysicsParams::to_array order
+//   frame count         u32
+//   point count         u32
+// initial state:
+//   the PhysicWorldSnapshot: the positions and velocities of the blobs, their
+//   animation states and speeds, the inputs, the running flag, the time since
+//   the ball out and the wind state (the random state as u32, the gust and
+//   its target as f32 and the steps to the next gust as i32), then the balls
+//   as a u32 count followed by every field of each Ball;
+//   the GameLogicSnapshot, except the rules already in the header: the
+//   scores, the touches and squish of the balls as a u32 count followed by
+//   i32 pairs, the last error, serving and winning players, the elapsed time
+//   and the error reason as u8 (0: the ground, 1: too many touches);
+//   the MatchLogicSnapshot, the finished sets being a u32 count followed by
+//   the scores as i32 pairs
+// frames:
+//   a sequence of records, either
+//   - an input run: one byte holding the inputs of both players (bits 0-2
+//     for the left player, 3-5 for the right one) followed by the number of
+//     frames it lasts as a LEB128 varint
+//   - a point boundary: a single POINT_BOUNDARY_MARKER byte, meaning that a
+//     rally ended after the frames read so far
+
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write, BufReader, BufWriter};
+use std::path::Path;
+
+use duel_match::MatchSnapshot;
+use game_constants::*;
+use game_logic::{ErrorReason, GameLogicSnapshot};
+use match_logic::MatchLogicSnapshot;
+use global::{PlayerKind, PlayerSide};
+use global::PlayerSide::*;
+use match_rules::{Handicap, MatchRules, ScoringMode};
+use environment::WindState;
+use physic_world::PhysicWorldSnapshot;
+use ball::Ball;
+use physics_params::{PhysicsParams, PHYSICS_PARAM_COUNT};
+use player_input::PlayerInput;
+use replay::Replay;
+use state_hash::StateHasher;
+use vector::Vector2f;
+
+pub const REPLAY_FILE_EXTENSION : &str = "rvr";
+pub const REPLAY_FORMAT_VERSION : u16 = 2;
+
+const REPLAY_MAGIC : [u8; 4] = [b'R', b'V', b'R', 0];
+const POINT_BOUNDARY_MARKER : u8 = 0xFF;
+
+const INPUT_LEFT_BIT : u8 = 1;
+const INPUT_RIGHT_BIT : u8 = 2;
+const INPUT_UP_BIT : u8 = 4;
+const INPUT_BITS_PER_PLAYER : u8 = 3;
+
+#[derive(Debug)]
+pub enum ReplayFileError {
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    PhysicsMismatch { expected : u64, found : u64 },
+    Corrupted(&'static str),
+}
+
+impl fmt::Display for ReplayFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayFileError::Io(error) =>
+                write!(f, "replay file i/o error: {}", error),
+            ReplayFileError::InvalidMagic =>
+                write!(f, "not a replay file"),
+            ReplayFileError::UnsupportedVersion(version) =>
+                write!(f, "unsupported replay format version {}", version),
+            ReplayFileError::PhysicsMismatch { expected, found } =>
+                write!(
+                    f,
+                    "replay recorded with other physics constants ({:016x} instead of {:016x})",
+                    found,
+                    expected
+                ),
+            ReplayFileError::Corrupted(reason) =>
+                write!(f, "corrupted replay file: {}", reason),
+        }
+    }
+}
+
+impl error::Error for ReplayFileError {}
+
+impl From<io::Error> for ReplayFileError {
+    fn from(error : io::Error) -> ReplayFileError {
+        ReplayFileError::Io(error)
+    }
+}
+
+// FNV-1a hash of the constants the simulation step reads, the way square
+// roots are computed and the parameters. Two builds only replay each
+// other's files if they agree on it. The classic field and rules are not
+// there: the parameters and the rules of the file replace them.
+pub fn physics_constants_hash(params : &PhysicsParams) -> u64 {
+    let float_constants = [
+        BLOBBY_HEIGHT,
+        BLOBBY_UPPER_SPHERE,
+        BLOBBY_UPPER_RADIUS,
+        BLOBBY_LOWER_SPHERE,
+        BLOBBY_LOWER_RADIUS,
+        BALL_RADIUS,
+        MAX_BALL_STEP_DISTANCE,
+        SPIN_BLOB_GRIP,
+        SPIN_GROUND_GRIP,
+        SPIN_NET_GRIP,
+        MAGNUS_COEFFICIENT,
+        SPIN_AIR_DAMPING,
+        WIND_GUST_RESPONSE,
+        BALL_MIDDLE_SPAWN_HEIGHT,
+        MULTI_BALL_SPAWN_SPACING,
+    ];
+
+    let integer_constants = [
+        BLOBBY_ANIMATION_FRAMES as i32,
+        MAX_BALL_SUB_STEPS as i32,
+        WIND_GUST_MIN_STEPS,
+        STEPS_PER_SECOND,
+        cfg!(feature = "deterministic") as i32,
+    ];
+
+    let mut hasher = StateHasher::new();
+
+    for value in float_constants.iter() {
+        hasher.write_f32(*value);
+    }
+
+    for value in integer_constants.iter() {
+        hasher.write_i32(*value);
+    }
+
+    hasher.write_bytes(&WIND_GUST_STEP_RANGE.to_le_bytes());
+
+    for value in params.to_array().iter() {
+        hasher.write_f32(*value);
+    }
+
+    hasher.finish()
+}
+
+pub fn save_replay(replay : &Replay, path : &Path) -> Result<(), ReplayFileError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_replay(replay, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_replay(path : &Path) -> Result<Replay, ReplayFileError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_replay(&mut reader)
+}
+
+pub fn write_replay<W: Write>(replay : &Replay, writer : &mut W) -> Result<(), ReplayFileError> {
+    writer.write_all(&REPLAY_MAGIC)?;
+    writer.write_all(&REPLAY_FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&physics_constants_hash(&replay.physics_params).to_le_bytes())?;
+    write_rules(writer, &replay.initial_state.game_logic.rules)?;
+    write_player_kind(writer, replay.player_kinds[0])?;
+    write_player_kind(writer, replay.player_kinds[1])?;
+    write_i32(writer, replay.bot_difficulties[0])?;
+    write_i32(writer, replay.bot_difficulties[1])?;
+    write_u32(writer, PHYSICS_PARAM_COUNT as u32)?;
+    for value in replay.physics_params.to_array().iter() {
+        write_f32(writer, *value)?;
+    }
+    write_u32(writer, replay.frames.len() as u32)?;
+    write_u32(writer, replay.point_boundaries.len() as u32)?;
+
+    write_physic_world_snapshot(writer, &replay.initial_state.physic_world)?;
+    write_game_logic_snapshot(writer, &replay.initial_state.game_logic)?;
+    write_match_logic_snapshot(writer, &replay.initial_state.match_logic)?;
+
+    let mut boundaries = replay.point_boundaries.iter().peekable();
+    let mut frame_index = 0;
+
+    loop {
+        while boundaries.peek() == Some(&&frame_index) {
+            boundaries.next();
+            writer.write_all(&[POINT_BOUNDARY_MARKER])?;
+        }
+
+        if frame_index >= replay.frames.len() {
+            break;
+        }
+
+        let bits = encode_inputs(&replay.frames[frame_index]);
+        let mut run_length = 1;
+
+        while
+            frame_index + run_length < replay.frames.len() &&
+            encode_inputs(&replay.frames[frame_index + run_length]) == bits &&
+            boundaries.peek() != Some(&&(frame_index + run_length))
+        {
+            run_length += 1;
+        }
+
+        writer.write_all(&[bits])?;
+        write_varint(writer, run_length as u32)?;
+        frame_index += run_length;
+    }
+
+    Ok(())
+}
+
+pub fn read_replay<R: Read>(reader : &mut R) -> Result<Replay, ReplayFileError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != REPLAY_MAGIC {
+        return Err(ReplayFileError::InvalidMagic);
+    }
+
+    let version = read_u16(reader)?;
+    if version != REPLAY_FORMAT_VERSION {
+        return Err(ReplayFileError::UnsupportedVersion(version));
+    }
+
+    let found_hash = read_u64(reader)?;
+    let rules = read_rules(reader)?;
+    let player_kinds = [read_player_kind(reader)?, read_player_kind(reader)?];
+    let bot_difficulties = [read_i32(reader)?, read_i32(reader)?];
+
+    if read_u32(reader)? as usize != PHYSICS_PARAM_COUNT {
+        return Err(ReplayFileError::Corrupted("invalid physics parameter count"));
+    }
+
+    let mut values = [0.0f32; PHYSICS_PARAM_COUNT];
+    for value in values.iter_mut() {
+        *value = read_f32(reader)?;
+    }
+    let physics_params = PhysicsParams::from_array(values);
+
+    let expected_hash = physics_constants_hash(&physics_params);
+    if found_hash != expected_hash {
+        return Err(ReplayFileError::PhysicsMismatch {
+            expected : expected_hash,
+            found : found_hash,
+        });
+    }
+
+    let frame_count = read_u32(reader)? as usize;
+    let point_count = read_u32(reader)? as usize;
+
+    let physic_world = read_physic_world_snapshot(reader)?;
+    let game_logic = read_game_logic_snapshot(reader, rules)?;
+    if
+        physic_world.balls.len() != rules.get_ball_count() ||
+        game_logic.touches_ball_count.len() != rules.get_ball_count()
+    {
+        return Err(ReplayFileError::Corrupted("ball count mismatch"));
+    }
+    let match_logic = read_match_logic_snapshot(reader)?;
+
+    let mut replay = Replay::new(
+        MatchSnapshot {
+            physic_world : physic_world,
+            game_logic : game_logic,
+            match_logic : match_logic,
+        },
+        physics_params
+    );
+    replay.player_kinds = player_kinds;
+    replay.bot_difficulties = bot_difficulties;
+
+    while replay.frames.len() < frame_count || replay.point_boundaries.len() < point_count {
+        let bits = read_u8(reader)?;
+
+        if bits == POINT_BOUNDARY_MARKER {
+            if replay.point_boundaries.len() >= point_count {
+                return Err(ReplayFileError::Corrupted("too many point boundaries"));
+            }
+            replay.point_boundaries.push(replay.frames.len());
+            continue;
+        }
+
+        let inputs = decode_inputs(bits)?;
+        let run_length = read_varint(reader)? as usize;
+
+        if run_length == 0 || replay.frames.len() + run_length > frame_count {
+            return Err(ReplayFileError::Corrupted("invalid input run length"));
+        }
+
+        for _ in 0..run_length {
+            replay.frames.push(inputs);
+        }
+    }
+
+    Ok(replay)
+}
+
+fn encode_input(input : &PlayerInput) -> u8 {
+    (if input.left { INPUT_LEFT_BIT } else { 0 }) |
+    (if input.right { INPUT_RIGHT_BIT } else { 0 }) |
+    (if input.up { INPUT_UP_BIT } else { 0 })
+}
+
+fn decode_input(bits : u8) -> PlayerInput {
+    PlayerInput {
+        left : bits & INPUT_LEFT_BIT != 0,
+        right : bits & INPUT_RIGHT_BIT != 0,
+        up : bits & INPUT_UP_BIT != 0,
+    }
+}
+
+fn encode_inputs(inputs : &[PlayerInput; 2]) -> u8 {
+    encode_input(&inputs[0]) | (encode_input(&inputs[1]) << INPUT_BITS_PER_PLAYER)
+}
+
+fn decode_inputs(bits : u8) -> Result<[PlayerInput; 2], ReplayFileError> {
+    if bits >> (2 * INPUT_BITS_PER_PLAYER) != 0 {
+        return Err(ReplayFileError::Corrupted("invalid input bits"));
+    }
+
+    Ok([decode_input(bits), decode_input(bits >> INPUT_BITS_PER_PLAYER)])
+}
+
+fn write_physic_world_snapshot<W: Write>(
+    writer : &mut W,
+    snapshot : &PhysicWorldSnapshot
+) -> Result<(), ReplayFileError> {
+    for position in snapshot.blob_positions.iter() {
+        write_vector(writer, position)?;
+    }
+    for velocity in snapshot.blob_velocities.iter() {
+        write_vector(writer, velocity)?;
+    }
+    for state in snapshot.blobs_animation_states.iter() {
+        write_f32(writer, *state)?;
+    }
+    for speed in snapshot.blobs_animation_speed.iter() {
+        write_f32(writer, *speed)?;
+    }
+
+    writer.write_all(&[encode_inputs(&snapshot.player_inputs)])?;
+
+    write_bool(writer, snapshot.is_game_running)?;
+    write_f32(writer, snapshot.time_since_ball_out)?;
+
+    write_u32(writer, snapshot.wind.random_state)?;
+    write_f32(writer, snapshot.wind.gust)?;
+    write_f32(writer, snapshot.wind.target_gust)?;
+    write_i32(writer, snapshot.wind.steps_to_next_gust)?;
+
+    write_u32(writer, snapshot.balls.len() as u32)?;
+    for ball in snapshot.balls.iter() {
+        write_vector(writer, &ball.position)?;
+        write_vector(writer, &ball.velocity)?;
+        write_f32(writer, ball.rotation)?;
+        write_f32(writer, ball.angular_velocity)?;
+        write_f32(writer, ball.spin)?;
+        write_bool(writer, ball.is_valid)?;
+        write_f32(writer, ball.last_hit_intensity)?;
+        write_bool(writer, ball.hit_by_blobs[0])?;
+        write_bool(writer, ball.hit_by_blobs[1])?;
+        write_bool(writer, ball.hit_wall)?;
+        write_bool(writer, ball.hit_net_side)?;
+        write_bool(writer, ball.hit_net_sphere)?;
+    }
+
+    Ok(())
+}
+
+fn read_physic_world_snapshot<R: Read>(reader : &mut R) -> Result<PhysicWorldSnapshot, ReplayFileError> {
+    let blob_positions = [read_vector(reader)?, read_vector(reader)?];
+    let blob_velocities = [read_vector(reader)?, read_vector(reader)?];
+    let blobs_animation_states = [read_f32(reader)?, read_f32(reader)?];
+    let blobs_animation_speed = [read_f32(reader)?, read_f32(reader)?];
+    let player_inputs = decode_inputs(read_u8(reader)?)?;
+    let is_game_running = read_bool(reader)?;
+    let time_since_ball_out = read_f32(reader)?;
+    let wind = read_wind_state(reader)?;
+
+    let ball_count = read_u32(reader)?;
+    if ball_count == 0 || ball_count > MAX_BALL_COUNT as u32 {
+        return Err(ReplayFileError::Corrupted("invalid ball count"));
+    }
+
+    let mut balls = vec!();
+    for _ in 0..ball_count {
+        balls.push(Ball {
+            position : read_vector(reader)?,
+            velocity : read_vector(reader)?,
+            rotation : read_f32(reader)?,
+            angular_velocity : read_f32(reader)?,
+            spin : read_f32(reader)?,
+            is_valid : read_bool(reader)?,
+            last_hit_intensity : read_f32(reader)?,
+            hit_by_blobs : [read_bool(reader)?, read_bool(reader)?],
+            hit_wall : read_bool(reader)?,
+            hit_net_side : read_bool(reader)?,
+            hit_net_sphere : read_bool(reader)?,
+        });
+    }
+
+    Ok(PhysicWorldSnapshot {
+        blob_positions : blob_positions,
+        blob_velocities : blob_velocities,
+        blobs_animation_states : blobs_animation_states,
+        blobs_animation_speed : blobs_animation_speed,
+        balls : balls,
+        player_inputs : player_inputs,
+        is_game_running : is_game_running,
+        time_since_ball_out : time_since_ball_out,
+        wind : wind,
+    })
+}
+
+fn read_wind_state<R: Read>(reader : &mut R) -> Result<WindState, ReplayFileError> {
+    let random_state = read_u32(reader)?;
+    if random_state == 0 {
+        return Err(ReplayFileError::Corrupted("invalid wind random state"));
+    }
+
+    Ok(WindState {
+        random_state : random_state,
+        gust : read_f32(reader)?,
+        target_gust : read_f32(reader)?,
+        steps_to_next_gust : read_i32(reader)?,
+    })
+}
+
+fn write_game_logic_snapshot<W: Write>(
+    writer : &mut W,
+    snapshot : &GameLogicSnapshot
+) -> Result<(), ReplayFileError> {
+    for value in snapshot.scores.iter() {
+        write_i32(writer, *value)?;
+    }
+    write_u32(writer, snapshot.touches_ball_count.len() as u32)?;
+    for (touches, squish) in snapshot.touches_ball_count.iter().zip(snapshot.squish.iter()) {
+        for value in touches.iter().chain(squish.iter()) {
+            write_i32(writer, *value)?;
+        }
+    }
+    write_player_side(writer, snapshot.last_error)?;
+    write_player_side(writer, snapshot.serving_player)?;
+    write_player_side(writer, snapshot.winning_player)?;
+    write_i32(writer, snapshot.elapsed_time)?;
+
+    let error_reason = match snapshot.last_error_reason {
+        ErrorReason::BallHitGround => 0u8,
+        ErrorReason::TooManyTouches => 1u8,
+    };
+    writer.write_all(&[error_reason])?;
+    Ok(())
+}
+
+fn read_game_logic_snapshot<R: Read>(
+    reader : &mut R,
+    rules : MatchRules
+) -> Result<GameLogicSnapshot, ReplayFileError> {
+    let scores = [read_i32(reader)?, read_i32(reader)?];
+
+    let ball_count = read_u32(reader)?;
+    if ball_count == 0 || ball_count > MAX_BALL_COUNT as u32 {
+        return Err(ReplayFileError::Corrupted("invalid ball count"));
+    }
+
+    let mut touches_ball_count = vec!();
+    let mut squish = vec!();
+    for _ in 0..ball_count {
+        touches_ball_count.push([read_i32(reader)?, read_i32(reader)?]);
+        squish.push([read_i32(reader)?, read_i32(reader)?]);
+    }
+
+    Ok(GameLogicSnapshot {
+        scores : scores,
+        touches_ball_count : touches_ball_count,
+        squish : squish,
+        last_error : read_player_side(reader)?,
+        serving_player : read_player_side(reader)?,
+        winning_player : read_player_side(reader)?,
+        elapsed_time : read_i32(reader)?,
+        last_error_reason : match read_u8(reader)? {
+            0 => ErrorReason::BallHitGround,
+            1 => ErrorReason::TooManyTouches,
+            _ => return Err(ReplayFileError::Corrupted("invalid error reason")),
+        },
+        rules : rules,
+    })
+}
+
+fn write_rules<W: Write>(writer : &mut W, rules : &MatchRules) -> Result<(), ReplayFileError> {
+    write_i32(writer, rules.score_to_win)?;
+    write_i32(writer, rules.win_margin)?;
+    write_i32(writer, rules.score_cap.unwrap_or(0))?;
+    write_i32(writer, rules.max_ball_touch_count)?;
+    write_i32(writer, rules.squish_tolerance)?;
+    write_player_side(writer, rules.first_serving_player)?;
+
+    let scoring_mode = match rules.scoring_mode {
+        ScoringMode::RallyScoring => 0u8,
+        ScoringMode::SideOutScoring => 1u8,
+    };
+    writer.write_all(&[scoring_mode])?;
+
+    write_i32(writer, rules.set_count)?;
+    write_bool(writer, rules.swap_sides_between_sets)?;
+    write_i32(writer, rules.time_limit.unwrap_or(0))?;
+    write_i32(writer, rules.ball_count)?;
+    write_bool(writer, rules.practice)?;
+    write_bool(writer, rules.infinite_touches)?;
+
+    for handicap in rules.handicaps.iter() {
+        write_i32(writer, handicap.head_start)?;
+        write_i32(writer, handicap.touch_penalty)?;
+        write_i32(writer, handicap.speed_penalty)?;
+    }
+
+    Ok(())
+}
+
+fn read_rules<R: Read>(reader : &mut R) -> Result<MatchRules, ReplayFileError> {
+    Ok(MatchRules {
+        score_to_win : read_i32(reader)?,
+        win_margin : read_i32(reader)?,
+        score_cap : match read_i32(reader)? {
+            0 => None,
+            score_cap => Some(score_cap),
+        },
+        max_ball_touch_count : read_i32(reader)?,
+        squish_tolerance : read_i32(reader)?,
+        first_serving_player : read_player_side(reader)?,
+        scoring_mode : match read_u8(reader)? {
+            0 => ScoringMode::RallyScoring,
+            1 => ScoringMode::SideOutScoring,
+            _ => return Err(ReplayFileError::Corrupted("invalid scoring mode")),
+        },
+        set_count : read_i32(reader)?,
+        swap_sides_between_sets : read_bool(reader)?,
+        time_limit : match read_i32(reader)? {
+            0 => None,
+            time_limit => Some(time_limit),
+        },
+        ball_count : read_i32(reader)?,
+        practice : read_bool(reader)?,
+        infinite_touches : read_bool(reader)?,
+        handicaps : [read_handicap(reader)?, read_handicap(reader)?],
+    })
+}
+
+fn read_handicap<R: Read>(reader : &mut R) -> Result<Handicap, ReplayFileError> {
+    Ok(Handicap {
+        head_start : read_i32(reader)?,
+        touch_penalty : read_i32(reader)?,
+        speed_penalty : read_i32(reader)?,
+    })
+}
+
+fn write_match_logic_snapshot<W: Write>(
+    writer : &mut W,
+    snapshot : &MatchLogicSnapshot
+) -> Result<(), ReplayFileError> {
+    for value in snapshot.set_scores.iter() {
+        write_i32(writer, *value)?;
+    }
+    write_u32(writer, snapshot.finished_sets.len() as u32)?;
+    for scores in snapshot.finished_sets.iter() {
+        write_i32(writer, scores[0])?;
+        write_i32(writer, scores[1])?;
+    }
+    write_bool(writer, snapshot.is_set_finished)?;
+    write_bool(writer, snapshot.sides_swapped)?;
+    write_player_side(writer, snapshot.winning_player)
+}
+
+fn read_match_logic_snapshot<R: Read>(reader : &mut R) -> Result<MatchLogicSnapshot, ReplayFileError> {
+    let set_scores = [read_i32(reader)?, read_i32(reader)?];
+
+    let finished_set_count = read_u32(reader)?;
+    let mut finished_sets = vec!();
+    for _ in 0..finished_set_count {
+        finished_sets.push([read_i32(reader)?, read_i32(reader)?]);
+    }
+
+    Ok(MatchLogicSnapshot {
+        set_scores : set_scores,
+        finished_sets : finished_sets,
+        is_set_finished : read_bool(reader)?,
+        sides_swapped : read_bool(reader)?,
+        winning_player : read_player_side(reader)?,
+    })
+}
+
+fn write_player_kind<W: Write>(writer : &mut W, kind : PlayerKind) -> Result<(), ReplayFileError> {
+    let value = match kind {
+        PlayerKind::Human => 0u8,
+        PlayerKind::Computer => 1u8,
+    };
+    writer.write_all(&[value])?;
+    Ok(())
+}
+
+fn read_player_kind<R: Read>(reader : &mut R) -> Result<PlayerKind, ReplayFileError> {
+    match read_u8(reader)? {
+        0 => Ok(PlayerKind::Human),
+        1 => Ok(PlayerKind::Computer),
+        _ => Err(ReplayFileError::Corrupted("invalid player kind")),
+    }
+}
+
+fn write_player_side<W: Write>(writer : &mut W, side : PlayerSide) -> Result<(), ReplayFileError> {
+    writer.write_all(&[(side as i8) as u8])?;
+    Ok(())
+}
+
+fn read_player_side<R: Read>(reader : &mut R) -> Result<PlayerSide, ReplayFileError> {
+    match read_u8(reader)? as i8 {
+        -1 => Ok(NoPlayer),
+        0 => Ok(LeftPlayer),
+        1 => Ok(RightPlayer),
+        _ => Err(ReplayFileError::Corrupted("invalid player side")),
+    }
+}
+
+fn write_vector<W: Write>(writer : &mut W, vector : &Vector2f) -> Result<(), ReplayFileError> {
+    write_f32(writer, vector.x)?;
+    write_f32(writer, vector.y)
+}
+
+fn read_vector<R: Read>(reader : &mut R) -> Result<Vector2f, ReplayFileError> {
+    let x = read_f32(reader)?;
+    let y = read_f32(reader)?;
+    Ok(Vector2f::new(x, y))
+}
+
+fn write_bool<W: Write>(writer : &mut W, value : bool) -> Result<(), ReplayFileError> {
+    writer.write_all(&[value as u8])?;
+    Ok(())
+}
+
+fn read_bool<R: Read>(reader : &mut R) -> Result<bool, ReplayFileError> {
+    match read_u8(reader)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(ReplayFileError::Corrupted("invalid boolean")),
+    }
+}
+
+fn write_f32<W: Write>(writer : &mut W, value : f32) -> Result<(), ReplayFileError> {
+    write_u32(writer, value.to_bits())
+}
+
+fn read_f32<R: Read>(reader : &mut R) -> Result<f32, ReplayFileError> {
+    Ok(f32::from_bits(read_u32(reader)?))
+}
+
+fn write_i32<W: Write>(writer : &mut W, value : i32) -> Result<(), ReplayFileError> {
+    writer.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+fn read_i32<R: Read>(reader : &mut R) -> Result<i32, ReplayFileError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(i32::from_le_bytes(bytes))
+}
+
+fn write_u32<W: Write>(writer : &mut W, value : u32) -> Result<(), ReplayFileError> {
+    writer.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+fn read_u8<R: Read>(reader : &mut R) -> Result<u8, ReplayFileError> {
+    let mut bytes = [0u8; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_u16<R: Read>(reader : &mut R) -> Result<u16, ReplayFileError> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32<R: Read>(reader : &mut R) -> Result<u32, ReplayFileError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader : &mut R) -> Result<u64, ReplayFileError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn write_varint<W: Write>(writer : &mut W, value : u32) -> Result<(), ReplayFileError> {
+    let mut value = value;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.write_all(&[byte])?;
+            return Ok(());
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(reader : &mut R) -> Result<u32, ReplayFileError> {
+    let mut value : u32 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = read_u8(reader)?;
+        if shift > 28 {
+            return Err(ReplayFileError::Corrupted("varint too long"));
+        }
+        value |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
@@ -2,18 +2,40 @@ extern crate rand;
 
 use player_input::*;
 use physic_world::PhysicWorld;
+use duel_match::DuelMatch;
+use ball::Ball;
+use physics_params::PhysicsParams;
+use environment::WindState;
+use arena::Arena;
+use character::Character;
 use game_constants::*;
 use global::PlayerSide;
 use vector::Vector2f;
 use game_logic::side_to_index;
 use global::PlayerSide::*;
 
+// steps a ball is followed before giving up on its prediction
+const SIMULATION_MAX_STEPS : usize = 75 * 5 * 60;
+
+// shorter horizon to choose the ball to play, every ball being predicted
+// at every step: 3 seconds of play
+const BALL_CHOICE_MAX_STEPS : usize = 3 * STEPS_PER_SECOND as usize;
+
+// height above the ground where the bot plays the ball, y = 200 on the
+// classic field
+const IMPACT_HEIGHT : f32 = 300.0f32;
+
 pub struct CurrentGameState {
     pub blob_positions : [Vector2f; 2],
     pub blob_velocities : [Vector2f; 2],
     pub is_game_running : bool,
     pub is_ball_valid : bool,
+    // spin of the played ball, the bot predicts its curve
+    pub ball_spin : f32,
     pub serving_player : PlayerSide,
+    pub wind : WindState,
+    // bodies of the blobs, by side
+    pub characters : [Character; 2],
 }
 
 impl CurrentGameState {
@@ -23,7 +45,10 @@ impl CurrentGameState {
             blob_velocities : [Vector2f::new(0.0f32, 0.0f32); 2],
             is_game_running : false,
             is_ball_valid : false,
+            ball_spin : 0.0f32,
             serving_player : LeftPlayer,
+            wind : WindState::new(WIND_DEFAULT_SEED),
+            characters : [Character::default(); 2],
         }
     }
 }
@@ -37,11 +62,14 @@ pub enum Axis {
 pub struct SimpleBot {
     side : PlayerSide,
     difficulty : i32,
+    // the field as seen from the left, mirrored for the right bot
+    arena : Arena,
 
     ball_x : f32,
     ball_y : f32,
     ball_velocity_x : f32,
     ball_velocity_y : f32,
+    ball_spin : f32,
 
     error_ball_x : f32,
     error_ball_y : f32,
@@ -61,15 +89,37 @@ pub struct SimpleBot {
 }
 
 impl SimpleBot {
-    pub fn new(side : PlayerSide, difficulty : i32) -> SimpleBot {
+    pub fn new(side : PlayerSide, difficulty : i32, physics_params : PhysicsParams) -> SimpleBot {
+        // the bot sees the field from the left, so the wind of the right one
+        // blows the other way and its field is mirrored
+        let simulated_params =
+            if side == RightPlayer {
+                PhysicsParams {
+                    wind_force : -physics_params.wind_force,
+                    wind_gust_force : -physics_params.wind_gust_force,
+                    arena : physics_params.arena.mirrored(),
+                    characters : [physics_params.characters[1], physics_params.characters[0]],
+                    ..physics_params
+                }
+            }
+            else {
+                physics_params
+            };
+
+        // only used to predict the ball in flight
+        let mut simulated_physic_world = PhysicWorld::new(simulated_params);
+        simulated_physic_world.set_game_running(true);
+
         SimpleBot {
             side : side,
             difficulty : difficulty,
+            arena : simulated_params.arena,
 
             ball_x : 0.0f32,
             ball_y : 0.0f32,
             ball_velocity_x : 0.0f32,
             ball_velocity_y : 0.0f32,
+            ball_spin : 0.0f32,
 
             error_ball_x : 0.0f32,
             error_ball_y : 0.0f32,
@@ -82,7 +132,7 @@ impl SimpleBot {
             want_right : false,
             want_left : false,
 
-            simulated_physic_world : PhysicWorld::new(),
+            simulated_physic_world : simulated_physic_world,
             current_game_state : CurrentGameState::new(),
             bot_impl : SimpleBotImpl::new(),
 
@@ -100,6 +150,15 @@ impl SimpleBot {
         self.want_right = false;
     }
 
+    pub fn get_side(&self) -> PlayerSide {
+        self.side
+    }
+
+    // the bot stays on its side, the players it plays for may change
+    pub fn set_difficulty(&mut self, difficulty : i32) {
+        self.difficulty = difficulty;
+    }
+
     pub fn get_blob_pos(&self, side : PlayerSide) -> Vector2f {
         match side {
             LeftPlayer => self.current_game_state.blob_positions[side_to_index(side)],
@@ -111,12 +170,18 @@ impl SimpleBot {
     pub fn pos_x(&self) -> f32 {
         let blob_pos = self.get_blob_pos(self.side);
         if self.side == RightPlayer {
-            return FIELD_WIDTH - blob_pos.x;
+            return self.mirror_x(blob_pos.x);
         } else {
             return blob_pos.x;
         }
     }
 
+    // x on the other side of the field, from the right bot to the left
+    // one and back
+    fn mirror_x(&self, x : f32) -> f32 {
+        self.arena.left_wall + self.arena.right_wall - x
+    }
+
     pub fn left(&mut self) {
         self.want_left = self.side == LeftPlayer;
         self.want_right = self.side != LeftPlayer;
@@ -133,12 +198,14 @@ impl SimpleBot {
 
     pub fn move_to(&mut self, target : Option<f32>) -> bool {
         let target = target.expect("invalid target for move_to");
+        let character = self.current_game_state.characters[self.side as usize];
+        let blobby_speed = character.get_speed(&self.simulated_physic_world.get_physics_params());
 
-        if self.pos_x() < target - BLOBBY_SPEED / 2.0f32 {
+        if self.pos_x() < target - blobby_speed / 2.0f32 {
             self.right();
             return false;
         }
-        else if self.pos_x() > target + BLOBBY_SPEED / 2.0f32 {
+        else if self.pos_x() > target + blobby_speed / 2.0f32 {
             self.left();
             return false;
         }
@@ -206,30 +273,48 @@ impl SimpleBot {
         axis : Axis,
         coordinate : f32
     ) -> (f32, f32, f32, f32, f32) {
+        // the spin is the one of the ball in play
+        let ball = Ball {
+            position : Vector2f::new(x, VERTICAL_PLANE_LENGTH - y),
+            velocity : Vector2f::new(vx, -vy),
+            spin : self.ball_spin,
+            ..Ball::new()
+        };
 
-        let ival = if axis == Axis::AxisX { x } else { VERTICAL_PLANE_LENGTH - y };
+        let (steps, ball) = self.simulate_ball_until(ball, axis, coordinate, SIMULATION_MAX_STEPS);
 
-        let init = ival < coordinate;
+        (steps, ball.position.x, ball.position.y, ball.velocity.x, ball.velocity.y)
+    }
 
-        self.simulated_physic_world.set_ball_position(Vector2f::new(x, VERTICAL_PLANE_LENGTH - y));
-        self.simulated_physic_world.set_ball_velocity(Vector2f::new(vx, -vy));
+    // Steps the ball, in the coordinates of the physic world, until it
+    // crosses the coordinate. Gives up after max_steps steps, the steps are
+    // then infinite.
+    fn simulate_ball_until(
+        &mut self,
+        mut ball : Ball,
+        axis : Axis,
+        coordinate : f32,
+        max_steps : usize
+    ) -> (f32, Ball) {
 
-        self.simulated_physic_world.set_player_input(LeftPlayer, PlayerInput::new());
-        self.simulated_physic_world.set_player_input(RightPlayer, PlayerInput::new());
+        let ival = if axis == Axis::AxisX { ball.position.x } else { VERTICAL_PLANE_LENGTH - ball.position.y };
+
+        let init = ival < coordinate;
 
-        self.simulated_physic_world.set_ball_validity(false);
-        self.simulated_physic_world.set_game_running(true);
+        self.simulated_physic_world.set_wind_state(self.current_game_state.wind);
 
-        let max_steps : f32 = 75.0f32 * 5.0f32 * 60.0f32;
+        let max_steps = max_steps as f32;
         let mut steps : f32 = 0.0f32;
 
-        while coordinate != ival && steps < max_steps {
-            steps = steps + 1.0f32;
-            self.simulated_physic_world.step();
-            let pos = self.simulated_physic_world.get_ball_position();
-            let v = if axis == Axis::AxisX { pos.x } else { VERTICAL_PLANE_LENGTH - pos.y };
-            if (v < coordinate) != init {
-                break;
+        if coordinate != ival {
+            for predicted_ball in self.simulated_physic_world.predict_ball_steps(ball).take(max_steps as usize) {
+                steps = steps + 1.0f32;
+                ball = predicted_ball;
+                let pos = ball.position;
+                let v = if axis == Axis::AxisX { pos.x } else { VERTICAL_PLANE_LENGTH - pos.y };
+                if (v < coordinate) != init {
+                    break;
+                }
             }
         }
 
@@ -237,10 +322,61 @@ impl SimpleBot {
             steps = std::f32::INFINITY;
         }
 
-        let pos = self.simulated_physic_world.get_ball_position();
-        let vel = self.simulated_physic_world.get_ball_velocity();
+        (steps, ball)
+    }
+
+    // Index of the ball to play with several balls in play: the valid one
+    // that lands the soonest on the side of the bot, or the one that lands
+    // the soonest at all when none is coming. The balls are only followed
+    // for BALL_CHOICE_MAX_STEPS, every ball being predicted at every step.
+    pub fn choose_ball(&mut self, balls : &[Ball]) -> usize {
+        if balls.len() <= 1 {
+            return 0;
+        }
+
+        let mut chosen_ball = 0;
+        let mut chosen_danger = (false, std::f32::INFINITY);
+
+        for (index, ball) in balls.iter().enumerate() {
+            if !ball.is_valid {
+                continue;
+            }
+
+            let mut bot_ball = Ball {
+                position : ball.position,
+                velocity : ball.velocity,
+                spin : ball.spin,
+                ..Ball::new()
+            };
+            if self.side == RightPlayer {
+                bot_ball.position.x = self.mirror_x(bot_ball.position.x);
+                bot_ball.velocity.x = - bot_ball.velocity.x;
+                bot_ball.spin = - bot_ball.spin;
+            }
 
-        (steps, pos.x, pos.y, vel.x, vel.y)
+            let (steps, landing_ball) =
+                self.simulate_ball_until(
+                    bot_ball,
+                    Axis::AxisY,
+                    self.get_impact_y(),
+                    BALL_CHOICE_MAX_STEPS
+                );
+            let landing_x = landing_ball.position.x;
+
+            // a ball still flying at the end of the horizon is no threat yet
+            let is_landing_on_bot_side = steps.is_finite() && landing_x < self.arena.net_position_x;
+            let danger = (is_landing_on_bot_side, steps);
+            let is_more_dangerous =
+                (danger.0 && !chosen_danger.0) ||
+                (danger.0 == chosen_danger.0 && danger.1 < chosen_danger.1);
+
+            if is_more_dangerous {
+                chosen_ball = index;
+                chosen_danger = danger;
+            }
+        }
+
+        chosen_ball
     }
 
     pub fn step(
@@ -256,10 +392,13 @@ impl SimpleBot {
 
         self.ball_velocity_x = ball_velocity.x;
         self.ball_velocity_y = - ball_velocity.y;
+        self.ball_spin = self.current_game_state.ball_spin;
 
         if self.side == RightPlayer {
-            self.ball_x = FIELD_WIDTH - self.ball_x;
+            self.ball_x = self.mirror_x(self.ball_x);
             self.ball_velocity_x = - self.ball_velocity_x;
+            // the mirrored ball turns the other way
+            self.ball_spin = - self.ball_spin;
         }
 
         let original_bvx = self.ball_velocity_x;
@@ -347,6 +486,31 @@ impl SimpleBot {
         }
     }
 
+    // Looks at the match and gives the input of the step to the blob on the
+    // side of the bot
+    pub fn play(&mut self, duel_match : &mut DuelMatch) {
+        let ball_index = self.choose_ball(duel_match.get_balls());
+        let ball = duel_match.get_balls()[ball_index];
+
+        let bot_data = CurrentGameState {
+            blob_positions : duel_match.get_world().get_blob_positions(),
+            blob_velocities : duel_match.get_world().get_blob_velocities(),
+            is_game_running : duel_match.get_world().is_game_running(),
+            is_ball_valid : ball.is_valid,
+            ball_spin : ball.spin,
+            serving_player : duel_match.get_serving_player(),
+            wind : duel_match.get_world().get_wind_state(),
+            characters : duel_match.get_world().get_characters(),
+        };
+
+        self.step(bot_data, ball.position, ball.velocity);
+
+        let side = self.side;
+        duel_match.get_world().set_player_input(side, self.compute_input());
+
+        self.reset_input();
+    }
+
     pub fn on_serve(&mut self, is_ball_ready : bool) {
         if self.bot_impl.serve_random.is_none() {
             self.bot_impl.serve_random = Some(SimpleBot::get_random());
@@ -363,7 +527,9 @@ impl SimpleBot {
     }
 
     pub fn on_opponent_serve(&mut self) {
-        self.move_to(Some(150.0f32));
+        // a bit behind the spawn point
+        let target = self.arena.left_spawn_x - 50.0f32;
+        self.move_to(Some(target));
     }
 
     pub fn estim_impact(&mut self, dest_y : f32) -> bool {
@@ -383,8 +549,9 @@ impl SimpleBot {
     }
 
     pub fn low_play(&mut self) {
-        if self.bot_impl.target.unwrap() > FIELD_MIDDLE {
-            self.move_to(Some(180.0f32));
+        if self.bot_impl.target.unwrap() > self.arena.net_position_x {
+            let target = self.arena.left_spawn_x - 20.0f32;
+            self.move_to(Some(target));
         }
         else {
             let target = self.bot_impl.target;
@@ -393,7 +560,13 @@ impl SimpleBot {
     }
 
     pub fn estim_impact_low(&mut self) -> bool {
-        self.estim_impact(200.0f32)
+        let impact_y = self.get_impact_y();
+        self.estim_impact(impact_y)
+    }
+
+    // y where the bot plays the ball, see IMPACT_HEIGHT
+    fn get_impact_y(&self) -> f32 {
+        self.arena.ground_height - IMPACT_HEIGHT
     }
 }
 
@@ -0,0 +1,45 @@
+use vector::Vector2f;
+
+// FNV-1a hash over the little endian bytes of the values written into it.
+// It is used for the physics constants of the replay files and for the state
+// of a match after each step, to detect as soon as two simulations diverge.
+pub struct StateHasher {
+    hash : u64,
+}
+
+impl StateHasher {
+    pub fn new() -> StateHasher {
+        StateHasher {
+            hash : 0xcbf29ce484222325,
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes : &[u8]) {
+        for byte in bytes.iter() {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    // floats are hashed by their bits, so that -0.0 and 0.0 differ
+    pub fn write_f32(&mut self, value : f32) {
+        self.write_bytes(&value.to_bits().to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value : i32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_bool(&mut self, value : bool) {
+        self.write_bytes(&[value as u8]);
+    }
+
+    pub fn write_vector(&mut self, value : &Vector2f) {
+        self.write_f32(value.x);
+        self.write_f32(value.y);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+}
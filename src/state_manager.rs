@@ -1,3 +1,5 @@
+extern crate rand;
+
 use quicksilver::{
     Result,
     lifecycle::{Asset, State, Window, Event},
@@ -7,13 +9,25 @@ use quicksilver::{
 
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use stdweb::web::Date;
 
 use local_game_state::LocalGameState;
 use home_menu_state::HomeMenuState;
 use win_menu_state::WinMenuState;
 use new_game_menu_state::NewGameMenuState;
+use training_state::TrainingState;
+use juggling_state::JugglingState;
+use juggling_result_state::JugglingResultState;
+use tournament_menu_state::TournamentMenuState;
+use bracket_state::BracketState;
+use tournament::Tournament;
 use new_game_menu_state::GameConfiguration;
-use game_constants::BLOBBY_ANIMATION_FRAMES;
+use game_constants::{BLOBBY_ANIMATION_FRAMES, MAX_STEPS_PER_FRAME, STEPS_PER_SECOND};
+use tick_clock::TickClock;
 
 use global::PlayerSide;
 
@@ -22,6 +36,11 @@ pub enum RustyGameState {
     NewGameMenu,
     LocalGame,
     WinMenu,
+    Training,
+    Juggling,
+    JugglingResult,
+    TournamentMenu,
+    Bracket,
 }
 
 pub struct StateManager {
@@ -29,15 +48,32 @@ pub struct StateManager {
     home_menu_state : Rc<RefCell<HomeMenuState>>,
     win_menu_state : Rc<RefCell<WinMenuState>>,
     new_game_menu_state : Rc<RefCell<NewGameMenuState>>,
+    training_state : Rc<RefCell<TrainingState>>,
+    juggling_state : Rc<RefCell<JugglingState>>,
+    juggling_result_state : Rc<RefCell<JugglingResultState>>,
+    tournament_menu_state : Rc<RefCell<TournamentMenuState>>,
+    bracket_state : Rc<RefCell<BracketState>>,
     game_assets: GamesAssets,
     current_state: RustyGameState,
+    tick_clock: TickClock,
+    last_update: Timestamp,
 }
 
 pub enum StateTransition {
     NoTransition,
     StateLessTransition(RustyGameState), // new state
     StartGameTransition(GameConfiguration), 
+    StartTrainingTransition(GameConfiguration),
+    StartJugglingTransition(GameConfiguration),
+    JugglingOverTransition(i32, i32, Option<String>), // touches, previous best, error saving the best
+    RetryJugglingTransition,
+    StartTournamentMenuTransition(GameConfiguration),
+    StartTournamentTransition(Tournament, GameConfiguration, bool), // simulate the bot matches
+    StartTournamentMatchTransition(GameConfiguration, [String; 2]), // names of the players
+    TournamentMatchOverTransition(PlayerSide), // winningPlayer
     WinStateTransition(PlayerSide), // winningPlayer
+    WatchReplayTransition,
+    SaveReplayTransition,
 }
 
 pub trait RustyVollyState {
@@ -87,8 +123,15 @@ impl StateManager {
             home_menu_state : Rc::new(RefCell::new(HomeMenuState::new())),
             win_menu_state : Rc::new(RefCell::new(WinMenuState::new())),
             new_game_menu_state : Rc::new(RefCell::new(NewGameMenuState::new())),
+            training_state : Rc::new(RefCell::new(TrainingState::new())),
+            juggling_state : Rc::new(RefCell::new(JugglingState::new())),
+            juggling_result_state : Rc::new(RefCell::new(JugglingResultState::new())),
+            tournament_menu_state : Rc::new(RefCell::new(TournamentMenuState::new())),
+            bracket_state : Rc::new(RefCell::new(BracketState::new())),
             game_assets : game_assets,
             current_state : RustyGameState::NewGameMenu,
+            tick_clock : TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME),
+            last_update : current_time(),
         }
     }
 
@@ -98,6 +141,11 @@ impl StateManager {
             RustyGameState::LocalGame => self.local_game_state.clone(),
             RustyGameState::WinMenu => self.win_menu_state.clone(),
             RustyGameState::NewGameMenu => self.new_game_menu_state.clone(),
+            RustyGameState::Training => self.training_state.clone(),
+            RustyGameState::Juggling => self.juggling_state.clone(),
+            RustyGameState::JugglingResult => self.juggling_result_state.clone(),
+            RustyGameState::TournamentMenu => self.tournament_menu_state.clone(),
+            RustyGameState::Bracket => self.bracket_state.clone(),
         }
     }
 
@@ -110,38 +158,139 @@ impl StateManager {
 
             StateTransition::WinStateTransition(player_side) => {
                 self.current_state = RustyGameState::WinMenu;
+                let mut local_game_state_mutable = self.local_game_state.borrow_mut();
+
                 let mut win_menu_state_mutable = self.win_menu_state.borrow_mut();
-                win_menu_state_mutable.set_winner(player_side);
+                win_menu_state_mutable.set_winner(player_side, local_game_state_mutable.get_finished_sets());
 
-                let mut local_game_state_mutable = self.local_game_state.borrow_mut();
                 local_game_state_mutable.reset();
             },
 
+            StateTransition::WatchReplayTransition => {
+                let mut local_game_state_mutable = self.local_game_state.borrow_mut();
+                if local_game_state_mutable.watch_last_replay() {
+                    self.current_state = RustyGameState::LocalGame;
+                }
+            },
+
+            StateTransition::SaveReplayTransition => {
+                let local_game_state = self.local_game_state.borrow();
+                self.win_menu_state.borrow_mut().set_message(local_game_state.save_last_replay());
+            },
+
             StateTransition::StartGameTransition(config) => {
                 let mut local_game_state_mutable = self.local_game_state.borrow_mut();
                 local_game_state_mutable.set_config(config);
+                self.win_menu_state.borrow_mut().set_tournament_players(None);
+                self.current_state = RustyGameState::LocalGame;
+            },
+
+            StateTransition::StartTrainingTransition(config) => {
+                let mut training_state_mutable = self.training_state.borrow_mut();
+                training_state_mutable.set_config(config, rand::random());
+                self.current_state = RustyGameState::Training;
+            },
+
+            StateTransition::StartJugglingTransition(config) => {
+                let mut juggling_state_mutable = self.juggling_state.borrow_mut();
+                juggling_state_mutable.set_config(config);
+                self.current_state = RustyGameState::Juggling;
+            },
+
+            StateTransition::JugglingOverTransition(touch_count, previous_best, save_error) => {
+                let mut juggling_result_state_mutable = self.juggling_result_state.borrow_mut();
+                juggling_result_state_mutable.set_result(touch_count, previous_best, save_error);
+                self.current_state = RustyGameState::JugglingResult;
+            },
+
+            StateTransition::RetryJugglingTransition => {
+                let mut juggling_state_mutable = self.juggling_state.borrow_mut();
+                juggling_state_mutable.restart();
+                self.current_state = RustyGameState::Juggling;
+            },
+
+            StateTransition::StartTournamentMenuTransition(config) => {
+                let mut tournament_menu_state_mutable = self.tournament_menu_state.borrow_mut();
+                tournament_menu_state_mutable.set_config(config);
+                self.current_state = RustyGameState::TournamentMenu;
+            },
+
+            StateTransition::StartTournamentTransition(tournament, config, simulate_bot_matches) => {
+                let mut bracket_state_mutable = self.bracket_state.borrow_mut();
+                bracket_state_mutable.set_tournament(tournament, config, simulate_bot_matches);
+                self.current_state = RustyGameState::Bracket;
+            },
+
+            StateTransition::StartTournamentMatchTransition(config, player_names) => {
+                let mut local_game_state_mutable = self.local_game_state.borrow_mut();
+                local_game_state_mutable.set_config(config);
+                self.win_menu_state.borrow_mut().set_tournament_players(Some(player_names));
                 self.current_state = RustyGameState::LocalGame;
             },
+
+            StateTransition::TournamentMatchOverTransition(player_side) => {
+                let mut bracket_state_mutable = self.bracket_state.borrow_mut();
+                bracket_state_mutable.record_winner(player_side);
+                self.win_menu_state.borrow_mut().set_tournament_players(None);
+                self.current_state = RustyGameState::Bracket;
+            },
         }
     }
 }
 
+// monotonic clock on native, std::time is not available on the web
+#[cfg(not(target_arch = "wasm32"))]
+type Timestamp = Instant;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn current_time() -> Timestamp {
+    Instant::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn elapsed_time(since : Timestamp, now : Timestamp) -> Duration {
+    now.duration_since(since)
+}
+
+// milliseconds since the epoch, the wall clock can go backward
+#[cfg(target_arch = "wasm32")]
+type Timestamp = f64;
+
+#[cfg(target_arch = "wasm32")]
+fn current_time() -> Timestamp {
+    Date::now()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn elapsed_time(since : Timestamp, now : Timestamp) -> Duration {
+    Duration::from_secs_f64((now - since).max(0.0) / 1000.0)
+}
+
 impl State for StateManager {
     fn new() -> Result<StateManager> {
         Ok(StateManager::new())
     }
 
     fn update(&mut self, _window: &mut Window) -> Result<()> {
-        let current_state_ref = self.get_current_state();
-        let transition = {
-            let mut current_state = current_state_ref.borrow_mut();
-            current_state.step(&mut self.game_assets)
-        };
-        self.update_state_if_needed(transition);
+        let now = current_time();
+        let elapsed = elapsed_time(self.last_update, now);
+        let step_count = self.tick_clock.advance(elapsed);
+        self.last_update = now;
+
+        for _ in 0..step_count {
+            let current_state_ref = self.get_current_state();
+            let transition = {
+                let mut current_state = current_state_ref.borrow_mut();
+                current_state.step(&mut self.game_assets)
+            };
+            self.update_state_if_needed(transition);
+        }
         Ok(())
     }
 
     fn draw(&mut self, window: &mut Window) -> Result<()> {
+        self.local_game_state.borrow_mut().set_interpolation(self.tick_clock.get_interpolation());
+
         let current_state_ref = self.get_current_state();
         {
             let mut current_state = current_state_ref.borrow_mut();
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+// Fixed timestep clock: turns the real time elapsed between two frames into
+// a whole number of simulation steps, the remainder being kept for the next
+// frames. The simulation therefore runs at the same speed on every machine,
+// whatever the frame rate.
+pub struct TickClock {
+    // duration of a step, in seconds
+    step_duration : f64,
+    // time not yet simulated, in seconds
+    accumulator : f64,
+    // most steps run for a single frame, the time beyond is dropped so that
+    // a long stall (window dragged, debugger...) does not fast-forward the game
+    max_steps_per_frame : u32,
+}
+
+impl TickClock {
+    pub fn new(steps_per_second : i32, max_steps_per_frame : u32) -> TickClock {
+        TickClock {
+            step_duration : 1.0f64 / steps_per_second as f64,
+            accumulator : 0.0f64,
+            max_steps_per_frame : max_steps_per_frame,
+        }
+    }
+
+    // Adds the elapsed time and returns the number of steps to run now
+    pub fn advance(&mut self, elapsed : Duration) -> u32 {
+        self.accumulator += elapsed.as_secs_f64();
+
+        let mut steps = 0;
+
+        while self.accumulator >= self.step_duration {
+            if steps == self.max_steps_per_frame {
+                self.accumulator = 0.0f64;
+                break;
+            }
+
+            self.accumulator -= self.step_duration;
+            steps += 1;
+        }
+
+        steps
+    }
+
+    // Fraction of a step already elapsed but not simulated, between 0 and 1,
+    // used to interpolate the rendering between the last two steps
+    pub fn get_interpolation(&self) -> f32 {
+        let alpha = (self.accumulator / self.step_duration) as f32;
+        // just below a whole step the f32 rounding gives 1, keep the largest
+        // f32 below 1 instead
+        alpha.min(1.0 - std::f32::EPSILON / 2.0)
+    }
+}
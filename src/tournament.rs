@@ -0,0 +1,452 @@
+use std::error;
+use std::fmt;
+
+use duel_match::{DuelMatch, FrameEvent};
+use game_constants::*;
+use global::PlayerKind;
+use global::PlayerKind::*;
+use global::PlayerSide;
+use global::PlayerSide::*;
+use match_rules::MatchRules;
+use physics_params::PhysicsParams;
+use simple_bot::SimpleBot;
+
+pub const MIN_PARTICIPANT_COUNT : usize = 4;
+pub const MAX_PARTICIPANT_COUNT : usize = 16;
+
+// Steps after which a simulated match is stopped, half an hour of play. The
+// leader wins it, see simulate_match
+pub const MAX_SIMULATED_STEPS : i32 = 30 * 60 * STEPS_PER_SECOND;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Participant {
+    pub kind : PlayerKind,
+    // difficulty of the SimpleBot playing for a computer, 0 for the best
+    pub bot_difficulty : i32,
+}
+
+impl Participant {
+    pub fn human() -> Participant {
+        Participant {
+            kind : Human,
+            bot_difficulty : 0,
+        }
+    }
+
+    pub fn bot(difficulty : i32) -> Participant {
+        Participant {
+            kind : Computer,
+            bot_difficulty : difficulty,
+        }
+    }
+}
+
+impl fmt::Display for Participant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            Human => write!(f, "human"),
+            Computer => write!(f, "bot, difficulty {}", self.bot_difficulty),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EliminationFormat {
+    // out after the first lost match
+    SingleElimination,
+    // out after the second one: the losers of the winners bracket go on in
+    // the losers bracket, whose winner meets the other one in the grand final
+    DoubleElimination,
+}
+
+impl fmt::Display for EliminationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EliminationFormat::SingleElimination => write!(f, "single elimination"),
+            EliminationFormat::DoubleElimination => write!(f, "double elimination"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BracketPart {
+    WinnersBracket,
+    LosersBracket,
+    GrandFinal,
+    // played only when the winner of the losers bracket wins the grand
+    // final, so that the other finalist also has to lose twice
+    GrandFinalReset,
+}
+
+// Where the two participants of a match come from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slot {
+    // a participant of the first round, by index
+    Seed(usize),
+    // nobody: the other participant goes through without playing
+    Bye,
+    WinnerOf(usize),
+    LoserOf(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchResult {
+    // participants by index, None for nobody
+    pub winner : Option<usize>,
+    pub loser : Option<usize>,
+    // false when the match had no opponent or was not needed
+    pub played : bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BracketMatch {
+    pub part : BracketPart,
+    // round within the part, from 1
+    pub round : i32,
+    pub slots : [Slot; 2],
+    pub result : Option<MatchResult>,
+}
+
+#[derive(Debug)]
+pub enum TournamentError {
+    ParticipantCount(usize),
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TournamentError::ParticipantCount(count) =>
+                write!(
+                    f,
+                    "a tournament needs {} to {} participants, not {}",
+                    MIN_PARTICIPANT_COUNT,
+                    MAX_PARTICIPANT_COUNT,
+                    count
+                ),
+        }
+    }
+}
+
+impl error::Error for TournamentError {}
+
+// Participant of a slot once its origin is known
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Entrant {
+    Known(usize),
+    Nobody,
+    Undecided,
+}
+
+// Order of the seeds in the first round, so that the best seeds meet last
+// and the byes go to them
+fn seeding_order(bracket_size : usize) -> Vec<usize> {
+    let mut order = vec!(0);
+
+    while order.len() < bracket_size {
+        let size = order.len() * 2;
+        order = order.iter().flat_map(|&seed| vec!(seed, size - 1 - seed)).collect();
+    }
+
+    order
+}
+
+// A bracket of matches over the participants. The participants are seeded
+// in the order they are given, missing ones up to the next power of two
+// being byes. The matches are listed in an order in which they can be
+// played: each one only waits for earlier ones.
+pub struct Tournament {
+    participants : Vec<Participant>,
+    format : EliminationFormat,
+    matches : Vec<BracketMatch>,
+}
+
+impl Tournament {
+    pub fn new(participants : Vec<Participant>, format : EliminationFormat) -> Result<Tournament, TournamentError> {
+        let participant_count = participants.len();
+        if !(MIN_PARTICIPANT_COUNT..=MAX_PARTICIPANT_COUNT).contains(&participant_count) {
+            return Err(TournamentError::ParticipantCount(participant_count));
+        }
+
+        let mut tournament = Tournament {
+            participants : participants,
+            format : format,
+            matches : vec!(),
+        };
+
+        tournament.create_matches();
+        tournament.resolve_matches();
+        Ok(tournament)
+    }
+
+    fn add_match(&mut self, part : BracketPart, round : i32, slots : [Slot; 2]) -> usize {
+        self.matches.push(BracketMatch {
+            part : part,
+            round : round,
+            slots : slots,
+            result : None,
+        });
+        self.matches.len() - 1
+    }
+
+    fn create_matches(&mut self) {
+        let participant_count = self.participants.len();
+        let bracket_size = participant_count.next_power_of_two();
+        let round_count = bracket_size.trailing_zeros() as i32;
+
+        let slot_of_seed = |seed : usize| if seed < participant_count { Slot::Seed(seed) } else { Slot::Bye };
+
+        // the winners bracket, each round halving the previous one
+        let mut winners_rounds : Vec<Vec<usize>> = vec!();
+
+        let seeds = seeding_order(bracket_size);
+        let first_round =
+            seeds.chunks(2)
+            .map(|pair| self.add_match(
+                BracketPart::WinnersBracket,
+                1,
+                [slot_of_seed(pair[0]), slot_of_seed(pair[1])]
+            ))
+            .collect();
+        winners_rounds.push(first_round);
+
+        for round in 2..=round_count {
+            let previous_round = winners_rounds.last().unwrap().clone();
+            let matches =
+                previous_round.chunks(2)
+                .map(|pair| self.add_match(
+                    BracketPart::WinnersBracket,
+                    round,
+                    [Slot::WinnerOf(pair[0]), Slot::WinnerOf(pair[1])]
+                ))
+                .collect();
+            winners_rounds.push(matches);
+        }
+
+        let winners_final = winners_rounds.last().unwrap()[0];
+
+        if self.format == EliminationFormat::SingleElimination {
+            return;
+        }
+
+        // the losers bracket: the losers of the first round meet, then each
+        // round of the winners bracket drops its losers against the
+        // survivors, who meet among themselves before the next drop
+        let mut losers_round = 1;
+        let mut survivors : Vec<Slot> =
+            winners_rounds[0].chunks(2)
+            .map(|pair| Slot::WinnerOf(self.add_match(
+                BracketPart::LosersBracket,
+                1,
+                [Slot::LoserOf(pair[0]), Slot::LoserOf(pair[1])]
+            )))
+            .collect();
+
+        for (winners_round, round_matches) in winners_rounds.iter().enumerate().skip(1) {
+            let mut dropped : Vec<Slot> =
+                round_matches.iter().map(|&index| Slot::LoserOf(index)).collect();
+
+            // crossed every other round, to delay the rematches
+            if winners_round % 2 == 1 {
+                dropped.reverse();
+            }
+
+            losers_round += 1;
+            survivors =
+                survivors.iter().zip(dropped.iter())
+                .map(|(&survivor, &loser)| Slot::WinnerOf(self.add_match(
+                    BracketPart::LosersBracket,
+                    losers_round,
+                    [loser, survivor]
+                )))
+                .collect();
+
+            if survivors.len() > 1 {
+                losers_round += 1;
+                survivors =
+                    survivors.chunks(2)
+                    .map(|pair| Slot::WinnerOf(self.add_match(
+                        BracketPart::LosersBracket,
+                        losers_round,
+                        [pair[0], pair[1]]
+                    )))
+                    .collect();
+            }
+        }
+
+        let grand_final = self.add_match(BracketPart::GrandFinal, 1, [Slot::WinnerOf(winners_final), survivors[0]]);
+        self.add_match(BracketPart::GrandFinalReset, 1, [Slot::WinnerOf(grand_final), Slot::LoserOf(grand_final)]);
+    }
+
+    fn get_entrant(&self, slot : Slot) -> Entrant {
+        let result_of = |index : usize| self.matches[index].result;
+
+        match slot {
+            Slot::Seed(seed) => Entrant::Known(seed),
+            Slot::Bye => Entrant::Nobody,
+            Slot::WinnerOf(index) => match result_of(index) {
+                Some(MatchResult { winner : Some(winner), .. }) => Entrant::Known(winner),
+                Some(_) => Entrant::Nobody,
+                None => Entrant::Undecided,
+            },
+            Slot::LoserOf(index) => match result_of(index) {
+                Some(MatchResult { loser : Some(loser), .. }) => Entrant::Known(loser),
+                Some(_) => Entrant::Nobody,
+                None => Entrant::Undecided,
+            },
+        }
+    }
+
+    // Decides the matches that need no play: the byes, and the reset of the
+    // grand final when the winner of the winners bracket won it
+    fn resolve_matches(&mut self) {
+        for index in 0..self.matches.len() {
+            if self.matches[index].result.is_some() {
+                continue;
+            }
+
+            let slots = self.matches[index].slots;
+            let entrants = [self.get_entrant(slots[0]), self.get_entrant(slots[1])];
+
+            let result =
+                match (entrants[0], entrants[1]) {
+                    (Entrant::Known(first), Entrant::Known(_)) => {
+                        if
+                            self.matches[index].part == BracketPart::GrandFinalReset &&
+                            self.was_unbeaten(first, index)
+                        {
+                            Some(MatchResult { winner : Some(first), loser : None, played : false })
+                        } else {
+                            None
+                        }
+                    },
+                    (Entrant::Known(participant), Entrant::Nobody) |
+                    (Entrant::Nobody, Entrant::Known(participant)) =>
+                        Some(MatchResult { winner : Some(participant), loser : None, played : false }),
+                    (Entrant::Nobody, Entrant::Nobody) =>
+                        Some(MatchResult { winner : None, loser : None, played : false }),
+                    _ => None,
+                };
+
+            self.matches[index].result = result;
+        }
+    }
+
+    // whether the participant lost no match before the given one
+    fn was_unbeaten(&self, participant : usize, before_match : usize) -> bool {
+        !self.matches[..before_match].iter().any(|bracket_match|
+            bracket_match.result.and_then(|result| result.loser) == Some(participant)
+        )
+    }
+
+    pub fn get_participants(&self) -> &[Participant] {
+        &self.participants
+    }
+
+    pub fn get_format(&self) -> EliminationFormat {
+        self.format
+    }
+
+    pub fn get_matches(&self) -> &[BracketMatch] {
+        &self.matches
+    }
+
+    // participants of the match by slot, None while unknown or for a bye
+    pub fn get_entrants(&self, match_index : usize) -> [Option<usize>; 2] {
+        let slots = self.matches[match_index].slots;
+        let known = |entrant| match entrant {
+            Entrant::Known(participant) => Some(participant),
+            _ => None,
+        };
+
+        [known(self.get_entrant(slots[0])), known(self.get_entrant(slots[1]))]
+    }
+
+    // First match waiting to be played, None once the tournament is over
+    pub fn get_next_match(&self) -> Option<usize> {
+        (0..self.matches.len()).find(|&index| {
+            let entrants = self.get_entrants(index);
+            self.matches[index].result.is_none() && entrants[0].is_some() && entrants[1].is_some()
+        })
+    }
+
+    // The first participant of the match played as player 1 and the second
+    // one as player 2. Matches that are not waiting to be played are left
+    // unchanged.
+    pub fn record_winner(&mut self, match_index : usize, winner : PlayerSide) {
+        let entrants = self.get_entrants(match_index);
+
+        if self.matches[match_index].result.is_some() {
+            return;
+        }
+
+        let (winner, loser) =
+            match (winner, entrants[0], entrants[1]) {
+                (LeftPlayer, Some(first), Some(second)) => (first, second),
+                (RightPlayer, Some(first), Some(second)) => (second, first),
+                _ => return,
+            };
+
+        self.matches[match_index].result =
+            Some(MatchResult { winner : Some(winner), loser : Some(loser), played : true });
+        self.resolve_matches();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.get_champion().is_some()
+    }
+
+    // the winner of the last match, the final of the bracket
+    pub fn get_champion(&self) -> Option<usize> {
+        self.matches.last().and_then(|last_match| last_match.result).and_then(|result| result.winner)
+    }
+}
+
+// Plays a whole match between two bots without drawing it and returns the
+// winning player. A match still running after MAX_SIMULATED_STEPS goes to
+// the player ahead in sets then in points, player 1 on a tie.
+pub fn simulate_match(
+    physics_params : PhysicsParams,
+    rules : MatchRules,
+    bot_difficulties : [i32; 2],
+    seed : u32
+) -> PlayerSide {
+    let mut duel_match = DuelMatch::new(physics_params, rules);
+    duel_match.get_world().seed_wind(seed);
+
+    // the bots are bound to a side, the difficulties to a player
+    let mut bots = [
+        SimpleBot::new(LeftPlayer, bot_difficulties[0], physics_params),
+        SimpleBot::new(RightPlayer, bot_difficulties[1], physics_params),
+    ];
+
+    let mut events = vec!();
+
+    for _ in 0..MAX_SIMULATED_STEPS {
+        for bot in bots.iter_mut() {
+            let player = duel_match.get_player_on_side(bot.get_side());
+            bot.set_difficulty(bot_difficulties[player as usize]);
+            bot.play(&mut duel_match);
+        }
+
+        events.clear();
+        duel_match.step(&mut events);
+
+        for event in events.iter() {
+            if let FrameEvent::EventMatchWon(winner) = *event {
+                return winner;
+            }
+        }
+    }
+
+    let set_scores = duel_match.get_set_scores();
+    let (left_score, right_score) = duel_match.get_scores();
+    let player1_score =
+        if duel_match.get_side_of_player(LeftPlayer) == LeftPlayer { left_score } else { right_score };
+    let player2_score = left_score + right_score - player1_score;
+
+    if (set_scores.1, player2_score) > (set_scores.0, player1_score) {
+        RightPlayer
+    } else {
+        LeftPlayer
+    }
+}
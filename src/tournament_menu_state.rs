@@ -0,0 +1,298 @@
+use quicksilver::{
+    Result,
+    geom::{Shape, Transform, Vector},
+    graphics::{Background::Img, Color, Image},
+    lifecycle::{Window, Event,},
+};
+
+use quicksilver::input::*;
+
+use state_manager::{
+    *,
+    RustyGameState::*,
+    StateTransition::*
+};
+
+use field_drawing::draw_background;
+use game_constants::*;
+
+use global::PlayerKind::*;
+use match_rules::{Handicap, MatchRules};
+use new_game_menu_state::GameConfiguration;
+use tournament::{
+    EliminationFormat,
+    Participant,
+    Tournament,
+    MIN_PARTICIPANT_COUNT,
+    MAX_PARTICIPANT_COUNT
+};
+
+// difficulties offered to the bots, a click on a participant goes from human
+// to each of them in turn
+const BOT_DIFFICULTIES : [i32; 4] = [0, 1, 2, 3];
+
+// The items of the menu, from top to bottom
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TournamentMenuItem {
+    CountItem,
+    FormatItem,
+    BotMatchesItem,
+    // a participant by index, in two columns
+    ParticipantItem(usize),
+    StartItem,
+    BackItem,
+}
+
+use self::TournamentMenuItem::*;
+
+// positions in window coordinates
+const ITEM_CENTER_X : f32 = 1000.0f32;
+const SETTINGS_TOP_Y : f32 = 200.0f32;
+const SETTINGS_SPACING_Y : f32 = 90.0f32;
+const PARTICIPANTS_TOP_Y : f32 = 500.0f32;
+const PARTICIPANTS_SPACING_Y : f32 = 80.0f32;
+const PARTICIPANT_COLUMNS_X : [f32; 2] = [560.0f32, 1360.0f32];
+const PARTICIPANT_ROWS : usize = MAX_PARTICIPANT_COUNT / 2;
+const START_Y : f32 = 1200.0f32;
+const BACK_Y : f32 = 1290.0f32;
+
+// clickable area around each item, in window coordinates
+const ITEM_CLICK_HALF_WIDTH : f32 = 380.0f32;
+const ITEM_CLICK_HALF_HEIGHT : f32 = 34.0f32;
+
+fn item_position(item : TournamentMenuItem) -> (f32, f32) {
+    match item {
+        CountItem => (ITEM_CENTER_X, SETTINGS_TOP_Y),
+        FormatItem => (ITEM_CENTER_X, SETTINGS_TOP_Y + SETTINGS_SPACING_Y),
+        BotMatchesItem => (ITEM_CENTER_X, SETTINGS_TOP_Y + 2.0f32 * SETTINGS_SPACING_Y),
+        ParticipantItem(index) => (
+            PARTICIPANT_COLUMNS_X[index / PARTICIPANT_ROWS],
+            PARTICIPANTS_TOP_Y + PARTICIPANTS_SPACING_Y * (index % PARTICIPANT_ROWS) as f32
+        ),
+        StartItem => (ITEM_CENTER_X, START_Y),
+        BackItem => (ITEM_CENTER_X, BACK_Y),
+    }
+}
+
+// the participants are written smaller to fit in their columns
+fn item_scale(item : TournamentMenuItem) -> f32 {
+    match item {
+        ParticipantItem(_) => DISPLAY_SCALE_FACTOR,
+        _ => DISPLAY_SCALE_FACTOR * 1.6f32,
+    }
+}
+
+// Settings of a tournament: the participants and the bracket. The matches
+// use the rules and physics chosen in the new game menu.
+pub struct TournamentMenuState {
+    line_images : Vec<Image>,
+    line_texts : Vec<String>,
+    participant_count : usize,
+    // settings of every participant, those after the count are kept for
+    // when it grows again
+    participants : [Participant; MAX_PARTICIPANT_COUNT],
+    format : EliminationFormat,
+    simulate_bot_matches : bool,
+    configuration : Option<GameConfiguration>,
+    // why the last start failed, shown on the start item until the next click
+    start_error : Option<String>,
+}
+
+impl TournamentMenuState {
+    pub fn new() -> TournamentMenuState {
+        let mut participants = [Participant::human(); MAX_PARTICIPANT_COUNT];
+        for (index, participant) in participants.iter_mut().enumerate().skip(1) {
+            *participant = Participant::bot(BOT_DIFFICULTIES[index % BOT_DIFFICULTIES.len()]);
+        }
+
+        TournamentMenuState {
+            line_images : vec!(),
+            line_texts : vec!(),
+            participant_count : 8,
+            participants : participants,
+            format : EliminationFormat::SingleElimination,
+            simulate_bot_matches : true,
+            configuration : None,
+            start_error : None,
+        }
+    }
+
+    // Matches of a tournament always have an end and nobody is handicapped
+    pub fn set_config(&mut self, config : GameConfiguration) {
+        self.configuration = Some(GameConfiguration {
+            rules : MatchRules {
+                practice : false,
+                handicaps : [Handicap::default(); 2],
+                ..config.rules
+            },
+            ..config
+        });
+    }
+
+    fn items(&self) -> Vec<TournamentMenuItem> {
+        let mut items = vec!(CountItem, FormatItem, BotMatchesItem);
+        items.extend((0..self.participant_count).map(ParticipantItem));
+        items.push(StartItem);
+        items.push(BackItem);
+        items
+    }
+
+    fn item_text(&self, item : TournamentMenuItem) -> String {
+        match item {
+            CountItem => format!("Participants: {}", self.participant_count),
+            FormatItem => format!("Format: {}", self.format),
+            BotMatchesItem =>
+                if self.simulate_bot_matches {
+                    "Bot matches: simulated".to_string()
+                } else {
+                    "Bot matches: played".to_string()
+                },
+            ParticipantItem(index) => format!("Player {}: {}", index + 1, self.participants[index]),
+            StartItem => match self.start_error {
+                Some(ref error) => format!("Cannot start: {}", error),
+                None => "Start the tournament".to_string(),
+            },
+            BackItem => "Back to the menu".to_string(),
+        }
+    }
+
+    // human, then a bot of each difficulty
+    fn next_participant(participant : Participant) -> Participant {
+        if participant.kind == Human {
+            return Participant::bot(BOT_DIFFICULTIES[0]);
+        }
+
+        match BOT_DIFFICULTIES.iter().position(|&difficulty| difficulty == participant.bot_difficulty) {
+            Some(index) if index + 1 < BOT_DIFFICULTIES.len() => Participant::bot(BOT_DIFFICULTIES[index + 1]),
+            _ => Participant::human(),
+        }
+    }
+
+    fn on_item_clicked(&mut self, item : TournamentMenuItem) -> StateTransition {
+        self.start_error = None;
+
+        match item {
+            CountItem => {
+                self.participant_count =
+                    if self.participant_count >= MAX_PARTICIPANT_COUNT {
+                        MIN_PARTICIPANT_COUNT
+                    } else {
+                        self.participant_count + 1
+                    };
+                NoTransition
+            },
+            FormatItem => {
+                self.format =
+                    match self.format {
+                        EliminationFormat::SingleElimination => EliminationFormat::DoubleElimination,
+                        EliminationFormat::DoubleElimination => EliminationFormat::SingleElimination,
+                    };
+                NoTransition
+            },
+            BotMatchesItem => {
+                self.simulate_bot_matches = !self.simulate_bot_matches;
+                NoTransition
+            },
+            ParticipantItem(index) => {
+                self.participants[index] = TournamentMenuState::next_participant(self.participants[index]);
+                NoTransition
+            },
+            StartItem => {
+                let configuration = match self.configuration {
+                    Some(ref configuration) => configuration.clone(),
+                    None => return NoTransition,
+                };
+
+                match Tournament::new(self.participants[..self.participant_count].to_vec(), self.format) {
+                    Ok(tournament) =>
+                        StateTransition::StartTournamentTransition(tournament, configuration, self.simulate_bot_matches),
+                    Err(error) => {
+                        self.start_error = Some(error.to_string());
+                        NoTransition
+                    },
+                }
+            },
+            BackItem => StateTransition::StateLessTransition(NewGameMenu),
+        }
+    }
+}
+
+impl RustyVollyState for TournamentMenuState {
+    fn step(&mut self, _game_assets: &mut GamesAssets) -> StateTransition {
+        NoTransition
+    }
+
+    fn draw_window_content(&mut self, window: &mut Window, game_assets: &mut GamesAssets) -> Result<()> {
+        window.clear(Color::WHITE)?;
+
+        draw_background(window, game_assets)?;
+
+        // draw text
+        {
+            let items = self.items();
+            let line_texts : Vec<String> = items.iter().map(|item| self.item_text(*item)).collect();
+
+            let should_recreate_texture = line_texts != self.line_texts;
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                if should_recreate_texture {
+                    let mut line_images = vec!();
+
+                    for text in line_texts.iter() {
+                        line_images.push(a_font.render(text, &game_assets.font_style).unwrap());
+                    }
+
+                    self.line_images = line_images;
+                    self.line_texts = line_texts;
+                }
+
+                for (item, image) in items.iter().zip(self.line_images.iter()) {
+                    let (x, y) = item_position(*item);
+                    let scale = item_scale(*item);
+
+                    window.draw_ex(
+                        &image.area().with_center((x * DISPLAY_SCALE_FACTOR, y * DISPLAY_SCALE_FACTOR)),
+                        Img(&image),
+                        Transform::scale(Vector::new(scale, scale)),
+                        4.0f32
+                    );
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event, window: &mut Window) -> StateTransition {
+        match *event {
+            Event::MouseButton(MouseButton::Left, ButtonState::Pressed) => {
+                let mouse_pos = window.mouse().pos();
+
+                let clicked_item =
+                    self.items().into_iter().find(|item| {
+                        let (x, y) = item_position(*item);
+                        let half_width =
+                            match *item {
+                                ParticipantItem(_) => ITEM_CLICK_HALF_WIDTH / 2.0f32,
+                                _ => ITEM_CLICK_HALF_WIDTH,
+                            };
+
+                        (mouse_pos.x - x * DISPLAY_SCALE_FACTOR).abs() <= half_width * DISPLAY_SCALE_FACTOR &&
+                        (mouse_pos.y - y * DISPLAY_SCALE_FACTOR).abs() <= ITEM_CLICK_HALF_HEIGHT * DISPLAY_SCALE_FACTOR
+                    });
+
+                match clicked_item {
+                    Some(item) => self.on_item_clicked(item),
+                    None => NoTransition,
+                }
+            },
+            Event::Key(Key::Escape, ButtonState::Pressed) => StateTransition::StateLessTransition(NewGameMenu),
+            _ => NoTransition,
+        }
+    }
+}
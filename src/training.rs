@@ -0,0 +1,228 @@
+use arena::Arena;
+use global::PlayerSide::*;
+use game_constants::*;
+use physic_world::PhysicWorld;
+use physics_params::PhysicsParams;
+use player_input::PlayerInput;
+use vector::Vector2f;
+
+// Where the launcher serves from, and the range each serve draws its
+// velocity from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaunchPattern {
+    pub position : Vector2f,
+    pub min_velocity : Vector2f,
+    pub max_velocity : Vector2f,
+}
+
+impl LaunchPattern {
+    // velocity between the minimum and the maximum, `mix` going from 0 to 1
+    // on each axis
+    pub fn get_velocity(&self, mix : Vector2f) -> Vector2f {
+        Vector2f::new(
+            self.min_velocity.x + (self.max_velocity.x - self.min_velocity.x) * mix.x,
+            self.min_velocity.y + (self.max_velocity.y - self.min_velocity.y) * mix.y
+        )
+    }
+}
+
+// serves of the launcher, thrown from behind the net into the left half.
+// The velocities are the ones of the classic physics
+pub fn launch_presets(arena : &Arena) -> [(&'static str, LaunchPattern); 3] {
+    let behind_net = Vector2f::new(arena.net_position_x + 100.0f32, arena.ground_height - 400.0f32);
+
+    [
+        ("lob", LaunchPattern {
+            position : behind_net,
+            min_velocity : Vector2f::new(-3.0f32, -10.0f32),
+            max_velocity : Vector2f::new(-2.0f32, -8.0f32),
+        }),
+        ("drive", LaunchPattern {
+            position : behind_net,
+            min_velocity : Vector2f::new(-4.0f32, -5.0f32),
+            max_velocity : Vector2f::new(-3.0f32, -3.0f32),
+        }),
+        ("smash", LaunchPattern {
+            position : Vector2f::new(arena.net_position_x + 60.0f32, arena.ground_height - 460.0f32),
+            min_velocity : Vector2f::new(-4.5f32, 1.0f32),
+            max_velocity : Vector2f::new(-3.5f32, 3.0f32),
+        }),
+    ]
+}
+
+// Part of the opponent court worth points when a return lands in it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TargetZone {
+    pub left : f32,
+    pub right : f32,
+    pub points : i32,
+}
+
+impl TargetZone {
+    pub fn contains(&self, x : f32) -> bool {
+        x >= self.left && x <= self.right
+    }
+}
+
+// a short one behind the net and a deep one at the back of the field, out
+// of reach of the idle blob
+pub fn default_target_zones(arena : &Arena) -> Vec<TargetZone> {
+    let net = arena.net_position_x;
+
+    vec!(
+        TargetZone { left : net + 40.0f32, right : net + 120.0f32, points : 3 },
+        TargetZone { left : arena.right_wall - 120.0f32, right : arena.right_wall, points : 2 },
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrainingEvent {
+    // the launcher served a new ball
+    Launch,
+    // the player hit the ball
+    Touch,
+    // the return landed on the opponent court, in the zone of that index if any
+    Return(Option<usize>),
+    // the ball landed on the side of the player, or untouched on the other one
+    Miss,
+}
+
+// A PhysicWorld without rules where a launcher serves balls to the left
+// player, through the ball setters of the world. The right blob stands idle.
+// Each return landing in a target zone scores its points, then the launcher
+// serves again.
+pub struct TrainingSession {
+    world : PhysicWorld,
+    pattern : LaunchPattern,
+    zones : Vec<TargetZone>,
+    random_state : u32,
+    // steps until the next serve
+    launch_countdown : i32,
+    // the player hit the current ball
+    touched : bool,
+    score : i32,
+    launch_count : i32,
+    return_count : i32,
+}
+
+impl TrainingSession {
+    pub fn new(
+        params : PhysicsParams,
+        pattern : LaunchPattern,
+        zones : Vec<TargetZone>,
+        seed : u32
+    ) -> TrainingSession {
+        let mut world = PhysicWorld::new(params);
+
+        // the first ball waits at the launcher
+        world.set_ball_position(pattern.position);
+        world.set_ball_validity(false);
+
+        TrainingSession {
+            world : world,
+            pattern : pattern,
+            zones : zones,
+            random_state : if seed == 0 { TRAINING_DEFAULT_SEED } else { seed },
+            launch_countdown : TRAINING_LAUNCH_DELAY,
+            touched : false,
+            score : 0,
+            launch_count : 0,
+            return_count : 0,
+        }
+    }
+
+    fn next_random(&mut self) -> u32 {
+        self.random_state ^= self.random_state << 13;
+        self.random_state ^= self.random_state >> 17;
+        self.random_state ^= self.random_state << 5;
+        self.random_state
+    }
+
+    // uniform in [0, 1), see WindState
+    fn next_unit(&mut self) -> f32 {
+        (self.next_random() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn launch(&mut self) {
+        let mix = Vector2f::new(self.next_unit(), self.next_unit());
+
+        self.world.set_ball_position(self.pattern.position);
+        self.world.set_ball_velocity(self.pattern.get_velocity(mix));
+        self.world.set_ball_spin(0.0f32);
+        self.world.set_ball_validity(true);
+        self.world.set_game_running(true);
+
+        self.touched = false;
+        self.launch_count += 1;
+    }
+
+    pub fn step(&mut self, input : PlayerInput, events : &mut Vec<TrainingEvent>) {
+        self.world.set_player_input(LeftPlayer, input);
+        self.world.step();
+
+        if self.world.is_ball_valid() {
+            if self.world.ball_hit_left_player() {
+                self.touched = true;
+                events.push(TrainingEvent::Touch);
+            }
+
+            let ball_left = self.world.ball_hit_left_ground();
+            let ball_right = self.world.ball_hit_right_ground();
+
+            if ball_left || ball_right {
+                if ball_right && self.touched {
+                    let landing_x = self.world.get_ball_position().x;
+                    let zone = self.zones.iter().position(|zone| zone.contains(landing_x));
+
+                    if let Some(index) = zone {
+                        self.score += self.zones[index].points;
+                    }
+                    self.return_count += 1;
+                    events.push(TrainingEvent::Return(zone));
+                } else {
+                    events.push(TrainingEvent::Miss);
+                }
+
+                // the ball bounces out of play until the next serve
+                self.world.set_ball_validity(false);
+                self.launch_countdown = TRAINING_LAUNCH_DELAY;
+            }
+        } else if self.launch_countdown > 0 {
+            self.launch_countdown -= 1;
+
+            if self.launch_countdown == 0 {
+                self.launch();
+                events.push(TrainingEvent::Launch);
+            }
+        }
+    }
+
+    // used from the next serve on
+    pub fn set_pattern(&mut self, pattern : LaunchPattern) {
+        self.pattern = pattern;
+    }
+
+    pub fn get_pattern(&self) -> LaunchPattern {
+        self.pattern
+    }
+
+    pub fn get_zones(&self) -> &[TargetZone] {
+        &self.zones
+    }
+
+    pub fn get_world(&self) -> &PhysicWorld {
+        &self.world
+    }
+
+    pub fn get_score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn get_launch_count(&self) -> i32 {
+        self.launch_count
+    }
+
+    pub fn get_return_count(&self) -> i32 {
+        self.return_count
+    }
+}
@@ -0,0 +1,229 @@
+use arena::Arena;
+use training::{TrainingSession, TrainingEvent, launch_presets, default_target_zones};
+use physics_params::PhysicsParams;
+use player_input::PlayerInput;
+use new_game_menu_state::GameConfiguration;
+use field_drawing::{draw_background, draw_world};
+
+use game_constants::*;
+
+use quicksilver::{
+    Result,
+    geom::{Circle, Rectangle, Shape, Transform, Vector},
+    graphics::{Background::{Col, Img}, Color, Image},
+    input::{*},
+    lifecycle::{Window, Event},
+};
+
+use state_manager::*;
+use state_manager::StateTransition::*;
+
+use self::RustyGameState::*;
+
+// Player 1 alone on the classic field, returning the serves of a launcher.
+// The number keys pick the serve, Escape goes back to the menu.
+pub struct TrainingState {
+    session : TrainingSession,
+    input : PlayerInput,
+    pattern_index : usize,
+    events : Vec<TrainingEvent>,
+    hud_text : String,
+    hud_texture : Option<Image>,
+}
+
+impl TrainingState {
+    pub fn new() -> TrainingState {
+        let arena = PhysicsParams::default().arena;
+
+        TrainingState {
+            session : TrainingSession::new(
+                PhysicsParams::default(),
+                launch_presets(&arena)[0].1,
+                default_target_zones(&arena),
+                0
+            ),
+            input : PlayerInput::new(),
+            pattern_index : 0,
+            events : vec!(),
+            hud_text : String::new(),
+            hud_texture : None,
+        }
+    }
+
+    // the launch presets are made for the classic physics, only the
+    // character of player 1 is kept
+    pub fn set_config(&mut self, config : GameConfiguration, seed : u32) {
+        let params = PhysicsParams {
+            characters : [config.physics_params.characters[0], PhysicsParams::default().characters[1]],
+            ..PhysicsParams::default()
+        };
+
+        self.session =
+            TrainingSession::new(
+                params,
+                launch_presets(&params.arena)[self.pattern_index].1,
+                default_target_zones(&params.arena),
+                seed
+            );
+        self.input = PlayerInput::new();
+    }
+
+    fn get_arena(&self) -> Arena {
+        self.session.get_world().get_physics_params().arena
+    }
+
+    fn select_pattern(&mut self, pattern_index : usize) {
+        self.pattern_index = pattern_index;
+        self.session.set_pattern(launch_presets(&self.get_arena())[pattern_index].1);
+    }
+
+    fn get_hud_text(&self) -> String {
+        format!(
+            "{}: {} points, {}/{} returned",
+            launch_presets(&self.get_arena())[self.pattern_index].0,
+            self.session.get_score(),
+            self.session.get_return_count(),
+            self.session.get_launch_count()
+        )
+    }
+}
+
+impl RustyVollyState for TrainingState {
+    fn step(&mut self, game_assets: &mut GamesAssets) -> StateTransition {
+        self.events.clear();
+        self.session.step(self.input, &mut self.events);
+
+        if self.events.contains(&TrainingEvent::Touch) {
+            let _ = game_assets.sounds[0].execute(|sound| {
+                sound.set_volume(10.0f32);
+                let _ = sound.play()?;
+                Ok(())
+            });
+        }
+
+        if self.events.iter().any(|event|
+            match *event {
+                TrainingEvent::Return(_) | TrainingEvent::Miss => true,
+                _ => false,
+            }
+        ) {
+            let _ = game_assets.sounds[1].execute(|sound| {
+                sound.set_volume(1.0f32);
+                let _ = sound.play()?;
+                Ok(())
+            });
+        }
+
+        NoTransition
+    }
+
+    fn draw_window_content(&mut self, window: &mut Window, game_assets: &mut GamesAssets) -> Result<()> {
+        window.clear(Color::WHITE)?;
+
+        draw_background(window, game_assets)?;
+
+        let scale = DISPLAY_SCALE_FACTOR * 2.4f32;
+        let world = self.session.get_world();
+        let ground_height = world.get_physics_params().arena.ground_height;
+
+        // draw the target zones as strips on the ground, the more points the
+        // more opaque
+        for zone in self.session.get_zones().iter() {
+            let zone_color = Color { r : 1.0f32, g : 0.8f32, b : 0.1f32, a : 0.2f32 + 0.1f32 * zone.points as f32 };
+
+            window.draw_ex(
+                &Rectangle::new(
+                    (zone.left * scale, (ground_height - 10.0f32) * scale),
+                    ((zone.right - zone.left) * scale, 20.0f32 * scale)
+                ),
+                Col(zone_color),
+                Transform::IDENTITY,
+                0.5f32
+            );
+        }
+
+        // draw the launcher
+        {
+            let launcher_position = self.session.get_pattern().position;
+
+            window.draw_ex(
+                &Circle::new((launcher_position.x * scale, launcher_position.y * scale), 8.0f32 * scale),
+                Col(Color { r : 0.3f32, g : 0.3f32, b : 0.35f32, a : 0.6f32 }),
+                Transform::IDENTITY,
+                0.5f32
+            );
+        }
+
+        // draw the player, the idle blob and the ball
+        draw_world(window, game_assets, world)?;
+
+        // draw the serve and the score
+        {
+            let transform =
+                Transform::scale(
+                    Vector::new(
+                        DISPLAY_SCALE_FACTOR * 1.6f32,
+                        DISPLAY_SCALE_FACTOR * 1.6f32
+                    )
+                );
+
+            let hud_text = self.get_hud_text();
+
+            let cloned_font_ref = game_assets.font.clone();
+
+            cloned_font_ref.borrow_mut().execute(|a_font| {
+
+                if self.hud_texture.is_none() || self.hud_text != hud_text {
+                    self.hud_texture = Some(a_font.render(&hud_text, &game_assets.font_style).unwrap());
+                    self.hud_text = hud_text;
+                }
+
+                match self.hud_texture {
+                    None => (),
+                    Some(ref image) => {
+                        window.draw_ex(
+                            &image.area().with_center(
+                                (
+                                    WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                                    SCORE_BASELINE_HEIGHT as f32 * DISPLAY_SCALE_FACTOR
+                                )
+                            ),
+                            Img(&image),
+                            transform,
+                            4.0f32
+                        );
+                    }
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event, _window: &mut Window) -> StateTransition {
+        if let &Event::Key(key, state) = event {
+            let pressed =
+                match state {
+                    ButtonState::Pressed => true,
+                    ButtonState::Released => false,
+                    _ => return NoTransition,
+                };
+
+            // W, A and D like in a match, the arrows work too
+            match key {
+                Key::W | Key::Up => self.input.up = pressed,
+                Key::A | Key::Left => self.input.left = pressed,
+                Key::D | Key::Right => self.input.right = pressed,
+                Key::Key1 if pressed => self.select_pattern(0),
+                Key::Key2 if pressed => self.select_pattern(1),
+                Key::Key3 if pressed => self.select_pattern(2),
+                Key::Escape if pressed => return StateTransition::StateLessTransition(NewGameMenu),
+                _ => (),
+            }
+        }
+
+        NoTransition
+    }
+}
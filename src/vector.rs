@@ -1,6 +1,8 @@
 use std::ops;
 
-#[derive(Debug, Clone, Copy)]
+use float_math;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vector2f {
     pub x : f32,
     pub y : f32
@@ -54,7 +56,7 @@ impl Vector2f {
     }
 
     pub fn length(&self) -> f32 {
-        (self.x * self.x + self.y * self.y).sqrt()
+        float_math::sqrt(self.x * self.x + self.y * self.y)
     }
 
     pub fn normalized(&self) -> Vector2f {
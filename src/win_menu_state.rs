@@ -20,19 +20,53 @@ use global::PlayerSide::*;
 
 pub struct WinMenuState {
     home_menu_text : Option<Image>,
+    sets_text : Option<Image>,
+    message_text : Option<Image>,
     winning_player : PlayerSide,
+    finished_sets : Vec<[i32; 2]>,
+    // names of player 1 and player 2 in a tournament match, whose end
+    // advances the bracket
+    tournament_players : Option<[String; 2]>,
+    // outcome of the last action, e.g. saving the replay
+    message : Option<String>,
 }
 
 impl WinMenuState {
     pub fn new() -> WinMenuState {
         WinMenuState {
             home_menu_text: None,
+            sets_text: None,
+            message_text: None,
             winning_player : NoPlayer,
+            finished_sets : Vec::new(),
+            tournament_players : None,
+            message : None,
         }
     }
 
-    pub fn set_winner(&mut self, winner: PlayerSide) {
+    // the set scores are only shown for matches over several sets
+    pub fn set_winner(&mut self, winner: PlayerSide, finished_sets: Vec<[i32; 2]>) {
         self.winning_player = winner;
+        self.finished_sets = finished_sets;
+    }
+
+    pub fn set_message(&mut self, message : Option<String>) {
+        self.message = message;
+        self.message_text = None;
+    }
+
+    pub fn set_tournament_players(&mut self, tournament_players : Option<[String; 2]>) {
+        self.tournament_players = tournament_players;
+    }
+
+    fn winner_name(&self) -> String {
+        match (self.winning_player, &self.tournament_players) {
+            (LeftPlayer, &Some(ref names)) => names[0].clone(),
+            (RightPlayer, &Some(ref names)) => names[1].clone(),
+            (LeftPlayer, &None) => "Player 1".to_string(),
+            (RightPlayer, &None) => "Player 2".to_string(),
+            _ => "Unknown Player".to_string(),
+        }
     }
 }
 
@@ -88,19 +122,29 @@ impl RustyVollyState for WinMenuState {
 
                 if self.home_menu_text.is_none() {
 
-                    let home_menu_text = match self.winning_player {
-                        LeftPlayer =>
-                            a_font.render(&format!("Player 1 won!"), &game_assets.font_style).unwrap(),
-                        RightPlayer =>
-                            a_font.render(&format!("Player 2 won!"), &game_assets.font_style).unwrap(),
-                        _ =>
-                            a_font.render(&format!("Unknown Player won!"), &game_assets.font_style).unwrap(),
-
-                    };
+                    let home_menu_text =
+                        a_font.render(&format!("{} won!", self.winner_name()), &game_assets.font_style).unwrap();
 
                     self.home_menu_text = Some(home_menu_text);
                 }
 
+                if self.sets_text.is_none() && self.finished_sets.len() > 1 {
+                    let sets_description =
+                        self.finished_sets
+                        .iter()
+                        .map(|set| format!("{}-{}", set[0], set[1]))
+                        .collect::<Vec<String>>()
+                        .join("  ");
+
+                    self.sets_text = Some(a_font.render(&sets_description, &game_assets.font_style).unwrap());
+                }
+
+                if self.message_text.is_none() {
+                    if let Some(ref message) = self.message {
+                        self.message_text = Some(a_font.render(message, &game_assets.font_style).unwrap());
+                    }
+                }
+
                 match self.home_menu_text {
                     None => (),
                     Some(ref image) => {
@@ -118,6 +162,40 @@ impl RustyVollyState for WinMenuState {
                     }
                 }
 
+                match self.sets_text {
+                    None => (),
+                    Some(ref image) => {
+                        window.draw_ex(
+                            &image.area().with_center(
+                                (
+                                    WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                                    WINDOW_HEIGHT as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR + 150.0f32
+                                )
+                            ),
+                            Img(&image),
+                            Transform::IDENTITY,
+                            4.0f32
+                        );
+                    }
+                }
+
+                match self.message_text {
+                    None => (),
+                    Some(ref image) => {
+                        window.draw_ex(
+                            &image.area().with_center(
+                                (
+                                    WINDOW_WIDTH as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR,
+                                    WINDOW_HEIGHT as f32 / 2.0f32 * DISPLAY_SCALE_FACTOR + 250.0f32
+                                )
+                            ),
+                            Img(&image),
+                            Transform::IDENTITY,
+                            4.0f32
+                        );
+                    }
+                }
+
                 Ok(())
             })?;
 
@@ -127,16 +205,30 @@ impl RustyVollyState for WinMenuState {
     }
 
     fn handle_event(&mut self, event: &Event, _window: &mut Window) -> StateTransition {
+        // a tournament goes on with its bracket
+        let next_transition =
+            if self.tournament_players.is_some() {
+                StateTransition::TournamentMatchOverTransition(self.winning_player)
+            } else {
+                StateTransition::StateLessTransition(NewGameMenu)
+            };
+
         let transition = match *event {
-            Event::Key(Key::Space, ButtonState::Pressed) =>  StateTransition::StateLessTransition(NewGameMenu),
-            Event::Key(Key::Return, ButtonState::Pressed) => StateTransition::StateLessTransition(NewGameMenu),
-            Event::MouseButton(MouseButton::Left, ButtonState::Pressed) => StateTransition::StateLessTransition(NewGameMenu),
+            Event::Key(Key::Space, ButtonState::Pressed) => next_transition,
+            Event::Key(Key::Return, ButtonState::Pressed) => next_transition,
+            Event::Key(Key::R, ButtonState::Pressed) => StateTransition::WatchReplayTransition,
+            Event::Key(Key::S, ButtonState::Pressed) => StateTransition::SaveReplayTransition,
+            Event::MouseButton(MouseButton::Left, ButtonState::Pressed) => next_transition,
             _ => NoTransition,
         };
 
         match transition {
             NoTransition => (),
-            _ => self.home_menu_text = None,
+            _ => {
+                self.home_menu_text = None;
+                self.sets_text = None;
+                self.set_message(None);
+            }
         };
 
         transition
@@ -0,0 +1,172 @@
+// Arenas change the geometry of the field: the default one is the classic
+// field, the others come from arena files
+
+extern crate rustyvolley;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use rustyvolley::arena::{Arena, ArenaFileError, load_arena, parse_arena};
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::replay_file::physics_constants_hash;
+use rustyvolley::vector::Vector2f;
+
+use common::{flying_world, replayed_state_hashes, throw_ball, through_file};
+
+fn arena_params(arena : Arena) -> PhysicsParams {
+    PhysicsParams {
+        arena,
+        ..PhysicsParams::default()
+    }
+}
+
+#[test]
+fn default_arena_is_the_classic_field() {
+    let world = PhysicWorld::new(PhysicsParams::default());
+
+    assert_eq!(world.get_blob(LeftPlayer), Vector2f::new(LEFT_SPAWN_POS_X as f32, GROUND_PLANE_HEIGHT));
+    assert_eq!(world.get_blob(RightPlayer), Vector2f::new(RIGHT_SPAWN_POS_X as f32, GROUND_PLANE_HEIGHT));
+    assert_eq!(Arena::default().get_blob_ground_height(), GROUND_PLANE_HEIGHT);
+    assert_eq!(parse_arena("# nothing changed\n").unwrap(), Arena::default());
+}
+
+#[test]
+fn shipped_arena_files_load() {
+    let folder = Path::new(env!("CARGO_MANIFEST_DIR")).join("arenas");
+    let mut count = 0;
+
+    for entry in fs::read_dir(folder).unwrap() {
+        let arena = load_arena(&entry.unwrap().path()).unwrap();
+        assert_ne!(arena, Arena::default());
+        count += 1;
+    }
+
+    assert!(count > 0);
+}
+
+#[test]
+fn bad_arena_files_are_rejected() {
+    match parse_arena("net_radius 7") {
+        Err(ArenaFileError::Syntax { line : 1 }) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    match parse_arena("\nnet_colour = 3") {
+        Err(ArenaFileError::UnknownField { line : 2, .. }) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    // the net beyond the right spawn point
+    match parse_arena("net_position_x = 700") {
+        Err(ArenaFileError::Invalid(_)) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn tall_net_stops_a_ball_the_classic_net_lets_through() {
+    let tall_net = Arena {
+        net_sphere_position : 200.0f32,
+        ..Arena::default()
+    };
+
+    let crosses = |arena : Arena| {
+        let mut world =
+            flying_world(arena_params(arena), Vector2f::new(300.0f32, 240.0f32), Vector2f::new(10.0f32, 0.0f32));
+
+        for _ in 0..60 {
+            world.step();
+        }
+
+        world.get_ball_position().x > NET_POSITION_X
+    };
+
+    assert!(crosses(Arena::default()));
+    assert!(!crosses(tall_net));
+}
+
+#[test]
+fn ceiling_sends_the_ball_back_down() {
+    let cave = Arena {
+        ceiling : Some(100.0f32),
+        ..Arena::default()
+    };
+
+    let mut world =
+        flying_world(arena_params(cave), Vector2f::new(200.0f32, 150.0f32), Vector2f::new(0.0f32, -20.0f32));
+    let mut hit_ceiling = false;
+
+    for _ in 0..20 {
+        world.step();
+        hit_ceiling |= world.ball_hit_wall();
+        assert!(world.get_ball_position().y >= 100.0f32 + BALL_RADIUS);
+    }
+
+    assert!(hit_ceiling);
+    assert!(world.get_ball_velocity().y > 0.0f32);
+}
+
+#[test]
+fn wide_court_scores_on_its_own_net_side() {
+    let wide_court = parse_arena(
+        "right_wall = 1000\nnet_position_x = 500\nleft_spawn_x = 250\nright_spawn_x = 750"
+    ).unwrap();
+
+    let mut duel_match = DuelMatch::new(arena_params(wide_court), MatchRules::default());
+
+    assert_eq!(duel_match.get_blob_position(RightPlayer).x, 750.0f32);
+    assert_eq!(duel_match.get_ball_position().x, 250.0f32);
+
+    // a ball landing at x = 450 is on the left side of this court
+    throw_ball(&mut duel_match, Vector2f::new(450.0f32, GROUND_PLANE_HEIGHT - 1.0f32), Vector2f::new(0.0f32, 10.0f32));
+    duel_match.step(&mut vec!());
+
+    assert_eq!(duel_match.get_scores(), (0, 1));
+}
+
+#[test]
+fn mirrored_arena_swaps_the_sides() {
+    let arena = Arena {
+        net_position_x : 380.0f32,
+        left_spawn_x : 150.0f32,
+        ..Arena::default()
+    };
+    let mirrored = arena.mirrored();
+
+    assert_eq!(mirrored.net_position_x, 420.0f32);
+    assert_eq!(mirrored.left_spawn_x, 800.0f32 - arena.right_spawn_x);
+    assert_eq!(mirrored.right_spawn_x, 650.0f32);
+    assert_eq!(mirrored.mirrored(), arena);
+}
+
+#[test]
+fn replay_keeps_the_arena() {
+    let cave = Arena {
+        ceiling : Some(100.0f32),
+        net_sphere_position : 250.0f32,
+        ..Arena::default()
+    };
+    let params = arena_params(cave);
+
+    assert_ne!(physics_constants_hash(&params), physics_constants_hash(&PhysicsParams::default()));
+
+    let mut duel_match = DuelMatch::new(params, MatchRules::default());
+    duel_match.start_recording();
+
+    let mut expected_hashes = vec!();
+    for _ in 0..2000 {
+        duel_match.step(&mut vec!());
+        expected_hashes.push(duel_match.get_state_hash());
+    }
+
+    let replay = through_file(&duel_match.stop_recording().unwrap());
+    assert_eq!(replay.physics_params.arena, cave);
+    assert_eq!(replayed_state_hashes(replay), expected_hashes);
+}
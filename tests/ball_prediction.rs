@@ -0,0 +1,104 @@
+// PhysicWorld::predict_ball follows a ball as if nobody touched it, without
+// changing the world, and tells where it first meets the field
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::vector::Vector2f;
+
+use common::flying_world;
+
+#[test]
+fn prediction_follows_the_simulation() {
+    // high above the blobs, nothing but gravity on the way
+    let mut world = flying_world(PhysicsParams::default(), Vector2f::new(100.0f32, 150.0f32), Vector2f::new(5.0f32, -8.0f32));
+    let prediction = world.predict_ball(0, 60);
+
+    assert_eq!(prediction.trajectory.len(), 60);
+
+    for position in prediction.trajectory.iter() {
+        world.step();
+        assert_eq!(world.get_ball_position(), *position);
+    }
+}
+
+#[test]
+fn prediction_leaves_the_world_untouched() {
+    let world = flying_world(PhysicsParams::default(), Vector2f::new(300.0f32, 200.0f32), Vector2f::new(-4.0f32, 2.0f32));
+    let snapshot = world.snapshot();
+
+    let prediction = world.predict_ball(0, 2000);
+
+    assert!(prediction.ground_contact.is_some());
+    assert_eq!(world.snapshot(), snapshot);
+}
+
+#[test]
+fn first_contacts_are_reported() {
+    // dropped on the left side
+    let prediction =
+        flying_world(PhysicsParams::default(), Vector2f::new(200.0f32, 100.0f32), Vector2f::new(0.0f32, 0.0f32)).predict_ball(0, 1000);
+    let (step, side) = prediction.ground_contact.unwrap();
+    assert_eq!(side, LeftPlayer);
+    assert!((prediction.get_landing_position().unwrap().x - 200.0f32).abs() < 0.01f32);
+    assert!(prediction.trajectory[step].y > GROUND_PLANE_HEIGHT);
+    assert_eq!(prediction.wall_contact, None);
+    assert_eq!(prediction.net_contact, None);
+
+    // lobbed over the net
+    let prediction =
+        flying_world(PhysicsParams::default(), Vector2f::new(200.0f32, 100.0f32), Vector2f::new(6.0f32, -5.0f32)).predict_ball(0, 1000);
+    assert_eq!(prediction.ground_contact.unwrap().1, RightPlayer);
+    assert_eq!(prediction.net_contact, None);
+
+    // thrown against the left wall, it bounces back before landing
+    let prediction =
+        flying_world(PhysicsParams::default(), Vector2f::new(100.0f32, 100.0f32), Vector2f::new(-10.0f32, 0.0f32)).predict_ball(0, 1000);
+    let wall_step = prediction.wall_contact.unwrap();
+    assert!(wall_step < prediction.ground_contact.unwrap().0);
+
+    // thrown low into the net
+    let prediction =
+        flying_world(PhysicsParams::default(), Vector2f::new(300.0f32, 350.0f32), Vector2f::new(10.0f32, 0.0f32)).predict_ball(0, 1000);
+    let net_step = prediction.net_contact.unwrap();
+    assert!(net_step < prediction.ground_contact.unwrap().0);
+    assert_eq!(prediction.ground_contact.unwrap().1, LeftPlayer);
+}
+
+#[test]
+fn waiting_ball_stays_put() {
+    let world = PhysicWorld::new(PhysicsParams::default());
+    let prediction = world.predict_ball(0, 100);
+
+    assert!(prediction.trajectory.iter().all(|position| *position == world.get_ball_position()));
+    assert_eq!(prediction.ground_contact, None);
+}
+
+#[test]
+fn every_ball_is_predicted() {
+    let mut world = PhysicWorld::new(PhysicsParams::default());
+    world.set_ball_count(2);
+    world.set_game_running(true);
+
+    // one ball dropped on each side
+    world.set_ball_position(Vector2f::new(200.0f32, 100.0f32));
+    world.set_ball_velocity(Vector2f::new(0.0f32, 0.0f32));
+    {
+        let ball = world.get_ball_mut(1);
+        ball.position = Vector2f::new(600.0f32, 150.0f32);
+        ball.velocity = Vector2f::new(0.0f32, 0.0f32);
+    }
+
+    let predictions = world.predict_balls(1000);
+
+    assert_eq!(predictions.len(), 2);
+    assert_eq!(predictions[0].ground_contact.unwrap().1, LeftPlayer);
+    assert_eq!(predictions[1].ground_contact.unwrap().1, RightPlayer);
+    assert_eq!(world.predict_ball(1, 1000), predictions[1]);
+    assert!((predictions[1].get_landing_position().unwrap().x - 600.0f32).abs() < 0.01f32);
+}
@@ -0,0 +1,127 @@
+// Characters give each player a blob of their own: walking speed, jump
+// strength and body size, as factors of the classic blob
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::character::Character;
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+use rustyvolley::vector::Vector2f;
+
+use common::replay_through_file;
+
+fn left_character_params(character : Character) -> PhysicsParams {
+    PhysicsParams {
+        characters : [character, Character::default()],
+        ..PhysicsParams::default()
+    }
+}
+
+const QUICK : Character = Character { speed : 1.25f32, jump : 1.0f32, size : 1.0f32 };
+const JUMPER : Character = Character { speed : 1.0f32, jump : 1.1f32, size : 1.0f32 };
+const GIANT : Character = Character { speed : 1.0f32, jump : 1.0f32, size : 1.25f32 };
+
+#[test]
+fn default_characters_are_classic_blobs() {
+    let world = PhysicWorld::new(PhysicsParams::default());
+
+    assert_eq!(world.get_characters(), [Character::default(); 2]);
+    assert_eq!(world.get_blob(LeftPlayer).y, GROUND_PLANE_HEIGHT);
+    assert_eq!(Character::default().get_speed(&PhysicsParams::default()), BLOBBY_SPEED);
+}
+
+#[test]
+fn quick_blob_walks_further() {
+    let walked = |character : Character| {
+        let mut world = PhysicWorld::new(left_character_params(character));
+        let start = world.get_blob(LeftPlayer).x;
+
+        world.set_player_input(LeftPlayer, PlayerInput { left : true, right : false, up : false });
+        for _ in 0..10 {
+            world.step();
+        }
+
+        start - world.get_blob(LeftPlayer).x
+    };
+
+    assert!((walked(QUICK) / walked(Character::default()) - 1.25f32).abs() < 0.001f32);
+}
+
+#[test]
+fn jumper_jumps_higher() {
+    let jump_height = |character : Character| {
+        let mut world = PhysicWorld::new(left_character_params(character));
+        let ground = world.get_blob(LeftPlayer).y;
+        let mut highest = ground;
+
+        world.set_player_input(LeftPlayer, PlayerInput { left : false, right : false, up : true });
+        for _ in 0..300 {
+            world.step();
+            highest = highest.min(world.get_blob(LeftPlayer).y);
+        }
+
+        ground - highest
+    };
+
+    assert!(jump_height(JUMPER) > 1.1f32 * jump_height(Character::default()));
+}
+
+#[test]
+fn giant_reaches_a_ball_the_classic_blob_misses() {
+    let hits = |character : Character| {
+        let mut world = PhysicWorld::new(left_character_params(character));
+        let blob = world.get_blob(LeftPlayer);
+
+        // a bigger blob stands higher, its feet still on the ground
+        assert!(blob.y <= GROUND_PLANE_HEIGHT);
+
+        world.set_ball_position(Vector2f::new(blob.x, 370.0f32));
+        world.set_ball_velocity(Vector2f::new(0.0f32, 0.0f32));
+        world.step();
+        world.ball_hit_left_player()
+    };
+
+    assert!(!hits(Character::default()));
+    assert!(hits(GIANT));
+}
+
+#[test]
+fn characters_follow_the_players_to_their_new_sides() {
+    let params = PhysicsParams {
+        characters : [QUICK, GIANT],
+        ..PhysicsParams::default()
+    };
+    let rules = MatchRules {
+        set_count : 3,
+        swap_sides_between_sets : true,
+        ..MatchRules::default()
+    };
+    let mut duel_match = DuelMatch::new(params, rules);
+
+    assert_eq!(duel_match.get_world().get_character(LeftPlayer), QUICK);
+
+    let mut snapshot = duel_match.snapshot();
+    snapshot.match_logic.sides_swapped = true;
+    duel_match.restore(&snapshot);
+
+    assert_eq!(duel_match.get_world().get_character(LeftPlayer), GIANT);
+    assert_eq!(duel_match.get_world().get_character(RightPlayer), QUICK);
+}
+
+#[test]
+fn replay_keeps_the_characters() {
+    let params = PhysicsParams {
+        characters : [JUMPER, GIANT],
+        ..PhysicsParams::default()
+    };
+
+    let replay = replay_through_file(params, MatchRules::default(), 100);
+    assert_eq!(replay.physics_params.characters, [JUMPER, GIANT]);
+}
@@ -0,0 +1,102 @@
+// Fixtures shared by the integration tests, every test file does not use
+// all of them
+#![allow(dead_code)]
+
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::game_constants::*;
+use rustyvolley::game_logic::GameLogic;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+use rustyvolley::replay::{Replay, ReplayPlayer};
+use rustyvolley::replay_file::{read_replay, write_replay};
+use rustyvolley::vector::Vector2f;
+
+// pseudo random inputs, changed every 20 steps
+pub fn scripted_inputs(step_count : usize) -> Vec<[PlayerInput; 2]> {
+    let mut seed : u32 = 2463534242;
+    let mut inputs = vec!();
+    let mut current = [PlayerInput::new(); 2];
+
+    for step in 0..step_count {
+        if step % 20 == 0 {
+            for input in current.iter_mut() {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+
+                input.left = seed & 1 != 0;
+                input.right = seed & 2 != 0;
+                input.up = seed & 4 != 0;
+            }
+        }
+        inputs.push(current);
+    }
+
+    inputs
+}
+
+pub fn step_with_inputs(duel_match : &mut DuelMatch, inputs : &[PlayerInput; 2]) {
+    duel_match.get_world().set_player_input(LeftPlayer, inputs[0]);
+    duel_match.get_world().set_player_input(RightPlayer, inputs[1]);
+    duel_match.step(&mut vec!());
+}
+
+// puts the ball of the match in play at the position with the velocity
+pub fn throw_ball(duel_match : &mut DuelMatch, position : Vector2f, velocity : Vector2f) {
+    duel_match.get_world().set_game_running(true);
+    duel_match.get_world().set_ball_position(position);
+    duel_match.get_world().set_ball_velocity(velocity);
+}
+
+// touches of the left side, far enough apart to all count
+pub fn touch_left(game_logic : &mut GameLogic, touch_count : i32) {
+    for _ in 0..touch_count {
+        for _ in 0..SQUISH_TOLERANCE + 1 {
+            game_logic.step();
+        }
+        game_logic.on_ball_hits_player(LeftPlayer, 0);
+    }
+}
+
+// a world with the ball already in play at the position
+pub fn flying_world(params : PhysicsParams, position : Vector2f, velocity : Vector2f) -> PhysicWorld {
+    let mut world = PhysicWorld::new(params);
+
+    world.set_game_running(true);
+    world.set_ball_position(position);
+    world.set_ball_velocity(velocity);
+    world
+}
+
+// the replay written to a file and read back
+pub fn through_file(replay : &Replay) -> Replay {
+    let mut file = vec!();
+    write_replay(replay, &mut file).unwrap();
+    read_replay(&mut &file[..]).unwrap()
+}
+
+// records step_count steps of a match without input, through a file
+pub fn replay_through_file(params : PhysicsParams, rules : MatchRules, step_count : usize) -> Replay {
+    let mut duel_match = DuelMatch::new(params, rules);
+    duel_match.start_recording();
+    for _ in 0..step_count {
+        duel_match.step(&mut vec!());
+    }
+
+    through_file(&duel_match.stop_recording().unwrap())
+}
+
+// state hash after every step of the replay played back
+pub fn replayed_state_hashes(replay : Replay) -> Vec<u64> {
+    let mut replay_player = ReplayPlayer::new(replay);
+    let mut replayed_match = replay_player.create_match();
+    let mut hashes = vec!();
+    while replay_player.step(&mut replayed_match, &mut vec!()) {
+        hashes.push(replayed_match.get_state_hash());
+    }
+
+    hashes
+}
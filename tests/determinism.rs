@@ -0,0 +1,107 @@
+// The simulation must give the same state, bit for bit, whenever it is fed
+// with the same inputs: replays, restored snapshots and the software square
+// root of the `deterministic` feature all rely on it
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::float_math::soft_sqrt;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+
+use common::{replayed_state_hashes, scripted_inputs, step_with_inputs, through_file};
+
+const STEP_COUNT : usize = 20000;
+
+fn state_hashes(duel_match : &mut DuelMatch, inputs : &[[PlayerInput; 2]]) -> Vec<u64> {
+    inputs.iter().map(|frame_inputs| {
+        step_with_inputs(duel_match, frame_inputs);
+        duel_match.get_state_hash()
+    }).collect()
+}
+
+#[test]
+fn same_inputs_give_the_same_state_hashes() {
+    let inputs = scripted_inputs(STEP_COUNT);
+
+    let mut first_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    let mut second_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+
+    assert_eq!(first_match.get_state_hash(), second_match.get_state_hash());
+    assert_eq!(
+        state_hashes(&mut first_match, &inputs),
+        state_hashes(&mut second_match, &inputs)
+    );
+}
+
+#[test]
+fn state_hash_changes_with_the_state() {
+    let inputs = scripted_inputs(STEP_COUNT);
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+
+    let hashes = state_hashes(&mut duel_match, &inputs[..100]);
+
+    assert!(hashes.windows(2).any(|pair| pair[0] != pair[1]));
+}
+
+#[test]
+fn restored_snapshot_continues_with_the_same_hashes() {
+    let inputs = scripted_inputs(STEP_COUNT);
+    let (first_inputs, last_inputs) = inputs.split_at(STEP_COUNT / 2);
+
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    for frame_inputs in first_inputs.iter() {
+        step_with_inputs(&mut duel_match, frame_inputs);
+    }
+
+    let snapshot = duel_match.snapshot();
+    let expected_hashes = state_hashes(&mut duel_match, last_inputs);
+
+    let mut restored_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    restored_match.restore(&snapshot);
+
+    assert_eq!(state_hashes(&mut restored_match, last_inputs), expected_hashes);
+}
+
+#[test]
+fn replay_file_reproduces_every_state_hash() {
+    let inputs = scripted_inputs(STEP_COUNT);
+
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    duel_match.start_recording();
+    let expected_hashes = state_hashes(&mut duel_match, &inputs);
+    let replay = duel_match.stop_recording().unwrap();
+
+    assert_eq!(replayed_state_hashes(through_file(&replay)), expected_hashes);
+}
+
+#[test]
+fn soft_sqrt_matches_the_correctly_rounded_sqrt() {
+    let special_values = [
+        0.0f32,
+        -0.0f32,
+        1.0f32,
+        2.0f32,
+        f32::from_bits(1),
+        f32::from_bits(0x007f_ffff),
+        f32::from_bits(0x7f7f_ffff),
+        1.0f32 / 0.0f32,
+    ];
+
+    for value in special_values.iter() {
+        assert_eq!(soft_sqrt(*value).to_bits(), value.sqrt().to_bits(), "sqrt({:e})", value);
+    }
+
+    assert!(soft_sqrt(-1.0f32).is_nan());
+    assert!(soft_sqrt(-1.0f32 / 0.0f32).is_nan());
+    assert!(soft_sqrt(f32::from_bits(0x7fc0_0000)).is_nan());
+
+    // every positive exponent, with a spread of mantissas
+    for bits in (0..0x7f80_0000u32).step_by(997) {
+        let value = f32::from_bits(bits);
+        assert_eq!(soft_sqrt(value).to_bits(), value.sqrt().to_bits(), "sqrt({:e})", value);
+    }
+}
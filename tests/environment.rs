@@ -0,0 +1,138 @@
+// Wind, air drag and gravity presets of the party variants. The classic game
+// has none of them, and the gusts must blow the same in a replay.
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::environment::GravityPreset::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::vector::Vector2f;
+
+use common::{flying_world, replayed_state_hashes, through_file};
+
+// the ball thrown up from the left side
+fn thrown_ball_world(params : PhysicsParams) -> PhysicWorld {
+    flying_world(params, Vector2f::new(200.0f32, 100.0f32), Vector2f::new(0.0f32, -5.0f32))
+}
+
+fn ball_after_steps(params : PhysicsParams, step_count : usize) -> (Vector2f, Vector2f) {
+    let mut world = thrown_ball_world(params);
+
+    for _ in 0..step_count {
+        world.step();
+    }
+
+    (world.get_ball_position(), world.get_ball_velocity())
+}
+
+#[test]
+fn classic_game_has_no_wind() {
+    let mut world = thrown_ball_world(PhysicsParams::default());
+    let wind = world.get_wind_state();
+
+    for _ in 0..1000 {
+        world.step();
+    }
+
+    assert_eq!(world.get_wind_force(), 0.0f32);
+    assert_eq!(world.get_wind_state(), wind);
+    assert_eq!(world.get_ball_velocity().x, 0.0f32);
+}
+
+#[test]
+fn wind_pushes_the_ball() {
+    let params = PhysicsParams {
+        wind_force : -0.02f32,
+        ..PhysicsParams::default()
+    };
+
+    let (position, velocity) = ball_after_steps(params, 20);
+
+    assert!(position.x < 200.0f32);
+    assert!(velocity.x < 0.0f32);
+}
+
+#[test]
+fn air_drag_slows_the_ball_down() {
+    let params = PhysicsParams {
+        air_drag : 0.01f32,
+        ..PhysicsParams::default()
+    };
+
+    let (dragged_position, _) = ball_after_steps(params, 20);
+    let (free_position, _) = ball_after_steps(PhysicsParams::default(), 20);
+
+    // the ball rises less high
+    assert!(dragged_position.y > free_position.y);
+}
+
+#[test]
+fn gusts_change_over_time_and_follow_the_seed() {
+    let params = PhysicsParams {
+        wind_gust_force : 0.05f32,
+        ..PhysicsParams::default()
+    };
+
+    let gust_forces = |seed : u32| {
+        let mut world = PhysicWorld::new(params);
+        world.seed_wind(seed);
+
+        (0..2000).map(|_| {
+            world.step();
+            world.get_wind_force()
+        }).collect::<Vec<f32>>()
+    };
+
+    let forces = gust_forces(1);
+
+    assert!(forces.iter().any(|force| *force != 0.0f32));
+    assert!(forces.iter().all(|force| force.abs() <= 0.05f32));
+    assert!(forces.windows(2).any(|pair| pair[0] != pair[1]));
+    assert_eq!(gust_forces(1), forces);
+    assert_ne!(gust_forces(2), forces);
+}
+
+#[test]
+fn replay_blows_the_same_gusts() {
+    let params = PhysicsParams {
+        wind_gust_force : 0.05f32,
+        air_drag : 0.001f32,
+        ..PhysicsParams::default()
+    };
+
+    let mut duel_match = DuelMatch::new(params, MatchRules::default());
+    duel_match.get_world().seed_wind(42);
+    duel_match.start_recording();
+
+    let mut expected_hashes = vec!();
+    for _ in 0..3000 {
+        duel_match.step(&mut vec!());
+        expected_hashes.push(duel_match.get_state_hash());
+    }
+
+    let replay = through_file(&duel_match.stop_recording().unwrap());
+    assert_eq!(replayed_state_hashes(replay), expected_hashes);
+}
+
+#[test]
+fn gravity_presets_scale_the_gravity_not_the_jump_height() {
+    let classic = PhysicsParams::default();
+    let moon = MoonGravity.apply(classic);
+    let heavy = HeavyGravity.apply(classic);
+
+    assert_eq!(NormalGravity.apply(classic), classic);
+    assert!(moon.ball_gravitation < classic.ball_gravitation);
+    assert!(heavy.ball_gravitation > classic.ball_gravitation);
+
+    let jump_height = |params : PhysicsParams| {
+        params.blobby_jump_acceleration * params.blobby_jump_acceleration /
+            (params.blobby_gravitation - params.blobby_jump_buffer)
+    };
+
+    assert!((jump_height(moon) / jump_height(classic) - 1.0f32).abs() < 1e-4f32);
+    assert!((jump_height(heavy) / jump_height(classic) - 1.0f32).abs() < 1e-4f32);
+}
@@ -0,0 +1,179 @@
+// The frame events carry the side of the net they happened on and the ball
+// right after the collision, the sounds and effects of the frontend rely on
+// them
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::game_constants::*;
+use rustyvolley::game_logic::ErrorReason;
+use rustyvolley::global::PlayerSide;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+use rustyvolley::vector::Vector2f;
+
+use common::throw_ball;
+
+const JUMP : PlayerInput = PlayerInput { left : false, right : false, up : true };
+
+fn new_match() -> DuelMatch {
+    DuelMatch::new(PhysicsParams::default(), MatchRules::default())
+}
+
+// name of the kind of event, to wait for it
+fn kind_of(event : &FrameEvent) -> &'static str {
+    match *event {
+        FrameEvent::EventBlobbyHit { .. } => "hit",
+        FrameEvent::EventBallHitGround { .. } => "ground",
+        FrameEvent::EventBallHitNetSphere { .. } | FrameEvent::EventBallHitNetSide { .. } => "net",
+        FrameEvent::EventBallHitWall { .. } => "wall",
+        _ => "other",
+    }
+}
+
+// events of the first step with an event of the kind
+fn step_until(duel_match : &mut DuelMatch, kind : &str) -> Vec<FrameEvent> {
+    for _ in 0..1000 {
+        let mut events = vec!();
+        duel_match.step(&mut events);
+        if events.iter().any(|event| kind_of(event) == kind) {
+            return events;
+        }
+    }
+
+    panic!("no {} event", kind);
+}
+
+#[test]
+fn serve_is_the_first_hit_of_the_server() {
+    let mut duel_match = new_match();
+    duel_match.get_world().set_player_input(LeftPlayer, JUMP);
+
+    let events = step_until(&mut duel_match, "hit");
+    let ball = duel_match.get_balls()[0];
+
+    assert_eq!(events[0], FrameEvent::EventServe(LeftPlayer));
+    match events[1] {
+        FrameEvent::EventBlobbyHit { side, ball_position, ball_velocity, intensity, touch_count } => {
+            assert_eq!(side, LeftPlayer);
+            assert_eq!(ball_position, ball.position);
+            assert_eq!(ball_velocity, ball.velocity);
+            assert_eq!(intensity, ball.last_hit_intensity);
+            assert!(intensity > 0.0);
+            assert!(ball_velocity.y < 0.0);
+            assert_eq!(touch_count, 1);
+        },
+        ref event => panic!("expected a hit, got {:?}", event),
+    }
+}
+
+#[test]
+fn touch_count_goes_up_to_the_error() {
+    let mut duel_match = new_match();
+
+    // the left side has already touched the ball three times
+    let mut snapshot = duel_match.snapshot();
+    snapshot.game_logic.touches_ball_count[0] = [MAX_BALL_TOUCH_COUNT, 0];
+    duel_match.restore(&snapshot);
+
+    let blob = duel_match.get_blob_position(LeftPlayer);
+    throw_ball(&mut duel_match, Vector2f::new(blob.x, blob.y - 100.0), Vector2f::new(0.0, 5.0));
+
+    let events = step_until(&mut duel_match, "hit");
+
+    let touch_counts : Vec<i32> = events.iter().filter_map(|event| match *event {
+        FrameEvent::EventBlobbyHit { side : LeftPlayer, touch_count, .. } => Some(touch_count),
+        _ => None,
+    }).collect();
+    assert_eq!(touch_counts, vec!(MAX_BALL_TOUCH_COUNT + 1));
+    assert!(events.contains(&FrameEvent::EventError { side : LeftPlayer, reason : ErrorReason::TooManyTouches }));
+    assert_eq!(duel_match.get_scores(), (0, 1));
+}
+
+#[test]
+fn ground_event_gives_the_side_and_the_error() {
+    let mut duel_match = new_match();
+    throw_ball(&mut duel_match, Vector2f::new(700.0, GROUND_PLANE_HEIGHT - 1.0), Vector2f::new(0.0, 10.0));
+
+    let events = step_until(&mut duel_match, "ground");
+    let ball = duel_match.get_balls()[0];
+
+    assert!(events.contains(&FrameEvent::EventBallHitGround {
+        side : RightPlayer,
+        ball_position : ball.position,
+        ball_velocity : ball.velocity,
+    }));
+    assert!(events.contains(&FrameEvent::EventError { side : RightPlayer, reason : ErrorReason::BallHitGround }));
+    assert!(!ball.is_valid);
+}
+
+#[test]
+fn wall_event_gives_the_side_of_the_wall() {
+    let walls : [(PlayerSide, f32, f32); 2] = [
+        (LeftPlayer, LEFT_PLANE + 60.0, -8.0),
+        (RightPlayer, RIGHT_PLANE - 60.0, 8.0),
+    ];
+
+    for &(side, x, velocity_x) in walls.iter() {
+        let mut duel_match = new_match();
+        throw_ball(&mut duel_match, Vector2f::new(x, 150.0), Vector2f::new(velocity_x, 0.0));
+
+        let events = step_until(&mut duel_match, "wall");
+        let ball = duel_match.get_balls()[0];
+
+        assert!(events.contains(&FrameEvent::EventBallHitWall {
+            side,
+            ball_position : ball.position,
+            ball_velocity : ball.velocity,
+        }));
+        // bounced back into the field
+        assert!(ball.velocity.x * velocity_x < 0.0);
+    }
+}
+
+#[test]
+fn ball_on_top_of_the_net_hits_the_net_sphere() {
+    let mut duel_match = new_match();
+    throw_ball(
+        &mut duel_match,
+        Vector2f::new(NET_POSITION_X + 5.0, NET_SPHERE_POSITION - 80.0),
+        Vector2f::new(0.0, 2.0)
+    );
+
+    let events = step_until(&mut duel_match, "net");
+    let ball = duel_match.get_balls()[0];
+
+    assert_eq!(events, vec!(FrameEvent::EventBallHitNetSphere {
+        ball_position : ball.position,
+        ball_velocity : ball.velocity,
+    }));
+    assert!(ball.velocity.y < 0.0);
+}
+
+#[test]
+fn ball_below_the_net_top_hits_the_net_side() {
+    let sides : [(PlayerSide, f32); 2] = [(LeftPlayer, -1.0), (RightPlayer, 1.0)];
+
+    for &(side, direction) in sides.iter() {
+        let mut duel_match = new_match();
+        throw_ball(
+            &mut duel_match,
+            Vector2f::new(NET_POSITION_X + direction * 80.0, NET_SPHERE_POSITION + 60.0),
+            Vector2f::new(-direction * 8.0, 0.0)
+        );
+
+        let events = step_until(&mut duel_match, "net");
+        let ball = duel_match.get_balls()[0];
+
+        assert_eq!(events, vec!(FrameEvent::EventBallHitNetSide {
+            side,
+            ball_position : ball.position,
+            ball_velocity : ball.velocity,
+        }));
+        assert!(ball.velocity.x * direction > 0.0);
+    }
+}
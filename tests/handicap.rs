@@ -0,0 +1,109 @@
+// Handicaps: a head start in every set, fewer touches and a slower blob,
+// following the players when they swap sides
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::game_constants::*;
+use rustyvolley::game_logic::{ErrorReason, GameLogic};
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::{Handicap, MatchRules};
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::vector::Vector2f;
+
+use common::{replay_through_file, throw_ball, touch_left};
+
+const HEAD_START : Handicap = Handicap { head_start : 3, touch_penalty : 0, speed_penalty : 0 };
+const PENALTIES : Handicap = Handicap { head_start : 0, touch_penalty : 1, speed_penalty : 50 };
+
+fn handicap_rules(handicaps : [Handicap; 2]) -> MatchRules {
+    MatchRules {
+        handicaps,
+        ..MatchRules::default()
+    }
+}
+
+#[test]
+fn head_start_is_given_in_every_set() {
+    let mut game_logic = GameLogic::new(handicap_rules([Handicap::default(), HEAD_START]));
+    assert_eq!(game_logic.get_scores(), (0, 3));
+
+    game_logic.on_ball_hits_ground(RightPlayer, 0);
+    assert_eq!(game_logic.get_scores(), (1, 3));
+
+    game_logic.start_new_game(LeftPlayer);
+    assert_eq!(game_logic.get_scores(), (0, 3));
+}
+
+#[test]
+fn touch_penalty_lowers_the_touch_limit() {
+    let mut game_logic = GameLogic::new(handicap_rules([PENALTIES, Handicap::default()]));
+    assert_eq!(game_logic.get_max_touch_count(LeftPlayer), MAX_BALL_TOUCH_COUNT - 1);
+    assert_eq!(game_logic.get_max_touch_count(RightPlayer), MAX_BALL_TOUCH_COUNT);
+
+    touch_left(&mut game_logic, MAX_BALL_TOUCH_COUNT - 1);
+    assert_eq!(game_logic.get_last_error_side(), NoPlayer);
+
+    touch_left(&mut game_logic, 1);
+    assert_eq!(game_logic.get_last_error_reason(), ErrorReason::TooManyTouches);
+    assert_eq!(game_logic.get_last_error_side(), LeftPlayer);
+
+    // a single touch is always left
+    let game_logic =
+        GameLogic::new(handicap_rules([
+            Handicap { touch_penalty : 10, ..Handicap::default() },
+            Handicap::default()
+        ]));
+    assert_eq!(game_logic.get_max_touch_count(LeftPlayer), 1);
+}
+
+#[test]
+fn speed_penalty_slows_the_blob_down() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), handicap_rules([PENALTIES, Handicap::default()]));
+
+    assert_eq!(duel_match.get_world().get_character(LeftPlayer).speed, 0.5f32);
+    assert_eq!(duel_match.get_world().get_character(RightPlayer).speed, 1.0f32);
+    assert_eq!(duel_match.get_handicap(LeftPlayer), PENALTIES);
+}
+
+#[test]
+fn handicaps_follow_the_players_to_their_new_sides() {
+    let rules = MatchRules {
+        score_to_win : 5,
+        set_count : 3,
+        swap_sides_between_sets : true,
+        handicaps : [PENALTIES, HEAD_START],
+        ..MatchRules::default()
+    };
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), rules);
+
+    // player 1 is a point away from the set, the ball falls on the right side
+    let mut snapshot = duel_match.snapshot();
+    snapshot.game_logic.scores = [4, 3];
+    duel_match.restore(&snapshot);
+    throw_ball(&mut duel_match, Vector2f::new(700.0f32, GROUND_PLANE_HEIGHT - 1.0f32), Vector2f::new(0.0f32, 10.0f32));
+
+    let mut events = vec!();
+    for _ in 0..2000 {
+        duel_match.step(&mut events);
+    }
+
+    assert!(events.contains(&FrameEvent::EventSetWon(LeftPlayer)));
+    assert_eq!(duel_match.get_side_of_player(RightPlayer), LeftPlayer);
+
+    // player 2 starts the new set on the left with its head start
+    assert_eq!(duel_match.get_scores(), (3, 0));
+    assert_eq!(duel_match.get_handicap(LeftPlayer), HEAD_START);
+    assert_eq!(duel_match.get_handicap(RightPlayer), PENALTIES);
+    assert_eq!(duel_match.get_world().get_character(RightPlayer).speed, 0.5f32);
+}
+
+#[test]
+fn replay_keeps_the_handicaps() {
+    let rules = handicap_rules([HEAD_START, PENALTIES]);
+    let replay = replay_through_file(PhysicsParams::default(), rules, 50);
+    assert_eq!(replay.initial_state.game_logic.rules, rules);
+    assert_eq!(replay.initial_state.game_logic.scores, [3, 0]);
+}
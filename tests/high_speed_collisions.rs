@@ -0,0 +1,109 @@
+// Regression suite for balls fast enough to cross the net or a blob within a
+// single step, which PhysicWorld must still collide with
+
+// std::f32::NAN, f32::NAN is too recent for the toolchain of the game
+#![allow(clippy::legacy_numeric_constants)]
+
+extern crate rustyvolley;
+
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::vector::Vector2f;
+
+// world with the blobs at their spawn positions and a free flying ball,
+// without gravity as long as nobody touched it
+fn world_with_ball(position : Vector2f, distance_per_step : Vector2f) -> PhysicWorld {
+    let params = PhysicsParams::default();
+    let mut world = PhysicWorld::new(params);
+
+    world.set_ball_position(position);
+    world.set_ball_velocity(distance_per_step * (1.0f32 / params.time_scaling));
+    world
+}
+
+#[test]
+fn normal_speed_moves_the_ball_in_a_single_step() {
+    let position = Vector2f::new(300.0f32, 150.0f32);
+    let mut world = world_with_ball(position, Vector2f::new(0.0f32, 0.0f32));
+    let velocity = Vector2f::new(12.0f32, -9.0f32);
+    world.set_ball_velocity(velocity);
+
+    world.step();
+
+    let time_scaling = PhysicsParams::default().time_scaling;
+    assert_eq!(world.get_ball_position(), position + velocity * time_scaling);
+    assert_eq!(world.get_ball_velocity(), velocity);
+}
+
+#[test]
+fn fast_ball_does_not_cross_the_net_from_the_left() {
+    let mut world =
+        world_with_ball(Vector2f::new(330.0f32, 350.0f32), Vector2f::new(300.0f32, 0.0f32));
+
+    world.step();
+
+    assert!(world.ball_hit_net_side());
+    assert!(world.get_ball_position().x < NET_POSITION_X);
+    assert!(world.get_ball_velocity().x < 0.0f32);
+}
+
+#[test]
+fn fast_ball_does_not_cross_the_net_from_the_right() {
+    let mut world =
+        world_with_ball(Vector2f::new(470.0f32, 350.0f32), Vector2f::new(-300.0f32, 0.0f32));
+
+    world.step();
+
+    assert!(world.ball_hit_net_side());
+    assert!(world.get_ball_position().x > NET_POSITION_X);
+    assert!(world.get_ball_velocity().x > 0.0f32);
+}
+
+#[test]
+fn fast_ball_bounces_on_the_net_sphere() {
+    let mut world =
+        world_with_ball(Vector2f::new(NET_POSITION_X, 50.0f32), Vector2f::new(0.0f32, 400.0f32));
+
+    world.step();
+
+    assert!(world.ball_hit_net_sphere());
+    assert!(world.get_ball_position().y < NET_SPHERE_POSITION);
+    assert!(world.get_ball_velocity().y < 0.0f32);
+}
+
+#[test]
+fn fast_ball_hits_the_blob_in_its_way() {
+    let blob_position = PhysicWorld::new(PhysicsParams::default()).get_blob(LeftPlayer);
+    let ball_height = blob_position.y - BLOBBY_UPPER_SPHERE;
+
+    let mut world =
+        world_with_ball(Vector2f::new(50.0f32, ball_height), Vector2f::new(300.0f32, 0.0f32));
+
+    world.step();
+
+    assert!(world.ball_hit_left_player());
+    assert!(world.get_ball_position().x < blob_position.x);
+    assert!(world.get_ball_velocity().x < 0.0f32);
+}
+
+#[test]
+fn very_fast_ball_still_stops_at_the_net() {
+    let mut world =
+        world_with_ball(Vector2f::new(330.0f32, 350.0f32), Vector2f::new(900.0f32, 0.0f32));
+
+    world.step();
+
+    assert!(world.ball_hit_net_side());
+    assert!(world.get_ball_position().x < NET_POSITION_X);
+}
+
+#[test]
+fn invalid_speed_does_not_hang_the_step() {
+    let mut world =
+        world_with_ball(Vector2f::new(330.0f32, 350.0f32), Vector2f::new(0.0f32, 0.0f32));
+    world.set_ball_velocity(Vector2f::new(std::f32::NAN, 0.0f32));
+
+    world.step();
+}
@@ -0,0 +1,115 @@
+// The juggling challenge counts the touches of the left blob until the ball
+// touches the ground or crosses the net, and keeps the best count in a file
+
+extern crate rustyvolley;
+
+use std::env;
+use std::fs;
+
+use rustyvolley::duel_match::FrameEvent;
+use rustyvolley::game_constants::*;
+use rustyvolley::juggling::*;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+
+// jumping under the ball keeps bouncing it straight up
+const JUMP : PlayerInput = PlayerInput { left : false, right : false, up : true };
+const AWAY : PlayerInput = PlayerInput { left : true, right : false, up : false };
+// jumping while running to the net sends the ball over it
+const TOWARD_NET : PlayerInput = PlayerInput { left : false, right : true, up : true };
+
+#[test]
+fn ball_waits_for_the_first_touch() {
+    let mut challenge = JugglingChallenge::new(PhysicsParams::default());
+    let serve_position = challenge.get_world().get_ball_position();
+
+    for _ in 0..500 {
+        challenge.step(PlayerInput::new(), &mut vec!());
+    }
+
+    assert_eq!(challenge.get_world().get_ball_position(), serve_position);
+    assert_eq!(challenge.get_touch_count(), 0);
+    assert!(!challenge.is_over());
+}
+
+#[test]
+fn touches_count_beyond_the_touch_limit() {
+    let mut challenge = JugglingChallenge::new(PhysicsParams::default());
+    let mut events = vec!();
+
+    for _ in 0..1500 {
+        challenge.step(JUMP, &mut events);
+    }
+
+    let mut hit_count = 0;
+    let mut error_count = 0;
+    for event in events.iter() {
+        match *event {
+            FrameEvent::EventBlobbyHit { .. } => hit_count += 1,
+            FrameEvent::EventError { .. } => error_count += 1,
+            _ => (),
+        }
+    }
+
+    assert!(challenge.get_touch_count() > MAX_BALL_TOUCH_COUNT);
+    assert_eq!(challenge.get_touch_count(), hit_count);
+    assert_eq!(error_count, 0);
+    assert!(!challenge.is_over());
+}
+
+#[test]
+fn challenge_ends_on_the_ground() {
+    let mut challenge = JugglingChallenge::new(PhysicsParams::default());
+
+    for _ in 0..1500 {
+        challenge.step(JUMP, &mut vec!());
+    }
+    let touch_count = challenge.get_touch_count();
+
+    // the blob walks away from the ball
+    for _ in 0..1000 {
+        challenge.step(AWAY, &mut vec!());
+    }
+    assert!(challenge.is_over());
+    assert_eq!(challenge.get_touch_count(), touch_count);
+
+    // nothing counts anymore
+    for _ in 0..1500 {
+        challenge.step(JUMP, &mut vec!());
+    }
+    assert_eq!(challenge.get_touch_count(), touch_count);
+}
+
+#[test]
+fn challenge_ends_when_the_ball_crosses_the_net() {
+    let mut challenge = JugglingChallenge::new(PhysicsParams::default());
+
+    for _ in 0..1500 {
+        challenge.step(TOWARD_NET, &mut vec!());
+        if challenge.is_over() {
+            break;
+        }
+    }
+
+    // over in the air, before the ball lands on the other side
+    assert!(challenge.is_over());
+    assert!(challenge.get_touch_count() > 0);
+    assert!(challenge.get_world().get_ball_position().x >= NET_POSITION_X);
+    assert!(challenge.get_world().is_ball_valid());
+}
+
+#[test]
+fn best_touches_are_kept_in_a_file() {
+    let path = env::temp_dir().join(format!("rustyvolley_juggling_best_{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(load_best_touches(&path), 0);
+
+    save_best_touches(&path, 42).unwrap();
+    assert_eq!(load_best_touches(&path), 42);
+
+    fs::write(&path, "not a number").unwrap();
+    assert_eq!(load_best_touches(&path), 0);
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,161 @@
+// MatchRules decide when a game is won. The default rules must play exactly
+// like the fixed rules of the classic game: the first to 15 points wins.
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::game_constants::*;
+use rustyvolley::game_logic::{ErrorReason, GameLogic};
+use rustyvolley::global::PlayerSide;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+
+use common::touch_left;
+
+fn rules_with_margin(win_margin : i32, score_cap : Option<i32>) -> MatchRules {
+    MatchRules {
+        win_margin,
+        score_cap,
+        ..MatchRules::default()
+    }
+}
+
+fn score_point(game_logic : &mut GameLogic, side : PlayerSide) {
+    let other_side = if side == LeftPlayer { RightPlayer } else { LeftPlayer };
+    game_logic.on_error(other_side, ErrorReason::BallHitGround, 0);
+}
+
+// both sides score in turn up to score each
+fn play_even_to(game_logic : &mut GameLogic, score : i32) {
+    while game_logic.get_scores().1 < score {
+        score_point(game_logic, LeftPlayer);
+        score_point(game_logic, RightPlayer);
+    }
+    assert_eq!(game_logic.get_scores(), (score, score));
+}
+
+#[test]
+fn default_rules_are_the_classic_ones() {
+    let rules = MatchRules::default();
+
+    assert_eq!(rules.score_to_win, SCORE_TO_WIN);
+    assert_eq!(rules.win_margin, 1);
+    assert_eq!(rules.score_cap, None);
+    assert_eq!(rules.max_ball_touch_count, MAX_BALL_TOUCH_COUNT);
+    assert_eq!(rules.squish_tolerance, SQUISH_TOLERANCE);
+    assert_eq!(rules.first_serving_player, LeftPlayer);
+}
+
+#[test]
+fn default_rules_win_like_the_classic_game() {
+    let rules = MatchRules::default();
+
+    // every score a classic game can reach: the opponent has not won yet
+    for score in 0..=SCORE_TO_WIN {
+        for opponent_score in 0..SCORE_TO_WIN {
+            assert_eq!(
+                rules.is_winning_score(score, opponent_score),
+                score >= SCORE_TO_WIN,
+                "{} to {}",
+                score,
+                opponent_score
+            );
+        }
+    }
+}
+
+#[test]
+fn default_game_follows_the_classic_game_rally_by_rally() {
+    let mut seed : u32 = 2463534242;
+    let mut game_logic = GameLogic::new(MatchRules::default());
+
+    // the classic game logic: the winner of a rally scores and serves, the
+    // first to SCORE_TO_WIN wins
+    let mut scores = [0, 0];
+    let mut winning_player = NoPlayer;
+    assert_eq!(game_logic.get_serving_player(), LeftPlayer);
+
+    while winning_player == NoPlayer {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        let rally_winner = if seed & 1 == 0 { LeftPlayer } else { RightPlayer };
+
+        score_point(&mut game_logic, rally_winner);
+
+        scores[rally_winner as usize] += 1;
+        if scores[rally_winner as usize] >= SCORE_TO_WIN {
+            winning_player = rally_winner;
+        }
+
+        assert_eq!(game_logic.get_scores(), (scores[0], scores[1]));
+        assert_eq!(game_logic.get_serving_player(), rally_winner);
+        assert_eq!(game_logic.get_winning_player(), winning_player);
+    }
+}
+
+#[test]
+fn fourth_touch_is_an_error_by_default() {
+    let mut game_logic = GameLogic::new(MatchRules::default());
+
+    touch_left(&mut game_logic, MAX_BALL_TOUCH_COUNT);
+    assert_eq!(game_logic.get_scores(), (0, 0));
+
+    touch_left(&mut game_logic, 1);
+    assert_eq!(game_logic.get_scores(), (0, 1));
+
+    assert_eq!(game_logic.get_last_error_side(), LeftPlayer);
+    assert_eq!(game_logic.get_last_error_reason(), ErrorReason::TooManyTouches);
+}
+
+#[test]
+fn win_by_two_goes_on_past_the_score_to_win() {
+    let mut game_logic = GameLogic::new(rules_with_margin(2, None));
+
+    play_even_to(&mut game_logic, SCORE_TO_WIN - 1);
+    score_point(&mut game_logic, LeftPlayer);
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+
+    score_point(&mut game_logic, RightPlayer);
+    score_point(&mut game_logic, RightPlayer);
+    assert_eq!(game_logic.get_scores(), (SCORE_TO_WIN, SCORE_TO_WIN + 1));
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+
+    score_point(&mut game_logic, RightPlayer);
+    assert_eq!(game_logic.get_winning_player(), RightPlayer);
+}
+
+#[test]
+fn win_by_two_is_won_at_once_with_a_lead() {
+    let mut game_logic = GameLogic::new(rules_with_margin(2, None));
+
+    play_even_to(&mut game_logic, SCORE_TO_WIN - 5);
+    for _ in 0..5 {
+        score_point(&mut game_logic, LeftPlayer);
+    }
+
+    assert_eq!(game_logic.get_scores(), (SCORE_TO_WIN, SCORE_TO_WIN - 5));
+
+    assert_eq!(game_logic.get_winning_player(), LeftPlayer);
+}
+
+#[test]
+fn score_cap_ends_a_long_deuce() {
+    let score_cap = SCORE_TO_WIN + 2;
+    let mut game_logic = GameLogic::new(rules_with_margin(2, Some(score_cap)));
+
+    play_even_to(&mut game_logic, score_cap - 1);
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+
+    // one point ahead is enough at the cap
+    score_point(&mut game_logic, LeftPlayer);
+    assert_eq!(game_logic.get_winning_player(), LeftPlayer);
+}
+
+#[test]
+fn margin_and_cap_are_described() {
+    assert_eq!(MatchRules::default().to_string(), "15 points");
+    assert_eq!(rules_with_margin(2, Some(21)).to_string(), "15 points, win by two, cap 21");
+    assert_eq!(rules_with_margin(3, None).to_string(), "15 points, win by 3");
+}
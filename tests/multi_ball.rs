@@ -0,0 +1,208 @@
+// Several balls in play at once: each one collides and scores on its own,
+// and the rally only ends when the last one has landed
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::simple_bot::SimpleBot;
+use rustyvolley::vector::Vector2f;
+
+use common::{replayed_state_hashes, scripted_inputs, step_with_inputs, through_file};
+
+fn multi_ball_rules(ball_count : i32) -> MatchRules {
+    MatchRules {
+        ball_count,
+        ..MatchRules::default()
+    }
+}
+
+#[test]
+fn classic_match_has_a_single_ball() {
+    let duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+
+    assert_eq!(duel_match.get_balls().len(), 1);
+    assert_eq!(duel_match.get_balls()[0].position, duel_match.get_ball_position());
+}
+
+#[test]
+fn balls_wait_apart_for_the_serve() {
+    let duel_match = DuelMatch::new(PhysicsParams::default(), multi_ball_rules(3));
+    let balls = duel_match.get_balls();
+
+    assert_eq!(balls.len(), 3);
+    assert!(balls.iter().all(|ball| ball.is_valid));
+
+    for (index, ball) in balls.iter().enumerate() {
+        for other_ball in balls[index + 1..].iter() {
+            assert!((ball.position - other_ball.position).length() > 2.0f32 * BALL_RADIUS);
+        }
+    }
+
+    // too many balls are clamped to what fits above the field
+    let crowded_match = DuelMatch::new(PhysicsParams::default(), multi_ball_rules(100));
+    assert_eq!(crowded_match.get_balls().len(), MAX_BALL_COUNT);
+}
+
+#[test]
+fn each_ball_scores_and_the_rally_waits_for_the_last_one() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), multi_ball_rules(2));
+    duel_match.get_world().set_game_running(true);
+
+    // the second ball falls on the left side, the first one stays in the air
+    {
+        let ball = duel_match.get_world().get_ball_mut(1);
+        ball.position = Vector2f::new(60.0f32, GROUND_PLANE_HEIGHT - 1.0f32);
+        ball.velocity = Vector2f::new(0.0f32, 10.0f32);
+    }
+    duel_match.get_world().set_ball_position(Vector2f::new(600.0f32, 100.0f32));
+
+    let mut events = vec!();
+    duel_match.step(&mut events);
+
+    assert_eq!(duel_match.get_scores(), (0, 1));
+    assert!(!duel_match.get_balls()[1].is_valid);
+    assert!(duel_match.get_balls()[0].is_valid);
+
+    // the first ball lands on the right side later on
+    let mut reset = false;
+    for _ in 0..2000 {
+        events.clear();
+        duel_match.step(&mut events);
+
+        if events.contains(&FrameEvent::EventReset) {
+            reset = true;
+            break;
+        }
+    }
+
+    assert!(reset);
+    assert_eq!(duel_match.get_scores(), (1, 1));
+    assert!(duel_match.get_balls().iter().all(|ball| ball.is_valid));
+}
+
+#[test]
+fn replay_keeps_every_ball() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), multi_ball_rules(3));
+    duel_match.start_recording();
+
+    let mut expected_hashes = vec!();
+    for frame_inputs in scripted_inputs(6000).iter() {
+        step_with_inputs(&mut duel_match, frame_inputs);
+        expected_hashes.push(duel_match.get_state_hash());
+    }
+
+    let replay = through_file(&duel_match.stop_recording().unwrap());
+    assert_eq!(replay.initial_state.physic_world.balls.len(), 3);
+    assert_eq!(replayed_state_hashes(replay), expected_hashes);
+}
+
+#[test]
+fn bot_plays_the_ball_coming_to_its_side() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), multi_ball_rules(2));
+    duel_match.get_world().set_game_running(true);
+
+    // the first ball flies away over the right side, the second one drops
+    // on the left side
+    duel_match.get_world().set_ball_position(Vector2f::new(600.0f32, 300.0f32));
+    {
+        let ball = duel_match.get_world().get_ball_mut(1);
+        ball.position = Vector2f::new(200.0f32, 100.0f32);
+        ball.velocity = Vector2f::new(0.0f32, 0.0f32);
+    }
+
+    let mut left_bot = SimpleBot::new(LeftPlayer, 0, PhysicsParams::default());
+    let mut right_bot = SimpleBot::new(RightPlayer, 0, PhysicsParams::default());
+
+    assert_eq!(left_bot.choose_ball(duel_match.get_balls()), 1);
+    assert_eq!(right_bot.choose_ball(duel_match.get_balls()), 0);
+
+    let mut single_ball_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    assert_eq!(left_bot.choose_ball(single_ball_match.get_world().get_balls()), 0);
+}
+
+#[test]
+fn balls_still_in_play_do_not_score_after_the_match_is_won() {
+    let rules = MatchRules {
+        score_to_win : 1,
+        win_margin : 1,
+        ..multi_ball_rules(2)
+    };
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), rules);
+    duel_match.get_world().set_game_running(true);
+
+    // the second ball wins the match, the first one is still in the air
+    {
+        let ball = duel_match.get_world().get_ball_mut(1);
+        ball.position = Vector2f::new(60.0f32, GROUND_PLANE_HEIGHT - 1.0f32);
+        ball.velocity = Vector2f::new(0.0f32, 10.0f32);
+    }
+    duel_match.get_world().set_ball_position(Vector2f::new(600.0f32, 100.0f32));
+
+    let mut events = vec!();
+    duel_match.step(&mut events);
+
+    assert_eq!(duel_match.get_scores(), (0, 1));
+    assert!(events.contains(&FrameEvent::EventMatchWon(RightPlayer)));
+
+    // the first ball lands on the right side, after the match is decided
+    let mut winners = vec!();
+    for _ in 0..2000 {
+        events.clear();
+        duel_match.step(&mut events);
+
+        for event in events.iter() {
+            if let FrameEvent::EventMatchWon(winner) = *event {
+                winners.push(winner);
+            }
+        }
+
+        if !duel_match.get_balls()[0].is_valid {
+            break;
+        }
+    }
+
+    assert!(!duel_match.get_balls()[0].is_valid);
+    assert_eq!(duel_match.get_scores(), (0, 1));
+    assert!(winners.iter().all(|winner| *winner == RightPlayer));
+}
+
+#[test]
+fn replay_marks_each_point_scored_once() {
+    let rules = MatchRules {
+        score_to_win : 2,
+        ..multi_ball_rules(3)
+    };
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), rules);
+    duel_match.get_world().set_game_running(true);
+    duel_match.start_recording();
+
+    // the second and the third balls fall on the left side in the same
+    // step, the first one stays in the air
+    for &(ball_index, x) in [(1, 60.0f32), (2, 130.0f32)].iter() {
+        let ball = duel_match.get_world().get_ball_mut(ball_index);
+        ball.position = Vector2f::new(x, GROUND_PLANE_HEIGHT - 1.0f32);
+        ball.velocity = Vector2f::new(0.0f32, 10.0f32);
+    }
+    duel_match.get_world().set_ball_position(Vector2f::new(600.0f32, 100.0f32));
+
+    duel_match.step(&mut vec!());
+    assert_eq!(duel_match.get_scores(), (0, 2));
+
+    // the first ball lands after the match is won and marks nothing
+    for _ in 0..2000 {
+        duel_match.step(&mut vec!());
+        if !duel_match.get_balls()[0].is_valid {
+            break;
+        }
+    }
+
+    assert!(!duel_match.get_balls()[0].is_valid);
+    let replay = duel_match.stop_recording().unwrap();
+    assert_eq!(replay.point_boundaries, vec!(1, 1));
+}
@@ -0,0 +1,166 @@
+// Static obstacles of the arena: circles and segments the ball bounces off,
+// keeping the restitution share of its speed towards them
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::arena::{Arena, ArenaFileError, Obstacle, ObstacleShape, parse_arena};
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::simple_bot::{Axis, SimpleBot};
+use rustyvolley::vector::Vector2f;
+
+use common::{flying_world, replay_through_file};
+
+fn obstacle_params(obstacles : &[Obstacle]) -> PhysicsParams {
+    let mut arena = Arena::default();
+    for obstacle in obstacles {
+        assert!(arena.add_obstacle(*obstacle));
+    }
+
+    PhysicsParams {
+        arena,
+        ..PhysicsParams::default()
+    }
+}
+
+fn bumper(centre : Vector2f, restitution : f32) -> Obstacle {
+    Obstacle {
+        shape : ObstacleShape::Circle { centre, radius : 20.0f32 },
+        restitution,
+    }
+}
+
+#[test]
+fn obstacle_lines_are_parsed() {
+    let arena = parse_arena(
+        "circle = 400, 150, 25, 1\nsegment = 0, 120, 400, 40, 0.8 # roof"
+    ).unwrap();
+    let obstacles : Vec<Obstacle> = arena.get_obstacles().cloned().collect();
+
+    assert_eq!(obstacles, vec!(
+        Obstacle {
+            shape : ObstacleShape::Circle { centre : Vector2f::new(400.0f32, 150.0f32), radius : 25.0f32 },
+            restitution : 1.0f32,
+        },
+        Obstacle {
+            shape : ObstacleShape::Segment {
+                start : Vector2f::new(0.0f32, 120.0f32),
+                end : Vector2f::new(400.0f32, 40.0f32),
+            },
+            restitution : 0.8f32,
+        },
+    ));
+
+    match parse_arena("circle = 400, 150, 25") {
+        Err(ArenaFileError::Syntax { line : 1 }) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    match parse_arena("segment = 10, 10, 10, 10, 1") {
+        Err(ArenaFileError::Invalid(_)) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let crowded = "circle = 400, 100, 5, 1\n".repeat(9);
+    match parse_arena(&crowded) {
+        Err(ArenaFileError::Invalid(_)) => (),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn ball_bounces_off_a_bumper_with_its_restitution() {
+    let bounce_speed = |restitution : f32| {
+        let params = obstacle_params(&[bumper(Vector2f::new(200.0f32, 150.0f32), restitution)]);
+        let mut world =
+            flying_world(params, Vector2f::new(120.0f32, 150.0f32), Vector2f::new(10.0f32, 0.0f32));
+        let mut hit_obstacle = false;
+
+        for _ in 0..20 {
+            world.step();
+            hit_obstacle |= world.ball_hit_wall();
+            let distance = (world.get_ball_position() - Vector2f::new(200.0f32, 150.0f32)).length();
+            assert!(distance >= 20.0f32 + BALL_RADIUS - 0.01f32);
+        }
+
+        assert!(hit_obstacle);
+        world.get_ball_velocity().x
+    };
+
+    // gravity makes the hit slightly off centre
+    assert!((bounce_speed(1.0f32) + 10.0f32).abs() < 0.2f32);
+    assert!((bounce_speed(0.5f32) + 5.0f32).abs() < 0.2f32);
+}
+
+#[test]
+fn slanted_segment_deflects_the_ball_sideways() {
+    // a roof going down to the right, the ball rising straight below it is
+    // sent to the left
+    let roof = Obstacle {
+        shape : ObstacleShape::Segment {
+            start : Vector2f::new(100.0f32, 50.0f32),
+            end : Vector2f::new(300.0f32, 250.0f32),
+        },
+        restitution : 1.0f32,
+    };
+    let mut world = flying_world(
+        obstacle_params(&[roof]),
+        Vector2f::new(200.0f32, 300.0f32),
+        Vector2f::new(0.0f32, -20.0f32)
+    );
+
+    for _ in 0..40 {
+        world.step();
+    }
+
+    assert!(world.get_ball_velocity().x < -5.0f32);
+}
+
+#[test]
+fn mirrored_arena_mirrors_the_obstacles() {
+    let arena = obstacle_params(&[bumper(Vector2f::new(150.0f32, 100.0f32), 1.0f32)]).arena;
+    let mirrored = arena.mirrored();
+
+    assert_eq!(
+        mirrored.get_obstacles().next().unwrap().shape,
+        ObstacleShape::Circle { centre : Vector2f::new(650.0f32, 100.0f32), radius : 20.0f32 }
+    );
+    assert_eq!(mirrored.mirrored(), arena);
+}
+
+#[test]
+fn replay_keeps_the_obstacles() {
+    let params = obstacle_params(&[bumper(Vector2f::new(400.0f32, 150.0f32), 0.9f32)]);
+
+    assert_eq!(replay_through_file(params, MatchRules::default(), 500).physics_params, params);
+}
+
+#[test]
+fn bot_prediction_sees_the_obstacles() {
+    // a bumper in the way of the ball drifting to the right sends it back
+    let params = obstacle_params(&[bumper(Vector2f::new(250.0f32, 250.0f32), 1.0f32)]);
+    let mut classic_bot = SimpleBot::new(LeftPlayer, 0, PhysicsParams::default());
+    let mut bumper_bot = SimpleBot::new(LeftPlayer, 0, params);
+
+    // steps until the ball drifting from x = 200 crosses x = 300
+    let predict = |bot : &mut SimpleBot| {
+        bot.simulate_until(
+            200.0f32,
+            VERTICAL_PLANE_LENGTH - 150.0f32,
+            2.0f32,
+            0.0f32,
+            Axis::AxisX,
+            300.0f32
+        ).0
+    };
+
+    let classic_steps = predict(&mut classic_bot);
+    let bumper_steps = predict(&mut bumper_bot);
+
+    assert!(classic_steps.is_finite());
+    assert!(bumper_steps > classic_steps);
+}
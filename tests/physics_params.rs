@@ -0,0 +1,122 @@
+// PhysicsParams go through to_array to be hashed and written in replays,
+// every field must come back from from_array at its place
+
+extern crate rustyvolley;
+
+use rustyvolley::arena::{Arena, Obstacle, ObstacleShape, MAX_ARENA_OBSTACLES};
+use rustyvolley::character::Character;
+use rustyvolley::physics_params::{PhysicsParams, PHYSICS_PARAM_COUNT};
+use rustyvolley::replay_file::physics_constants_hash;
+use rustyvolley::vector::Vector2f;
+
+// Every field set to its own value, without `..` so that a new field does
+// not build until it is added here
+fn distinct_params() -> PhysicsParams {
+    let mut obstacles = [None; MAX_ARENA_OBSTACLES];
+    obstacles[0] = Some(Obstacle {
+        shape : ObstacleShape::Circle { centre : Vector2f::new(301.0, 302.0), radius : 303.0 },
+        restitution : 0.25,
+    });
+    obstacles[MAX_ARENA_OBSTACLES - 1] = Some(Obstacle {
+        shape : ObstacleShape::Segment { start : Vector2f::new(311.0, 312.0), end : Vector2f::new(313.0, 314.0) },
+        restitution : 0.75,
+    });
+
+    PhysicsParams {
+        blobby_jump_buffer : 1.0,
+        blobby_gravitation : 2.0,
+        blobby_jump_acceleration : 3.0,
+        blobby_speed : 4.0,
+        blobby_animation_speed : 5.0,
+        ball_gravitation : 6.0,
+        ball_collision_velocity : 7.0,
+        standard_ball_angular_velocity : 8.0,
+        ball_angular_velocity_scale_factor : 9.0,
+        damp_ball_scale_factor : 10.0,
+        speed_scale_on_ground_bounce_x : 11.0,
+        speed_scale_on_ground_bounce_y : 12.0,
+        perpendicular_kinematic_energy_damping_factor : 13.0,
+        parallel_kinematic_energy_damping_factor : 14.0,
+        time_scaling : 15.0,
+        spin_factor : 16.0,
+        wind_force : 17.0,
+        wind_gust_force : 18.0,
+        air_drag : 19.0,
+        arena : Arena {
+            left_wall : 20.0,
+            right_wall : 21.0,
+            net_position_x : 22.0,
+            net_sphere_position : 23.0,
+            net_radius : 24.0,
+            ground_height : 25.0,
+            ceiling : Some(26.0),
+            left_spawn_x : 27.0,
+            right_spawn_x : 28.0,
+            ball_spawn_y : 29.0,
+            obstacles,
+        },
+        characters : [
+            Character { speed : 41.0, jump : 42.0, size : 43.0 },
+            Character { speed : 44.0, jump : 45.0, size : 46.0 },
+        ],
+    }
+}
+
+#[test]
+fn default_params_round_trip() {
+    let params = PhysicsParams::default();
+
+    assert_eq!(PhysicsParams::from_array(params.to_array()), params);
+}
+
+#[test]
+fn every_field_round_trips_at_its_place() {
+    let params = distinct_params();
+
+    assert_eq!(PhysicsParams::from_array(params.to_array()), params);
+}
+
+#[test]
+fn array_round_trips() {
+    let values = distinct_params().to_array();
+
+    assert_eq!(values.len(), PHYSICS_PARAM_COUNT);
+    assert_eq!(&PhysicsParams::from_array(values).to_array()[..], &values[..]);
+}
+
+#[test]
+fn open_field_has_no_ceiling_after_a_round_trip() {
+    let mut params = distinct_params();
+    params.arena.ceiling = None;
+
+    assert_eq!(PhysicsParams::from_array(params.to_array()).arena.ceiling, None);
+}
+
+#[test]
+fn ceiling_at_the_top_of_the_window_survives_a_round_trip() {
+    let mut params = PhysicsParams::default();
+    params.arena.ceiling = Some(0.0);
+    assert!(params.arena.validate().is_ok());
+
+    let read_back = PhysicsParams::from_array(params.to_array());
+
+    assert_eq!(read_back.arena.ceiling, Some(0.0));
+    assert_ne!(physics_constants_hash(&params), physics_constants_hash(&PhysicsParams::default()));
+}
+
+#[test]
+fn every_value_changes_the_physics_hash() {
+    let values = distinct_params().to_array();
+    let hash = physics_constants_hash(&PhysicsParams::from_array(values));
+
+    for index in 0..PHYSICS_PARAM_COUNT {
+        let mut changed_values = values;
+        changed_values[index] += 0.5;
+        let changed_params = PhysicsParams::from_array(changed_values);
+
+        // from_array drops the values an obstacle slot does not use
+        if changed_params.to_array()[..] != values[..] {
+            assert_ne!(physics_constants_hash(&changed_params), hash, "value {}", index);
+        }
+    }
+}
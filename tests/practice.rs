@@ -0,0 +1,120 @@
+// Practice rules: errors end the rallies without scoring, the touch limit
+// can be lifted and the rally restarted at any moment
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::game_constants::*;
+use rustyvolley::game_logic::{ErrorReason, GameLogic};
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+use rustyvolley::vector::Vector2f;
+
+use common::{replay_through_file, throw_ball, touch_left};
+
+fn practice_rules() -> MatchRules {
+    MatchRules {
+        practice : true,
+        ..MatchRules::default()
+    }
+}
+
+#[test]
+fn practice_errors_score_nothing() {
+    let mut game_logic = GameLogic::new(practice_rules());
+
+    for _ in 0..2 * SCORE_TO_WIN {
+        game_logic.on_ball_hits_ground(LeftPlayer, 0);
+        assert_eq!(game_logic.get_last_error_side(), LeftPlayer);
+    }
+
+    assert_eq!(game_logic.get_scores(), (0, 0));
+    assert_eq!(game_logic.get_serving_player(), LeftPlayer);
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+}
+
+#[test]
+fn practice_rally_still_ends_on_the_ground() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), practice_rules());
+    duel_match.start_recording();
+    throw_ball(&mut duel_match, Vector2f::new(60.0f32, GROUND_PLANE_HEIGHT - 1.0f32), Vector2f::new(0.0f32, 10.0f32));
+
+    let mut events = vec!();
+    for _ in 0..2000 {
+        duel_match.step(&mut events);
+    }
+
+    assert!(events.contains(&FrameEvent::EventError { side : LeftPlayer, reason : ErrorReason::BallHitGround }));
+    assert!(events.contains(&FrameEvent::EventReset));
+    assert_eq!(duel_match.get_scores(), (0, 0));
+    assert_eq!(duel_match.get_serving_player(), LeftPlayer);
+
+    // no point was played
+    assert!(duel_match.stop_recording().unwrap().point_boundaries.is_empty());
+}
+
+#[test]
+fn infinite_touches_lift_the_touch_limit() {
+    let mut game_logic =
+        GameLogic::new(MatchRules {
+            infinite_touches : true,
+            ..practice_rules()
+        });
+    touch_left(&mut game_logic, 10);
+    assert_eq!(game_logic.get_last_error_side(), NoPlayer);
+    assert_eq!(game_logic.get_touch_count(LeftPlayer, 0), 10);
+}
+
+#[test]
+fn restart_rally_puts_the_ball_above_the_blob() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), practice_rules());
+    let serve_position = duel_match.get_ball_position();
+    let spawn_position = duel_match.get_blob_position(LeftPlayer);
+
+    // the blob walks away and the ball flies
+    duel_match.get_world().set_player_input(LeftPlayer, PlayerInput { left : false, right : true, up : false });
+    duel_match.get_world().set_game_running(true);
+    duel_match.get_world().set_ball_velocity(Vector2f::new(5.0f32, -5.0f32));
+    for _ in 0..30 {
+        duel_match.step(&mut vec!());
+    }
+    assert_ne!(duel_match.get_ball_position(), serve_position);
+    assert_ne!(duel_match.get_blob_position(LeftPlayer), spawn_position);
+
+    duel_match.restart_rally(LeftPlayer);
+
+    assert_eq!(duel_match.get_ball_position(), serve_position);
+    assert_eq!(duel_match.get_blob_position(LeftPlayer), spawn_position);
+    assert!(!duel_match.get_world().is_game_running());
+
+    // and above the other blob
+    duel_match.restart_rally(RightPlayer);
+    assert_eq!(duel_match.get_ball_position().x, duel_match.get_blob_position(RightPlayer).x);
+}
+
+#[test]
+fn restart_rally_ends_the_recording() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), practice_rules());
+    duel_match.start_recording();
+    duel_match.step(&mut vec!());
+
+    // a replay could not follow the ball put back by hand
+    duel_match.restart_rally(LeftPlayer);
+
+    assert!(!duel_match.is_recording());
+    assert!(duel_match.stop_recording().is_none());
+}
+
+#[test]
+fn replay_keeps_the_practice_rules() {
+    let rules = MatchRules {
+        infinite_touches : true,
+        ..practice_rules()
+    };
+    let replay = replay_through_file(PhysicsParams::default(), rules, 50);
+    assert_eq!(replay.initial_state.game_logic.rules, rules);
+}
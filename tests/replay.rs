@@ -0,0 +1,128 @@
+// A recorded match plays back step for step, events included, and keeps
+// who played it
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::global::PlayerKind::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::replay::{Replay, ReplayPlayer};
+use rustyvolley::simple_bot::SimpleBot;
+
+use common::{scripted_inputs, step_with_inputs, through_file};
+
+const STEP_COUNT : usize = 4000;
+const BOT_DIFFICULTY : i32 = 25;
+
+// a serving bot against a human standing still, the difficulty by player
+fn recorded_bot_match() -> (Replay, Vec<Vec<FrameEvent>>, Vec<u64>) {
+    let params = PhysicsParams::default();
+    let rules = MatchRules {
+        first_serving_player : RightPlayer,
+        ..MatchRules::default()
+    };
+    let mut duel_match = DuelMatch::new(params, rules);
+    let mut bot = SimpleBot::new(RightPlayer, BOT_DIFFICULTY, params);
+
+    duel_match.start_recording_players([Human, Computer], [0, BOT_DIFFICULTY]);
+
+    let mut events = vec!();
+    let mut hashes = vec!();
+    for _ in 0..STEP_COUNT {
+        bot.play(&mut duel_match);
+
+        let mut frame_events = vec!();
+        duel_match.step(&mut frame_events);
+        events.push(frame_events);
+        hashes.push(duel_match.get_state_hash());
+    }
+
+    (duel_match.stop_recording().unwrap(), events, hashes)
+}
+
+#[test]
+fn replay_keeps_the_players_of_the_match() {
+    let (replay, _, _) = recorded_bot_match();
+
+    assert_eq!(replay.player_kinds, [Human, Computer]);
+    assert_eq!(replay.bot_difficulties, [0, BOT_DIFFICULTY]);
+
+    let read_back = through_file(&replay);
+
+    assert_eq!(read_back.player_kinds, [Human, Computer]);
+    assert_eq!(read_back.bot_difficulties, [0, BOT_DIFFICULTY]);
+}
+
+#[test]
+fn recording_without_players_keeps_humans() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    duel_match.start_recording();
+    duel_match.step(&mut vec!());
+    let replay = duel_match.stop_recording().unwrap();
+
+    assert_eq!(replay.player_kinds, [Human, Human]);
+    assert_eq!(replay.bot_difficulties, [0, 0]);
+}
+
+#[test]
+fn recording_starts_from_the_current_state() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    for inputs in scripted_inputs(100).iter() {
+        step_with_inputs(&mut duel_match, inputs);
+    }
+
+    let initial_state = duel_match.snapshot();
+    duel_match.start_recording();
+    assert!(duel_match.is_recording());
+    duel_match.step(&mut vec!());
+
+    let replay = duel_match.stop_recording().unwrap();
+    assert!(!duel_match.is_recording());
+    assert_eq!(replay.initial_state, initial_state);
+    assert_eq!(duel_match.stop_recording(), None);
+}
+
+#[test]
+fn playback_gives_the_recorded_events_and_states() {
+    let (replay, expected_events, expected_hashes) = recorded_bot_match();
+    assert_eq!(replay.get_frame_count(), STEP_COUNT);
+    assert!(!replay.point_boundaries.is_empty());
+
+    let mut replay_player = ReplayPlayer::new(replay);
+    let mut duel_match = replay_player.create_match();
+    let mut events = vec!();
+    let mut hashes = vec!();
+
+    let mut frame_events = vec!();
+    while replay_player.step(&mut duel_match, &mut frame_events) {
+        events.push(frame_events.clone());
+        frame_events.clear();
+        hashes.push(duel_match.get_state_hash());
+    }
+
+    assert!(replay_player.is_finished());
+    assert_eq!(events, expected_events);
+    assert_eq!(hashes, expected_hashes);
+}
+
+#[test]
+fn rewound_playback_starts_over() {
+    let (replay, _, expected_hashes) = recorded_bot_match();
+    let mut replay_player = ReplayPlayer::new(replay);
+
+    let mut duel_match = replay_player.create_match();
+    for _ in 0..STEP_COUNT / 2 {
+        replay_player.step(&mut duel_match, &mut vec!());
+    }
+
+    replay_player.rewind();
+    assert_eq!(replay_player.get_frame_index(), 0);
+
+    let mut duel_match = replay_player.create_match();
+    replay_player.step(&mut duel_match, &mut vec!());
+    assert_eq!(duel_match.get_state_hash(), expected_hashes[0]);
+}
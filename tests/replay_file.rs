@@ -0,0 +1,129 @@
+// The .rvr files must give back the recorded replay exactly, and refuse
+// the files they cannot play faithfully instead of replaying something else
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::global::PlayerKind;
+use rustyvolley::match_rules::{Handicap, MatchRules, ScoringMode};
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::replay::Replay;
+use rustyvolley::replay_file::{read_replay, write_replay, ReplayFileError, REPLAY_FORMAT_VERSION};
+
+use common::{scripted_inputs, step_with_inputs};
+
+// magic then format version, the physics hash follows
+const VERSION_OFFSET : usize = 4;
+const PHYSICS_HASH_OFFSET : usize = 6;
+
+fn file_rules() -> MatchRules {
+    let mut handicaps = [Handicap::default(); 2];
+    handicaps[1] = Handicap { head_start : 2, touch_penalty : 1, speed_penalty : 10 };
+
+    MatchRules {
+        score_to_win : 7,
+        win_margin : 2,
+        score_cap : Some(9),
+        scoring_mode : ScoringMode::SideOutScoring,
+        set_count : 3,
+        swap_sides_between_sets : true,
+        time_limit : Some(6000),
+        handicaps,
+        ..MatchRules::default()
+    }
+}
+
+// recorded from the middle of a rally, with several points in it
+fn recorded_replay() -> Replay {
+    let params = PhysicsParams {
+        ball_gravitation : 0.3,
+        ..PhysicsParams::default()
+    };
+
+    let inputs = scripted_inputs(3200);
+    let (first_inputs, recorded_inputs) = inputs.split_at(200);
+
+    let mut duel_match = DuelMatch::new(params, file_rules());
+    for frame_inputs in first_inputs.iter() {
+        step_with_inputs(&mut duel_match, frame_inputs);
+    }
+
+    duel_match.start_recording();
+    for frame_inputs in recorded_inputs.iter() {
+        step_with_inputs(&mut duel_match, frame_inputs);
+    }
+    let mut replay = duel_match.stop_recording().unwrap();
+    replay.player_kinds = [PlayerKind::Human, PlayerKind::Computer];
+    replay.bot_difficulties = [0, 30];
+
+    replay
+}
+
+fn written(replay : &Replay) -> Vec<u8> {
+    let mut file = vec!();
+    write_replay(replay, &mut file).unwrap();
+    file
+}
+
+#[test]
+fn file_round_trip_gives_back_the_replay() {
+    let replay = recorded_replay();
+    assert!(!replay.point_boundaries.is_empty());
+    assert!(replay.initial_state.physic_world.is_game_running);
+
+    let read_back = read_replay(&mut &written(&replay)[..]).unwrap();
+
+    assert_eq!(read_back.frames, replay.frames);
+    assert_eq!(read_back.point_boundaries, replay.point_boundaries);
+    assert_eq!(read_back.initial_state.game_logic.rules, file_rules());
+    assert_eq!(read_back.initial_state, replay.initial_state);
+    assert_eq!(read_back.physics_params, replay.physics_params);
+    assert_eq!(read_back.player_kinds, replay.player_kinds);
+    assert_eq!(read_back.bot_difficulties, replay.bot_difficulties);
+    assert_eq!(read_back, replay);
+}
+
+#[test]
+fn file_with_other_physics_is_rejected() {
+    let mut file = written(&recorded_replay());
+    file[PHYSICS_HASH_OFFSET] ^= 1;
+
+    match read_replay(&mut &file[..]) {
+        Err(ReplayFileError::PhysicsMismatch { expected, found }) => assert_ne!(expected, found),
+        result => panic!("expected a physics mismatch, got {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn file_with_a_bad_magic_is_rejected() {
+    let mut file = written(&recorded_replay());
+    file[0] = b'X';
+
+    match read_replay(&mut &file[..]) {
+        Err(ReplayFileError::InvalidMagic) => {},
+        result => panic!("expected an invalid magic, got {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn file_of_another_version_is_rejected() {
+    let mut file = written(&recorded_replay());
+    let version = REPLAY_FORMAT_VERSION + 1;
+    file[VERSION_OFFSET..PHYSICS_HASH_OFFSET].copy_from_slice(&version.to_le_bytes());
+
+    match read_replay(&mut &file[..]) {
+        Err(ReplayFileError::UnsupportedVersion(found)) => assert_eq!(found, version),
+        result => panic!("expected an unsupported version, got {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn truncated_file_is_rejected() {
+    let file = written(&recorded_replay());
+
+    for length in 0..file.len() {
+        assert!(read_replay(&mut &file[..length]).is_err(), "file cut at {} bytes", length);
+    }
+}
@@ -0,0 +1,158 @@
+// Matches of several sets: the sets are counted by player, the first server
+// alternates between the players and the players may swap sides, taking
+// their inputs with them
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_logic::MatchLogic;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+use rustyvolley::vector::Vector2f;
+
+use common::throw_ball;
+
+fn set_rules(swap_sides_between_sets : bool) -> MatchRules {
+    MatchRules {
+        score_to_win : 1,
+        set_count : 3,
+        swap_sides_between_sets,
+        ..MatchRules::default()
+    }
+}
+
+// drops the ball on the ground of the side, away from its blob, and steps
+// until the round is over
+fn drop_ball_on(duel_match : &mut DuelMatch, side : PlayerSide) -> Vec<FrameEvent> {
+    let ball_x = if side == LeftPlayer { 100.0f32 } else { 700.0f32 };
+
+    throw_ball(duel_match, Vector2f::new(ball_x, GROUND_PLANE_HEIGHT - 1.0f32), Vector2f::new(0.0f32, 10.0f32));
+
+    let mut events = vec!();
+    for _ in 0..2000 {
+        duel_match.step(&mut events);
+        if events.contains(&FrameEvent::EventReset) {
+            return events;
+        }
+    }
+
+    panic!("the round never ended");
+}
+
+#[test]
+fn sets_to_win_are_a_majority() {
+    let sets_to_win = |set_count| MatchRules { set_count, ..MatchRules::default() }.get_sets_to_win();
+
+    assert_eq!(sets_to_win(1), 1);
+    assert_eq!(sets_to_win(3), 2);
+    assert_eq!(sets_to_win(5), 3);
+}
+
+#[test]
+fn match_is_won_with_the_majority_of_sets() {
+    let rules = set_rules(false);
+    let mut match_logic = MatchLogic::new();
+
+    match_logic.on_set_won(LeftPlayer, [1, 0], &rules);
+    assert!(match_logic.is_set_finished());
+    assert_eq!(match_logic.get_winning_player(), NoPlayer);
+
+    match_logic.start_next_set(&rules);
+    assert!(!match_logic.is_set_finished());
+    match_logic.on_set_won(RightPlayer, [0, 1], &rules);
+    match_logic.start_next_set(&rules);
+    match_logic.on_set_won(RightPlayer, [0, 1], &rules);
+
+    assert_eq!(match_logic.get_set_scores(), (1, 2));
+    assert_eq!(match_logic.get_finished_sets(), &[[1, 0], [0, 1], [0, 1]]);
+    assert_eq!(match_logic.get_winning_player(), RightPlayer);
+}
+
+#[test]
+fn first_server_alternates_between_the_sides_without_swap() {
+    let rules = set_rules(false);
+    let mut match_logic = MatchLogic::new();
+
+    match_logic.on_set_won(LeftPlayer, [1, 0], &rules);
+    assert_eq!(match_logic.start_next_set(&rules), RightPlayer);
+    match_logic.on_set_won(RightPlayer, [0, 1], &rules);
+    assert_eq!(match_logic.start_next_set(&rules), LeftPlayer);
+
+    assert_eq!(match_logic.get_side_of_player(LeftPlayer), LeftPlayer);
+}
+
+#[test]
+fn first_server_stays_on_its_side_with_swap() {
+    let rules = set_rules(true);
+    let mut match_logic = MatchLogic::new();
+
+    // the right player serves the second set, from the left side
+    match_logic.on_set_won(LeftPlayer, [1, 0], &rules);
+    assert_eq!(match_logic.start_next_set(&rules), LeftPlayer);
+    assert_eq!(match_logic.get_side_of_player(LeftPlayer), RightPlayer);
+    assert_eq!(match_logic.get_player_on_side(LeftPlayer), RightPlayer);
+
+    // and the left player the third set, back on the left side
+    match_logic.on_set_won(RightPlayer, [0, 1], &rules);
+    assert_eq!(match_logic.start_next_set(&rules), LeftPlayer);
+    assert_eq!(match_logic.get_side_of_player(LeftPlayer), LeftPlayer);
+    assert_eq!(match_logic.get_side_of_player(NoPlayer), NoPlayer);
+}
+
+#[test]
+fn played_match_swaps_the_sides_between_sets() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), set_rules(true));
+
+    // the left player jumps in place, the right one stands still
+    let mut jumping = PlayerInput::new();
+    jumping.up = true;
+    let standing = PlayerInput::new();
+    duel_match.get_world().set_player_input(LeftPlayer, jumping);
+    duel_match.get_world().set_player_input(RightPlayer, standing);
+
+    let events = drop_ball_on(&mut duel_match, RightPlayer);
+    assert!(events.contains(&FrameEvent::EventSetWon(LeftPlayer)));
+    assert!(!events.contains(&FrameEvent::EventMatchWon(LeftPlayer)));
+
+    // second set: the players and their inputs swapped sides, the right
+    // player serves from the left side
+    assert_eq!(duel_match.get_side_of_player(LeftPlayer), RightPlayer);
+    assert_eq!(duel_match.get_world().get_player_input(LeftPlayer), standing);
+    assert_eq!(duel_match.get_world().get_player_input(RightPlayer), jumping);
+    assert_eq!(duel_match.get_scores(), (0, 0));
+    assert_eq!(duel_match.get_serving_player(), LeftPlayer);
+    assert_eq!(duel_match.get_set_scores(), (1, 0));
+
+    // the side the set is won on is not the player who wins it
+    let events = drop_ball_on(&mut duel_match, RightPlayer);
+    assert!(events.contains(&FrameEvent::EventSetWon(RightPlayer)));
+    assert_eq!(duel_match.get_side_of_player(LeftPlayer), LeftPlayer);
+    assert_eq!(duel_match.get_world().get_player_input(LeftPlayer), jumping);
+    assert_eq!(duel_match.get_finished_sets(), &[[1, 0], [0, 1]]);
+
+    let events = drop_ball_on(&mut duel_match, LeftPlayer);
+    assert!(events.contains(&FrameEvent::EventSetWon(RightPlayer)));
+    assert!(events.contains(&FrameEvent::EventMatchWon(RightPlayer)));
+    assert_eq!(duel_match.get_set_scores(), (1, 2));
+}
+
+#[test]
+fn played_match_keeps_the_sides_without_swap() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), set_rules(false));
+    let mut jumping = PlayerInput::new();
+    jumping.up = true;
+    duel_match.get_world().set_player_input(LeftPlayer, jumping);
+
+    drop_ball_on(&mut duel_match, RightPlayer);
+
+    assert_eq!(duel_match.get_side_of_player(LeftPlayer), LeftPlayer);
+    assert_eq!(duel_match.get_world().get_player_input(LeftPlayer), jumping);
+    assert_eq!(duel_match.get_serving_player(), RightPlayer);
+    assert_eq!(duel_match.get_set_scores(), (1, 0));
+}
@@ -0,0 +1,148 @@
+// Side-out scoring: only the serving player scores, the receiver winning a
+// rally only takes the serve
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, FrameEvent};
+use rustyvolley::game_constants::*;
+use rustyvolley::game_logic::{other_side, ErrorReason, GameLogic};
+use rustyvolley::global::PlayerSide;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::{MatchRules, ScoringMode};
+use rustyvolley::physics_params::PhysicsParams;
+
+use common::{scripted_inputs, touch_left};
+
+fn side_out_rules() -> MatchRules {
+    MatchRules {
+        scoring_mode : ScoringMode::SideOutScoring,
+        ..MatchRules::default()
+    }
+}
+
+fn win_rally(game_logic : &mut GameLogic, side : PlayerSide) {
+    game_logic.on_error(other_side(side), ErrorReason::BallHitGround, 0);
+}
+
+#[test]
+fn server_scores_and_keeps_the_serve() {
+    let mut game_logic = GameLogic::new(side_out_rules());
+
+    win_rally(&mut game_logic, LeftPlayer);
+    win_rally(&mut game_logic, LeftPlayer);
+
+    assert_eq!(game_logic.get_scores(), (2, 0));
+    assert_eq!(game_logic.get_serving_player(), LeftPlayer);
+}
+
+#[test]
+fn receiver_only_takes_the_serve() {
+    let mut game_logic = GameLogic::new(side_out_rules());
+
+    win_rally(&mut game_logic, RightPlayer);
+
+    assert_eq!(game_logic.get_scores(), (0, 0));
+    assert_eq!(game_logic.get_serving_player(), RightPlayer);
+
+    // now serving, the right player scores
+    win_rally(&mut game_logic, RightPlayer);
+
+    assert_eq!(game_logic.get_scores(), (0, 1));
+    assert_eq!(game_logic.get_serving_player(), RightPlayer);
+}
+
+#[test]
+fn serve_follows_the_first_serving_player() {
+    let mut game_logic = GameLogic::new(MatchRules {
+        first_serving_player : RightPlayer,
+        ..side_out_rules()
+    });
+
+    win_rally(&mut game_logic, LeftPlayer);
+    assert_eq!(game_logic.get_scores(), (0, 0));
+    assert_eq!(game_logic.get_serving_player(), LeftPlayer);
+
+    win_rally(&mut game_logic, LeftPlayer);
+    assert_eq!(game_logic.get_scores(), (1, 0));
+}
+
+#[test]
+fn receiver_cannot_win_the_game() {
+    let mut game_logic = GameLogic::new(MatchRules {
+        score_to_win : 2,
+        ..side_out_rules()
+    });
+
+    // 1 to 1, the left player serving
+    win_rally(&mut game_logic, LeftPlayer);
+    win_rally(&mut game_logic, RightPlayer);
+    win_rally(&mut game_logic, RightPlayer);
+    win_rally(&mut game_logic, LeftPlayer);
+    assert_eq!(game_logic.get_scores(), (1, 1));
+    assert_eq!(game_logic.get_serving_player(), LeftPlayer);
+
+    // the right player needs a point, a side-out does not give it
+    win_rally(&mut game_logic, RightPlayer);
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+
+    win_rally(&mut game_logic, RightPlayer);
+    assert_eq!(game_logic.get_scores(), (1, 2));
+    assert_eq!(game_logic.get_winning_player(), RightPlayer);
+}
+
+#[test]
+fn too_many_touches_is_a_side_out_too() {
+    let mut game_logic = GameLogic::new(side_out_rules());
+
+    touch_left(&mut game_logic, MAX_BALL_TOUCH_COUNT + 1);
+
+    assert_eq!(game_logic.get_scores(), (0, 0));
+    assert_eq!(game_logic.get_serving_player(), RightPlayer);
+}
+
+#[test]
+fn played_match_scores_only_the_server_rallies() {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), side_out_rules());
+    let mut side_out_count = 0;
+    let mut point_count = 0;
+
+    for inputs in scripted_inputs(10000).iter() {
+        let serving_player = duel_match.get_serving_player();
+        let (left_score, right_score) = duel_match.get_scores();
+
+        duel_match.get_world().set_player_input(LeftPlayer, inputs[0]);
+        duel_match.get_world().set_player_input(RightPlayer, inputs[1]);
+        let mut events = vec!();
+        duel_match.step(&mut events);
+
+        for event in events.iter() {
+            if let FrameEvent::EventError { side, .. } = *event {
+                let rally_winner = other_side(side);
+                let scores = duel_match.get_scores();
+
+                if rally_winner == serving_player {
+                    point_count += 1;
+                    let expected_scores =
+                        if rally_winner == LeftPlayer {
+                            (left_score + 1, right_score)
+                        }
+                        else {
+                            (left_score, right_score + 1)
+                        };
+                    assert_eq!(scores, expected_scores);
+                }
+                else {
+                    side_out_count += 1;
+                    assert_eq!(scores, (left_score, right_score));
+                }
+
+                assert_eq!(duel_match.get_serving_player(), rally_winner);
+            }
+        }
+    }
+
+    assert!(point_count > 0);
+    assert!(side_out_count > 0);
+}
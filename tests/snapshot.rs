@@ -0,0 +1,64 @@
+// A snapshot taken in the middle of a rally brings the match back to that
+// exact point: stepping again from it gives the same states
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::{DuelMatch, MatchSnapshot};
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+
+use common::{scripted_inputs, step_with_inputs};
+
+const RALLY_STEP : usize = 200;
+const STEP_COUNT : usize = 1500;
+
+fn states_after(duel_match : &mut DuelMatch, inputs : &[[PlayerInput; 2]]) -> Vec<MatchSnapshot> {
+    inputs.iter().map(|frame_inputs| {
+        step_with_inputs(duel_match, frame_inputs);
+        duel_match.snapshot()
+    }).collect()
+}
+
+fn match_in_a_rally(inputs : &[[PlayerInput; 2]]) -> DuelMatch {
+    let mut duel_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    for frame_inputs in inputs.iter() {
+        step_with_inputs(&mut duel_match, frame_inputs);
+    }
+
+    assert!(duel_match.get_world().is_game_running());
+    duel_match
+}
+
+#[test]
+fn restored_match_steps_through_the_same_states() {
+    let inputs = scripted_inputs(RALLY_STEP + STEP_COUNT);
+    let (rally_inputs, next_inputs) = inputs.split_at(RALLY_STEP);
+
+    let mut duel_match = match_in_a_rally(rally_inputs);
+    let snapshot = duel_match.snapshot();
+    let expected_states = states_after(&mut duel_match, next_inputs);
+    assert_ne!(duel_match.snapshot(), snapshot);
+
+    duel_match.restore(&snapshot);
+    assert_eq!(duel_match.snapshot(), snapshot);
+    assert_eq!(states_after(&mut duel_match, next_inputs), expected_states);
+}
+
+#[test]
+fn snapshot_restores_into_another_match() {
+    let inputs = scripted_inputs(RALLY_STEP + STEP_COUNT);
+    let (rally_inputs, next_inputs) = inputs.split_at(RALLY_STEP);
+
+    let mut duel_match = match_in_a_rally(rally_inputs);
+    let snapshot = duel_match.snapshot();
+    let expected_states = states_after(&mut duel_match, next_inputs);
+
+    let mut other_match = DuelMatch::new(PhysicsParams::default(), MatchRules::default());
+    other_match.restore(&snapshot);
+
+    assert_eq!(other_match.get_ball_position(), snapshot.physic_world.balls[0].position);
+    assert_eq!(states_after(&mut other_match, next_inputs), expected_states);
+}
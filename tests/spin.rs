@@ -0,0 +1,160 @@
+// The spin model only acts when PhysicsParams::spin_factor is set, the
+// default keeps the classic trajectories
+
+extern crate rustyvolley;
+
+mod common;
+
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::simple_bot::{Axis, SimpleBot};
+use rustyvolley::vector::Vector2f;
+
+use common::{flying_world, throw_ball};
+
+fn spin_params() -> PhysicsParams {
+    PhysicsParams {
+        spin_factor : 1.0f32,
+        ..PhysicsParams::default()
+    }
+}
+
+// ball falling on the right half of the head of the left blob
+fn world_with_off_centre_hit(params : PhysicsParams) -> PhysicWorld {
+    let mut world = PhysicWorld::new(params);
+    let blob_position = world.get_blob(LeftPlayer);
+
+    world.set_ball_position(
+        Vector2f::new(blob_position.x + 20.0f32, blob_position.y - BLOBBY_UPPER_SPHERE - 50.0f32)
+    );
+    world.set_ball_velocity(Vector2f::new(0.0f32, 5.0f32));
+    world
+}
+
+// ball flying to the right with the spin
+fn world_with_spin(params : PhysicsParams, spin : f32) -> PhysicWorld {
+    let mut world = flying_world(params, Vector2f::new(200.0f32, 100.0f32), Vector2f::new(8.0f32, 0.0f32));
+
+    world.set_ball_spin(spin);
+    world
+}
+
+#[test]
+fn off_centre_hit_spins_the_ball() {
+    let mut world = world_with_off_centre_hit(spin_params());
+
+    world.step();
+
+    assert!(world.ball_hit_left_player());
+    assert!(world.get_ball_spin() > 0.0f32);
+}
+
+#[test]
+fn classic_physics_ignore_spin() {
+    let mut world = world_with_off_centre_hit(PhysicsParams::default());
+
+    world.step();
+
+    assert!(world.ball_hit_left_player());
+    assert_eq!(world.get_ball_spin(), 0.0f32);
+
+    let mut spinning_world = world_with_spin(PhysicsParams::default(), 0.5f32);
+    let mut still_world = world_with_spin(PhysicsParams::default(), 0.0f32);
+
+    for _ in 0..10 {
+        spinning_world.step();
+        still_world.step();
+    }
+
+    assert_eq!(spinning_world.get_ball_position(), still_world.get_ball_position());
+}
+
+#[test]
+fn topspin_bends_the_flight_down() {
+    let mut topspin_world = world_with_spin(spin_params(), 0.2f32);
+    let mut backspin_world = world_with_spin(spin_params(), -0.2f32);
+    let mut still_world = world_with_spin(spin_params(), 0.0f32);
+
+    for _ in 0..20 {
+        topspin_world.step();
+        backspin_world.step();
+        still_world.step();
+    }
+
+    let still_height = still_world.get_ball_position().y;
+
+    assert!(topspin_world.get_ball_position().y > still_height);
+    assert!(backspin_world.get_ball_position().y < still_height);
+}
+
+#[test]
+fn spin_changes_the_ground_bounce() {
+    let bounce = |spin : f32| {
+        let mut world = PhysicWorld::new(spin_params());
+
+        world.set_ball_validity(false);
+        world.set_ball_position(Vector2f::new(200.0f32, GROUND_PLANE_HEIGHT_MAX - BALL_RADIUS - 1.0f32));
+        world.set_ball_velocity(Vector2f::new(0.0f32, 10.0f32));
+        world.set_ball_spin(spin);
+        world.step();
+        world.get_ball_velocity().x
+    };
+
+    // a ball rolling forward on its own is pushed forward by the ground
+    assert!(bounce(0.2f32) > 0.0f32);
+    assert!(bounce(-0.2f32) < 0.0f32);
+    assert_eq!(bounce(0.0f32), 0.0f32);
+}
+
+// x where the bot expects the flying ball to come down to the height,
+// in the coordinates of its side
+fn bot_expected_x(side : PlayerSide, spin : f32, height : f32) -> f32 {
+    let flying = world_with_spin(spin_params(), spin);
+    let ball = flying.get_balls()[0];
+
+    let mut duel_match = DuelMatch::new(spin_params(), MatchRules::default());
+    throw_ball(&mut duel_match, ball.position, ball.velocity);
+    duel_match.get_world().set_ball_spin(spin);
+
+    let mut bot = SimpleBot::new(side, 0, spin_params());
+    bot.play(&mut duel_match);
+
+    let (x, velocity_x) =
+        if side == LeftPlayer {
+            (ball.position.x, ball.velocity.x)
+        }
+        else {
+            (LEFT_PLANE + RIGHT_PLANE - ball.position.x, - ball.velocity.x)
+        };
+
+    bot.simulate_until(
+        x,
+        VERTICAL_PLANE_LENGTH - ball.position.y,
+        velocity_x,
+        - ball.velocity.y,
+        Axis::AxisY,
+        VERTICAL_PLANE_LENGTH - height
+    ).1
+}
+
+#[test]
+fn bot_prediction_keeps_the_spin_of_the_ball() {
+    let height = 300.0f32;
+    let mut world = world_with_spin(spin_params(), 0.2f32);
+    while world.get_ball_position().y < height {
+        world.step();
+    }
+    let landing_x = world.get_ball_position().x;
+
+    let left_x = bot_expected_x(LeftPlayer, 0.2f32, height);
+    let right_x = LEFT_PLANE + RIGHT_PLANE - bot_expected_x(RightPlayer, 0.2f32, height);
+
+    assert_eq!(left_x, landing_x);
+    assert!((right_x - landing_x).abs() < 0.01f32, "{} instead of {}", right_x, landing_x);
+    assert!((bot_expected_x(LeftPlayer, 0.0f32, height) - landing_x).abs() > 1.0f32);
+}
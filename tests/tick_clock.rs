@@ -0,0 +1,86 @@
+// The fixed timestep clock turns the real time between two frames into whole
+// simulation steps, whatever the frame rate
+
+extern crate rustyvolley;
+
+use std::time::Duration;
+
+use rustyvolley::tick_clock::TickClock;
+
+// a power of two keeps the step duration exact in floating point
+const STEPS_PER_SECOND : i32 = 64;
+const STEP_NANOS : u64 = 1_000_000_000 / STEPS_PER_SECOND as u64;
+const MAX_STEPS_PER_FRAME : u32 = 5;
+
+fn steps(count : u64) -> Duration {
+    Duration::from_nanos(STEP_NANOS * count)
+}
+
+#[test]
+fn one_step_is_run_per_step_duration() {
+    let mut clock = TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME);
+
+    assert_eq!(clock.advance(steps(1)), 1);
+    assert_eq!(clock.advance(steps(3)), 3);
+    assert_eq!(clock.advance(steps(1) / 2), 0);
+    assert_eq!(clock.advance(steps(1) / 2), 1);
+}
+
+#[test]
+fn remainder_is_kept_for_the_next_frames() {
+    let mut clock = TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME);
+    let quarter = steps(1) / 4;
+
+    let total : u32 = (0..12).map(|_| clock.advance(quarter)).sum();
+
+    assert_eq!(total, 3);
+}
+
+#[test]
+fn catch_up_is_clamped_after_a_stall() {
+    let mut clock = TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME);
+
+    assert_eq!(clock.advance(Duration::from_secs(10)), MAX_STEPS_PER_FRAME);
+
+    // the time beyond the clamp is dropped, not run on the next frames
+    assert_eq!(clock.advance(Duration::from_secs(0)), 0);
+    assert_eq!(clock.get_interpolation(), 0.0);
+    assert_eq!(clock.advance(steps(1)), 1);
+}
+
+#[test]
+fn interpolation_stays_below_one_step() {
+    let mut clock = TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME);
+    let mut elapsed_nanos = 1_000_000;
+
+    for _ in 0..500 {
+        clock.advance(Duration::from_nanos(elapsed_nanos));
+        let alpha = clock.get_interpolation();
+        assert!((0.0..1.0).contains(&alpha), "alpha {} out of [0, 1)", alpha);
+
+        // irregular frame times, from 1 ms to about 4 steps
+        elapsed_nanos = (elapsed_nanos * 7 + 3_000_000) % (STEP_NANOS * 4) + 1_000_000;
+    }
+}
+
+#[test]
+fn interpolation_is_the_fraction_of_the_pending_step() {
+    let mut clock = TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME);
+
+    assert_eq!(clock.advance(steps(2) + steps(1) / 4), 2);
+    assert_eq!(clock.get_interpolation(), 0.25);
+}
+
+#[test]
+fn zero_elapsed_time_runs_nothing() {
+    let mut clock = TickClock::new(STEPS_PER_SECOND, MAX_STEPS_PER_FRAME);
+
+    // a clock going backward is clamped to zero by the frontend before
+    // reaching the tick clock, a Duration cannot be negative
+    assert_eq!(clock.advance(Duration::from_secs(0)), 0);
+    assert_eq!(clock.get_interpolation(), 0.0);
+
+    clock.advance(steps(1) / 2);
+    assert_eq!(clock.advance(Duration::from_secs(0)), 0);
+    assert_eq!(clock.get_interpolation(), 0.5);
+}
@@ -0,0 +1,126 @@
+// Timed matches: the clock only runs during rallies, the rally the time
+// runs out in is the last one unless the scores are even, then the next
+// point wins, whatever the scoring mode
+
+extern crate rustyvolley;
+
+use rustyvolley::duel_match::DuelMatch;
+use rustyvolley::game_logic::{ErrorReason, GameLogic};
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::{MatchRules, ScoringMode};
+use rustyvolley::physics_params::PhysicsParams;
+
+const TIME_LIMIT : i32 = 100;
+
+fn timed_rules(scoring_mode : ScoringMode) -> MatchRules {
+    MatchRules {
+        time_limit : Some(TIME_LIMIT),
+        scoring_mode,
+        ..MatchRules::default()
+    }
+}
+
+fn run_out_the_clock(game_logic : &mut GameLogic) {
+    for _ in 0..TIME_LIMIT {
+        game_logic.step_clock();
+    }
+    assert!(game_logic.is_time_up());
+}
+
+#[test]
+fn clock_stops_at_the_time_limit() {
+    let mut game_logic = GameLogic::new(timed_rules(ScoringMode::RallyScoring));
+    assert_eq!(game_logic.get_remaining_time(), Some(TIME_LIMIT));
+
+    run_out_the_clock(&mut game_logic);
+    game_logic.step_clock();
+
+    assert_eq!(game_logic.get_remaining_time(), Some(0));
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+}
+
+#[test]
+fn rally_scoring_leader_wins_when_the_rally_ends() {
+    let mut game_logic = GameLogic::new(timed_rules(ScoringMode::RallyScoring));
+    game_logic.on_error(RightPlayer, ErrorReason::BallHitGround, 0);
+    run_out_the_clock(&mut game_logic);
+
+    // the trailing player wins the last rally but stays behind
+    game_logic.on_error(LeftPlayer, ErrorReason::BallHitGround, 0);
+    assert_eq!(game_logic.get_scores(), (1, 1));
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+
+    game_logic.on_error(LeftPlayer, ErrorReason::TooManyTouches, 0);
+    assert_eq!(game_logic.get_scores(), (1, 2));
+    assert_eq!(game_logic.get_winning_player(), RightPlayer);
+}
+
+#[test]
+fn rally_scoring_leader_keeps_the_win_after_losing_the_last_rally() {
+    let mut game_logic = GameLogic::new(timed_rules(ScoringMode::RallyScoring));
+    game_logic.on_error(RightPlayer, ErrorReason::BallHitGround, 0);
+    game_logic.on_error(RightPlayer, ErrorReason::BallHitGround, 0);
+    run_out_the_clock(&mut game_logic);
+
+    game_logic.on_error(LeftPlayer, ErrorReason::BallHitGround, 0);
+
+    assert_eq!(game_logic.get_scores(), (2, 1));
+    assert_eq!(game_logic.get_winning_player(), LeftPlayer);
+}
+
+#[test]
+fn side_out_leader_wins_when_the_receiver_takes_the_serve() {
+    let mut game_logic = GameLogic::new(timed_rules(ScoringMode::SideOutScoring));
+    game_logic.on_error(RightPlayer, ErrorReason::BallHitGround, 0);
+    assert_eq!(game_logic.get_scores(), (1, 0));
+    run_out_the_clock(&mut game_logic);
+
+    // the receiver only wins the serve, the leader is still ahead
+    game_logic.on_error(LeftPlayer, ErrorReason::BallHitGround, 0);
+
+    assert_eq!(game_logic.get_scores(), (1, 0));
+    assert_eq!(game_logic.get_serving_player(), RightPlayer);
+    assert_eq!(game_logic.get_winning_player(), LeftPlayer);
+}
+
+#[test]
+fn side_out_golden_point_goes_on_until_a_point_is_scored() {
+    let mut game_logic = GameLogic::new(timed_rules(ScoringMode::SideOutScoring));
+    run_out_the_clock(&mut game_logic);
+
+    // side-outs leave the scores even
+    game_logic.on_error(LeftPlayer, ErrorReason::BallHitGround, 0);
+    game_logic.on_error(RightPlayer, ErrorReason::BallHitGround, 0);
+    assert_eq!(game_logic.get_scores(), (0, 0));
+    assert_eq!(game_logic.get_winning_player(), NoPlayer);
+
+    game_logic.on_error(RightPlayer, ErrorReason::BallHitGround, 0);
+
+    assert_eq!(game_logic.get_scores(), (1, 0));
+    assert_eq!(game_logic.get_winning_player(), LeftPlayer);
+}
+
+#[test]
+fn match_without_time_limit_never_runs_out() {
+    let mut game_logic = GameLogic::new(MatchRules::default());
+
+    for _ in 0..TIME_LIMIT * 10 {
+        game_logic.step_clock();
+    }
+
+    assert_eq!(game_logic.get_remaining_time(), None);
+    assert!(!game_logic.is_time_up());
+}
+
+#[test]
+fn clock_waits_for_the_serve() {
+    let mut duel_match =
+        DuelMatch::new(PhysicsParams::default(), timed_rules(ScoringMode::RallyScoring));
+
+    for _ in 0..TIME_LIMIT * 2 {
+        duel_match.step(&mut vec!());
+    }
+
+    assert!(!duel_match.get_world().is_game_running());
+    assert_eq!(duel_match.get_remaining_time(), Some(TIME_LIMIT));
+}
@@ -0,0 +1,137 @@
+// Tournament brackets: seeding with byes, single and double elimination, and
+// the instant simulation of the matches between bots
+
+extern crate rustyvolley;
+
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::match_rules::MatchRules;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::tournament::*;
+
+use rustyvolley::tournament::EliminationFormat::*;
+
+fn humans(count : usize) -> Vec<Participant> {
+    vec!(Participant::human(); count)
+}
+
+// Plays the tournament to its end, the winner of each match picked by the
+// given function from the two participants, returns the matches lost by
+// each participant
+fn play_all<F>(tournament : &mut Tournament, pick_winner : F) -> Vec<i32>
+    where F : Fn(usize, usize) -> usize
+{
+    let mut losses = vec!(0; tournament.get_participants().len());
+
+    while let Some(match_index) = tournament.get_next_match() {
+        let entrants = tournament.get_entrants(match_index);
+        let (first, second) = (entrants[0].unwrap(), entrants[1].unwrap());
+
+        if pick_winner(first, second) == first {
+            losses[second] += 1;
+            tournament.record_winner(match_index, LeftPlayer);
+        } else {
+            losses[first] += 1;
+            tournament.record_winner(match_index, RightPlayer);
+        }
+    }
+
+    losses
+}
+
+#[test]
+fn participant_count_is_checked() {
+    assert!(Tournament::new(humans(MIN_PARTICIPANT_COUNT - 1), SingleElimination).is_err());
+    assert!(Tournament::new(humans(MAX_PARTICIPANT_COUNT + 1), DoubleElimination).is_err());
+    assert!(Tournament::new(humans(MIN_PARTICIPANT_COUNT), DoubleElimination).is_ok());
+    assert!(Tournament::new(humans(MAX_PARTICIPANT_COUNT), SingleElimination).is_ok());
+}
+
+#[test]
+fn best_seeds_get_the_byes() {
+    let tournament = Tournament::new(humans(5), SingleElimination).unwrap();
+
+    // the first round of 8 has three byes, only seeds 4 and 5 play
+    let next_match = tournament.get_next_match().unwrap();
+    assert_eq!(tournament.get_entrants(next_match), [Some(3), Some(4)]);
+
+    let first_round_played =
+        tournament.get_matches().iter()
+        .filter(|bracket_match| bracket_match.round == 1 && bracket_match.result.is_none())
+        .count();
+    assert_eq!(first_round_played, 1);
+}
+
+#[test]
+fn single_elimination_crowns_the_unbeaten() {
+    for participant_count in MIN_PARTICIPANT_COUNT..=MAX_PARTICIPANT_COUNT {
+        let mut tournament = Tournament::new(humans(participant_count), SingleElimination).unwrap();
+
+        // the best seed always wins
+        let losses = play_all(&mut tournament, |first, second| first.min(second));
+
+        assert_eq!(tournament.get_champion(), Some(0));
+        assert_eq!(losses.iter().sum::<i32>(), participant_count as i32 - 1);
+        assert!(losses[1..].iter().all(|&loss_count| loss_count == 1));
+    }
+}
+
+#[test]
+fn double_elimination_needs_two_losses() {
+    for participant_count in MIN_PARTICIPANT_COUNT..=MAX_PARTICIPANT_COUNT {
+        // the worst seed always wins, the favourite of the winners bracket
+        // changes every time
+        let mut tournament = Tournament::new(humans(participant_count), DoubleElimination).unwrap();
+        let losses = play_all(&mut tournament, |first, second| first.max(second));
+
+        let champion = tournament.get_champion().unwrap();
+        assert!(losses[champion] <= 1);
+
+        for (participant, &loss_count) in losses.iter().enumerate() {
+            if participant != champion {
+                assert_eq!(loss_count, 2, "{} participants, participant {}", participant_count, participant);
+            }
+        }
+    }
+}
+
+#[test]
+fn grand_final_is_replayed_after_an_upset() {
+    // the best seed wins everything but the first grand final
+    let mut tournament = Tournament::new(humans(4), DoubleElimination).unwrap();
+    let mut grand_final_played = false;
+
+    while let Some(match_index) = tournament.get_next_match() {
+        let part = tournament.get_matches()[match_index].part;
+        let entrants = tournament.get_entrants(match_index);
+
+        let winner =
+            if part == BracketPart::GrandFinal {
+                grand_final_played = true;
+                if entrants[0] == Some(0) { RightPlayer } else { LeftPlayer }
+            } else if entrants[1] == Some(0) {
+                RightPlayer
+            } else {
+                LeftPlayer
+            };
+
+        tournament.record_winner(match_index, winner);
+    }
+
+    assert!(grand_final_played);
+
+    let reset = tournament.get_matches().last().unwrap();
+    assert_eq!(reset.part, BracketPart::GrandFinalReset);
+    assert!(reset.result.unwrap().played);
+    assert_eq!(tournament.get_champion(), Some(0));
+}
+
+#[test]
+fn simulated_match_has_a_winner() {
+    let rules = MatchRules {
+        score_to_win : 2,
+        ..MatchRules::default()
+    };
+
+    let winner = simulate_match(PhysicsParams::default(), rules, [0, 3], 7);
+    assert!(winner == LeftPlayer || winner == RightPlayer);
+}
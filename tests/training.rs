@@ -0,0 +1,171 @@
+// In training a launcher serves balls to the left player, whose returns
+// score the points of the target zone they land in
+
+extern crate rustyvolley;
+
+use rustyvolley::arena::Arena;
+use rustyvolley::ball::Ball;
+use rustyvolley::game_constants::*;
+use rustyvolley::global::PlayerSide::*;
+use rustyvolley::physic_world::PhysicWorld;
+use rustyvolley::physics_params::PhysicsParams;
+use rustyvolley::player_input::PlayerInput;
+use rustyvolley::training::*;
+use rustyvolley::vector::Vector2f;
+
+const IDLE : PlayerInput = PlayerInput { left : false, right : false, up : false };
+// the blob waits against the left wall, out of the way of the serves
+const AWAY : PlayerInput = PlayerInput { left : true, right : false, up : false };
+
+fn arena() -> Arena {
+    PhysicsParams::default().arena
+}
+
+// a ball dropped on the blob, which sends it over the net
+fn dropped_at(x : f32) -> LaunchPattern {
+    LaunchPattern {
+        position : Vector2f::new(x, 200.0f32),
+        min_velocity : Vector2f::new(0.0f32, 0.0f32),
+        max_velocity : Vector2f::new(0.0f32, 0.0f32),
+    }
+}
+
+// steps until the ball lands, with the events of those steps
+fn play_serve(session : &mut TrainingSession, input : PlayerInput) -> Vec<TrainingEvent> {
+    let mut events = vec!();
+
+    for _ in 0..3000 {
+        session.step(input, &mut events);
+
+        // the ball goes out of play as it lands
+        let landed = events.contains(&TrainingEvent::Launch) && !session.get_world().is_ball_valid();
+        if landed {
+            break;
+        }
+    }
+
+    events
+}
+
+#[test]
+fn presets_serve_into_the_left_half() {
+    let mut world = PhysicWorld::new(PhysicsParams::default());
+    world.set_game_running(true);
+
+    for &(name, pattern) in launch_presets(&arena()).iter() {
+        for &mix_x in [0.0f32, 0.5f32, 1.0f32].iter() {
+            for &mix_y in [0.0f32, 0.5f32, 1.0f32].iter() {
+                let ball = Ball {
+                    position : pattern.position,
+                    velocity : pattern.get_velocity(Vector2f::new(mix_x, mix_y)),
+                    ..Ball::new()
+                };
+                let prediction = world.predict_ball_from(ball, 2000);
+
+                assert_eq!(prediction.ground_contact.unwrap().1, LeftPlayer, "{}", name);
+                assert_eq!(prediction.net_contact, None, "{}", name);
+            }
+        }
+    }
+}
+
+#[test]
+fn launcher_serves_after_a_delay() {
+    let (_, pattern) = launch_presets(&arena())[1];
+    let mut session = TrainingSession::new(PhysicsParams::default(), pattern, default_target_zones(&arena()), 7);
+    let mut events = vec!();
+
+    for _ in 0..TRAINING_LAUNCH_DELAY - 1 {
+        session.step(IDLE, &mut events);
+    }
+    assert!(events.is_empty());
+    assert!(!session.get_world().is_ball_valid());
+
+    session.step(IDLE, &mut events);
+    assert_eq!(events, vec!(TrainingEvent::Launch));
+    assert_eq!(session.get_launch_count(), 1);
+
+    let world = session.get_world();
+    let velocity = world.get_ball_velocity();
+    assert!(world.is_ball_valid());
+    assert_eq!(world.get_ball_position(), pattern.position);
+    assert!(velocity.x >= pattern.min_velocity.x && velocity.x <= pattern.max_velocity.x);
+    assert!(velocity.y >= pattern.min_velocity.y && velocity.y <= pattern.max_velocity.y);
+}
+
+#[test]
+fn untouched_serve_is_a_miss() {
+    for &(name, pattern) in launch_presets(&arena()).iter() {
+        let mut session = TrainingSession::new(PhysicsParams::default(), pattern, default_target_zones(&arena()), 3);
+
+        let events = play_serve(&mut session, AWAY);
+
+        assert_eq!(events, vec!(TrainingEvent::Launch, TrainingEvent::Miss), "{}", name);
+        assert_eq!(session.get_score(), 0);
+        assert_eq!(session.get_return_count(), 0);
+
+        // and the launcher serves again
+        let events = play_serve(&mut session, AWAY);
+        assert_eq!(events[0], TrainingEvent::Launch);
+        assert_eq!(session.get_launch_count(), 2);
+    }
+}
+
+#[test]
+fn returns_score_the_zone_they_land_in() {
+    // bounced off the head of the blob into the short zone
+    let mut session = TrainingSession::new(PhysicsParams::default(), dropped_at(215.0f32), default_target_zones(&arena()), 1);
+    let events = play_serve(&mut session, IDLE);
+    assert!(events.contains(&TrainingEvent::Touch));
+    assert_eq!(*events.last().unwrap(), TrainingEvent::Return(Some(0)));
+    assert_eq!(session.get_score(), 3);
+
+    // into the deep zone
+    let mut session = TrainingSession::new(PhysicsParams::default(), dropped_at(227.0f32), default_target_zones(&arena()), 1);
+    assert_eq!(*play_serve(&mut session, IDLE).last().unwrap(), TrainingEvent::Return(Some(1)));
+    assert_eq!(session.get_score(), 2);
+
+    // between the zones, a return worth nothing
+    let mut session = TrainingSession::new(PhysicsParams::default(), dropped_at(221.0f32), default_target_zones(&arena()), 1);
+    assert_eq!(*play_serve(&mut session, IDLE).last().unwrap(), TrainingEvent::Return(None));
+    assert_eq!(session.get_score(), 0);
+    assert_eq!(session.get_return_count(), 1);
+}
+
+#[test]
+fn new_pattern_applies_to_the_next_serve() {
+    let (_, lob) = launch_presets(&arena())[0];
+    let (_, smash) = launch_presets(&arena())[2];
+    let mut session = TrainingSession::new(PhysicsParams::default(), lob, default_target_zones(&arena()), 5);
+
+    play_serve(&mut session, AWAY);
+    session.set_pattern(smash);
+    assert_eq!(session.get_pattern(), smash);
+
+    let mut events = vec!();
+    while !events.contains(&TrainingEvent::Launch) {
+        session.step(AWAY, &mut events);
+    }
+    assert_eq!(session.get_world().get_ball_position(), smash.position);
+}
+
+#[test]
+fn zones_and_launcher_follow_the_arena() {
+    let wide_arena = Arena {
+        right_wall : 1000.0f32,
+        net_position_x : 500.0f32,
+        ground_height : 550.0f32,
+        ..arena()
+    };
+
+    let zones = default_target_zones(&wide_arena);
+    assert_eq!((zones[0].left, zones[0].right), (540.0f32, 620.0f32));
+    assert_eq!((zones[1].left, zones[1].right), (880.0f32, 1000.0f32));
+
+    // the classic presets, moved with the net and the ground
+    let classic_presets = launch_presets(&arena());
+    for (&(name, pattern), &(_, classic)) in launch_presets(&wide_arena).iter().zip(classic_presets.iter()) {
+        assert_eq!(pattern.position, classic.position + Vector2f::new(100.0f32, 50.0f32), "{}", name);
+        assert_eq!(pattern.min_velocity, classic.min_velocity, "{}", name);
+    }
+}